@@ -0,0 +1,77 @@
+use super::events_from_chunks;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use optic_engine::{Changelog, RfcEvent, SpecChunkEvent, SpecEvent, ROOT_BATCH_ID};
+use std::process;
+use tokio::io::{stdout, AsyncWriteExt};
+
+pub const SUBCOMMAND_NAME: &'static str = "changelog";
+
+pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
+  SubCommand::with_name(SUBCOMMAND_NAME)
+    .about("Describes the endpoints, responses and fields changed between two batch commits")
+    .arg(
+      Arg::with_name("from")
+        .long("from")
+        .takes_value(true)
+        .value_name("BATCH_ID")
+        .required(true)
+        .help("The batch commit to compare from, or 'root' for the spec before any commit"),
+    )
+    .arg(
+      Arg::with_name("to")
+        .long("to")
+        .takes_value(true)
+        .value_name("BATCH_ID")
+        .help("The batch commit to compare to, defaulting to the last one"),
+    )
+    .arg(
+      Arg::with_name("format")
+        .long("format")
+        .takes_value(true)
+        .possible_values(&["json", "markdown"])
+        .default_value("json")
+        .help("The format of the changelog"),
+    )
+}
+
+pub async fn main<'a>(command_matches: &'a ArgMatches<'a>, spec_chunks: Vec<SpecChunkEvent>) {
+  let spec_events = events_from_chunks(spec_chunks).await;
+  let from_batch_id = command_matches.value_of("from").expect("from is required");
+  let to_batch_id = command_matches
+    .value_of("to")
+    .map(String::from)
+    .unwrap_or_else(|| last_batch_id(&spec_events));
+
+  let changelog = Changelog::between_batches(&spec_events, from_batch_id, &to_batch_id)
+    .unwrap_or_else(|err| {
+      eprintln!("Could not describe changes: {}", err);
+      process::exit(1);
+    });
+
+  let output = match command_matches.value_of("format") {
+    Some("markdown") => changelog.to_markdown(),
+    _ => serde_json::to_string_pretty(&changelog).expect("changelog should serialize to json"),
+  };
+
+  let mut stdout = stdout();
+  stdout
+    .write_all(output.as_bytes())
+    .await
+    .unwrap_or_else(|err| panic!("could not write changelog to stdout: {}", err));
+  stdout
+    .flush()
+    .await
+    .unwrap_or_else(|err| panic!("could not write changelog to stdout: {}", err));
+}
+
+/// The id of the last batch committed to the spec, or `root` when none has been
+pub fn last_batch_id(spec_events: &[SpecEvent]) -> String {
+  spec_events
+    .iter()
+    .rev()
+    .find_map(|event| match event {
+      SpecEvent::RfcEvent(RfcEvent::BatchCommitEnded(e)) => Some(e.batch_id.clone()),
+      _ => None,
+    })
+    .unwrap_or_else(|| String::from(ROOT_BATCH_ID))
+}
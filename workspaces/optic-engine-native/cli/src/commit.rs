@@ -1,10 +1,11 @@
+use super::changelog::last_batch_id;
 use super::events_from_chunks;
 use chrono::Utc;
 use clap::{App, Arg, ArgMatches, SubCommand};
 use futures::StreamExt;
 use optic_engine::append_batch_to_spec;
 use optic_engine::streams;
-use optic_engine::CommandContext;
+use optic_engine::{Changelog, CommandContext, RfcCommand};
 use optic_engine::{SpecChunkEvent, SpecEvent};
 use optic_engine::{SpecCommand, SpecProjection};
 use std::path::Path;
@@ -13,6 +14,7 @@ use tokio::io::{stdin, stdout};
 use uuid::Uuid;
 
 pub const SUBCOMMAND_NAME: &'static str = "commit";
+const BREAKING_CHANGE_JUSTIFICATION_KEY: &'static str = "breakingChangeJustification";
 
 pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
   SubCommand::with_name(SUBCOMMAND_NAME)
@@ -50,6 +52,7 @@ pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
         .default_value("anonymous")
         .help("Unique id of the client the commands to be committed"),
     )
+    .arg(create_allow_breaking_arg())
 }
 
 pub async fn main<'a>(
@@ -80,6 +83,8 @@ pub async fn main<'a>(
     .value_of("client-id")
     .expect("client-id is required");
 
+  let breaking_change_justification = command_matches.value_of("allow-breaking");
+
   commit(
     events_from_chunks(spec_chunks).await,
     &spec_path,
@@ -87,10 +92,74 @@ pub async fn main<'a>(
     append_to_root,
     client_id,
     client_session_id,
+    breaking_change_justification,
   )
   .await;
 }
 
+pub fn create_allow_breaking_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name("allow-breaking")
+    .long("allow-breaking")
+    .required(false)
+    .value_name("JUSTIFICATION")
+    .takes_value(true)
+    .help(
+      "Commit changes that break existing clients, recording why as a contribution to the batch",
+    )
+}
+
+/// The contribution recording why a batch breaks existing clients of the spec, exiting when no
+/// justification was given for it
+pub fn justify_breaking_changes(
+  spec_events: &[SpecEvent],
+  spec_projection: &SpecProjection,
+  batch_spec_projection: &SpecProjection,
+  batch_id: &str,
+  justification: Option<&str>,
+) -> Option<SpecCommand> {
+  breaking_change_contribution(
+    spec_events,
+    spec_projection,
+    batch_spec_projection,
+    batch_id,
+    justification,
+  )
+  .unwrap_or_else(|changelog| {
+      eprintln!("{}", changelog.to_markdown());
+      eprintln!(
+        "Refusing to commit changes that break existing clients. Pass --allow-breaking with a justification to commit them anyway."
+      );
+      process::exit(1);
+    })
+}
+
+fn breaking_change_contribution(
+  spec_events: &[SpecEvent],
+  spec_projection: &SpecProjection,
+  batch_spec_projection: &SpecProjection,
+  batch_id: &str,
+  justification: Option<&str>,
+) -> Result<Option<SpecCommand>, Changelog> {
+  let changelog = Changelog::between_specs(
+    spec_projection,
+    batch_spec_projection,
+    &last_batch_id(spec_events),
+    batch_id,
+  );
+  if !changelog.has_breaking_changes() {
+    return Ok(None);
+  }
+
+  match justification {
+    Some(justification) => Ok(Some(SpecCommand::from(RfcCommand::add_contribution(
+      String::from(batch_id),
+      String::from(BREAKING_CHANGE_JUSTIFICATION_KEY),
+      String::from(justification),
+    )))),
+    None => Err(changelog),
+  }
+}
+
 async fn commit(
   spec_events: Vec<SpecEvent>,
   spec_dir_path: impl AsRef<Path>,
@@ -98,6 +167,7 @@ async fn commit(
   append_to_root: bool,
   client_id: &str,
   client_session_id: &str,
+  breaking_change_justification: Option<&str>,
 ) {
   let stdin = stdin(); // TODO: deal with std in never having been attached
   let mut input_commands = streams::spec_events::from_json_lines(stdin);
@@ -110,8 +180,9 @@ async fn commit(
     Utc::now(),
   );
 
+  let spec_projection = SpecProjection::from(spec_events.clone());
   let mut batch = append_batch_to_spec(
-    SpecProjection::from(spec_events.clone()),
+    spec_projection.clone(),
     String::from(commit_message),
     batch_command_context,
   );
@@ -127,6 +198,18 @@ async fn commit(
       .expect("command could not be applied");
   }
 
+  if let Some(contribution) = justify_breaking_changes(
+    &spec_events,
+    &spec_projection,
+    batch.spec_projection(),
+    &batch_id,
+    breaking_change_justification,
+  ) {
+    batch
+      .with_command(contribution)
+      .expect("breaking change justification could not be added to the batch");
+  }
+
   let mut new_events = batch.commit();
 
   let spec_chunk_event = if append_to_root {
@@ -148,3 +231,99 @@ async fn commit(
     .await
     .unwrap_or_else(|err| panic!("could not write new events to stdout: {}", err))
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use optic_engine::{Aggregate, EndpointCommand, RfcEvent};
+  use serde_json::json;
+
+  fn spec_events() -> Vec<SpecEvent> {
+    serde_json::from_value(json!([
+      {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"todos"}},
+      {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"GET"}},
+      {"ResponseAddedByPathAndMethod":{"responseId":"response_1","pathId":"path_1","httpMethod":"GET","httpStatusCode":200}},
+    ]))
+    .expect("should be able to deserialize test events")
+  }
+
+  fn breaking_batch(spec_events: &[SpecEvent]) -> SpecProjection {
+    let mut batch = append_batch_to_spec(
+      SpecProjection::from(spec_events.to_vec()),
+      String::from("remove the todos response"),
+      CommandContext::new(
+        String::from("batch_2"),
+        String::from("test-client"),
+        String::from("test-session"),
+        Utc::now(),
+      ),
+    );
+    batch
+      .with_command(SpecCommand::from(EndpointCommand::remove_response(
+        String::from("response_1"),
+      )))
+      .expect("removing the response should be valid");
+    batch.spec_projection().clone()
+  }
+
+  #[test]
+  fn refuses_breaking_changes_without_justification() {
+    let spec_events = spec_events();
+    let spec_projection = SpecProjection::from(spec_events.clone());
+    let batch_spec_projection = breaking_batch(&spec_events);
+
+    let result = breaking_change_contribution(
+      &spec_events,
+      &spec_projection,
+      &batch_spec_projection,
+      "batch_2",
+      None,
+    );
+    let changelog = result.expect_err("breaking changes should be refused");
+    assert!(changelog.has_breaking_changes());
+  }
+
+  #[test]
+  fn accepts_non_breaking_changes_without_justification() {
+    let spec_events = spec_events();
+    let spec_projection = SpecProjection::from(spec_events.clone());
+
+    let result = breaking_change_contribution(
+      &spec_events,
+      &spec_projection,
+      &spec_projection,
+      "batch_2",
+      None,
+    );
+    assert!(matches!(result, Ok(None)));
+  }
+
+  #[test]
+  fn records_justification_of_breaking_changes_as_contribution() {
+    let spec_events = spec_events();
+    let spec_projection = SpecProjection::from(spec_events.clone());
+    let batch_spec_projection = breaking_batch(&spec_events);
+
+    let contribution = breaking_change_contribution(
+      &spec_events,
+      &spec_projection,
+      &batch_spec_projection,
+      "batch_2",
+      Some("nobody reads todos anymore"),
+    )
+    .expect("justified breaking changes should be accepted")
+    .expect("justification should be recorded");
+
+    let contributed_spec_projection = batch_spec_projection;
+    let events = contributed_spec_projection
+      .execute(contribution)
+      .expect("contribution should be valid");
+    assert!(events.iter().any(|event| matches!(
+      event,
+      SpecEvent::RfcEvent(RfcEvent::ContributionAdded(e))
+        if e.id == "batch_2"
+          && e.key == BREAKING_CHANGE_JUSTIFICATION_KEY
+          && e.value == "nobody reads todos anymore"
+    )));
+  }
+}
@@ -0,0 +1,97 @@
+use super::events_from_chunks;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use optic_engine::compact_spec_events;
+use optic_engine::streams;
+use optic_engine::{SpecChunkEvent, SpecEvent};
+use std::path::Path;
+use std::process;
+use tokio::fs;
+use tokio::io::stdout;
+
+pub const SUBCOMMAND_NAME: &'static str = "compact";
+
+pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
+  SubCommand::with_name(SUBCOMMAND_NAME)
+    .about("Compacts the spec into a minimal root spec file, squashing its batch commits")
+    .arg(
+      Arg::with_name("keep-batch")
+        .long("keep-batch")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .value_name("BATCH_ID")
+        .help("Keep the spec as it was right after this batch commit in the history"),
+    )
+    .arg(
+      Arg::with_name("in-place")
+        .long("in-place")
+        .help("Replace the spec files with the compacted spec, rather than printing it"),
+    )
+}
+
+pub async fn main<'a>(
+  command_matches: &'a ArgMatches<'a>,
+  spec_chunks: Vec<SpecChunkEvent>,
+  spec_path: impl AsRef<Path>,
+) {
+  let kept_batch_ids: Vec<&str> = command_matches
+    .values_of("keep-batch")
+    .map(|values| values.collect())
+    .unwrap_or_default();
+
+  let batch_chunk_names: Vec<String> = spec_chunks
+    .iter()
+    .filter_map(|chunk| match chunk {
+      SpecChunkEvent::Batch(batch_chunk) => Some(batch_chunk.name.clone()),
+      _ => None,
+    })
+    .collect();
+
+  let spec_events = events_from_chunks(spec_chunks).await;
+  let compacted_events = compact_spec_events(&spec_events, &kept_batch_ids).unwrap_or_else(|err| {
+    eprintln!("Could not compact spec: {}", err);
+    process::exit(1);
+  });
+  eprintln!(
+    "compacted {} spec events into {}",
+    spec_events.len(),
+    compacted_events.len()
+  );
+
+  if !command_matches.is_present("in-place") {
+    streams::spec_events::write_to_json_array(stdout(), &compacted_events)
+      .await
+      .unwrap_or_else(|err| panic!("could not write compacted events to stdout: {}", err));
+    return;
+  }
+
+  // the root spec file is only replaced once the compacted spec has been written completely
+  let root_path = spec_path.as_ref().join("specification.json");
+  let compacting_path = spec_path.as_ref().join("specification.json.compacting");
+  if let Err(err) = write_events(&compacting_path, &compacted_events).await {
+    eprintln!("Could not write compacted spec: {}", err);
+    let _ = fs::remove_file(&compacting_path).await;
+    process::exit(1);
+  }
+  if let Err(err) = fs::rename(&compacting_path, &root_path).await {
+    eprintln!("Could not replace root spec file: {}", err);
+    process::exit(1);
+  }
+
+  // all events of the batch chunks are part of the root spec file now
+  for chunk_name in batch_chunk_names {
+    if let Err(err) = fs::remove_file(spec_path.as_ref().join(&chunk_name)).await {
+      eprintln!("Could not remove spec chunk {}: {}", chunk_name, err);
+      process::exit(1);
+    }
+  }
+}
+
+async fn write_events(path: impl AsRef<Path>, spec_events: &[SpecEvent]) -> Result<(), String> {
+  let file = fs::File::create(path)
+    .await
+    .map_err(|err| err.to_string())?;
+  streams::spec_events::write_to_json_array(file, spec_events)
+    .await
+    .map_err(|err| format!("{:?}", err))
+}
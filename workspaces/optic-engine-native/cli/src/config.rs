@@ -0,0 +1,47 @@
+use clap::{Arg, ArgMatches};
+use optic_engine::IgnoreRules;
+use serde::Deserialize;
+use std::process;
+use tokio::fs;
+
+pub const CONFIG_ARG_NAME: &'static str = "config";
+
+pub fn create_config_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name(CONFIG_ARG_NAME)
+    .long(CONFIG_ARG_NAME)
+    .takes_value(true)
+    .value_name("CONFIG_PATH")
+    .help("Path to a YAML or JSON file with rules for interactions and body trails to ignore")
+}
+
+/// Config shared by the commands that diff or learn from interactions, like:
+///
+/// ```yaml
+/// ignore:
+///   paths: ["/health", "/metrics/**"]
+///   statusCodes: ["5xx"]
+///   bodyTrails: ["$.debug"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InteractionsConfig {
+  pub ignore: IgnoreRules,
+}
+
+/// The config at the path passed to the command, or the default config when none was passed
+pub async fn from_matches(command_matches: Option<&ArgMatches<'_>>) -> InteractionsConfig {
+  let config_path = match command_matches.and_then(|matches| matches.value_of(CONFIG_ARG_NAME)) {
+    Some(config_path) => config_path,
+    None => return InteractionsConfig::default(),
+  };
+
+  let config_source = fs::read_to_string(config_path).await.unwrap_or_else(|err| {
+    eprintln!("Could not read config file: {}", err);
+    process::exit(1);
+  });
+  // YAML is a superset of JSON, so either can be parsed as YAML
+  serde_yaml::from_str(&config_source).unwrap_or_else(|err| {
+    eprintln!("Config file could not be parsed: {}", err);
+    process::exit(1);
+  })
+}
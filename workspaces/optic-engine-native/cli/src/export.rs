@@ -0,0 +1,104 @@
+use super::events_from_chunks;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use optic_engine::{export_json_schema, export_openapi, OpenApiExportConfig};
+use optic_engine::{SpecChunkEvent, SpecProjection};
+use std::process;
+use tokio::io::{stdout, AsyncWriteExt};
+
+pub const SUBCOMMAND_NAME: &'static str = "export";
+const OPENAPI_SUBCOMMAND_NAME: &'static str = "openapi";
+const JSON_SCHEMA_SUBCOMMAND_NAME: &'static str = "json-schema";
+
+pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
+  SubCommand::with_name(SUBCOMMAND_NAME)
+    .about("Exports the spec as a document in another format to stdout")
+    .setting(AppSettings::SubcommandRequiredElseHelp)
+    .subcommand(
+      SubCommand::with_name(OPENAPI_SUBCOMMAND_NAME)
+        .about("Exports the spec as an OpenAPI 3 document")
+        .arg(
+          Arg::with_name("format")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["yaml", "json"])
+            .default_value("yaml")
+            .help("The serialization format of the exported document"),
+        )
+        .arg(
+          Arg::with_name("title")
+            .long("title")
+            .takes_value(true)
+            .help("The title of the API described by the exported document"),
+        )
+        .arg(
+          Arg::with_name("api-version")
+            .long("api-version")
+            .takes_value(true)
+            .help("The version of the API described by the exported document"),
+        ),
+    )
+    .subcommand(
+      SubCommand::with_name(JSON_SCHEMA_SUBCOMMAND_NAME)
+        .about("Exports a single shape as a JSON Schema (Draft 2020-12) document")
+        .arg(
+          Arg::with_name("shape-id")
+            .required(true)
+            .value_name("SHAPE_ID")
+            .help("The id of the shape to describe"),
+        ),
+    )
+}
+
+pub async fn main<'a>(command_matches: &'a ArgMatches<'a>, spec_chunks: Vec<SpecChunkEvent>) {
+  let spec_projection = SpecProjection::from(events_from_chunks(spec_chunks).await);
+
+  match command_matches.subcommand() {
+    (OPENAPI_SUBCOMMAND_NAME, Some(openapi_matches)) => {
+      let mut export_config = OpenApiExportConfig::default();
+      if let Some(title) = openapi_matches.value_of("title") {
+        export_config.title = String::from(title);
+      }
+      if let Some(api_version) = openapi_matches.value_of("api-version") {
+        export_config.version = String::from(api_version);
+      }
+
+      let document = export_openapi(&spec_projection, &export_config);
+      let output = match openapi_matches.value_of("format") {
+        Some("json") => serde_json::to_string_pretty(&document)
+          .expect("exported OpenAPI document should serialize to json"),
+        _ => serde_yaml::to_string(&document)
+          .expect("exported OpenAPI document should serialize to yaml"),
+      };
+
+      write_document(output).await;
+    }
+    (JSON_SCHEMA_SUBCOMMAND_NAME, Some(json_schema_matches)) => {
+      let shape_id = json_schema_matches
+        .value_of("shape-id")
+        .expect("shape-id is required");
+
+      let document = export_json_schema(spec_projection.shape(), &String::from(shape_id))
+        .unwrap_or_else(|| {
+          eprintln!("Could not find shape with id '{}' in the spec", shape_id);
+          process::exit(1);
+        });
+      let output = serde_json::to_string_pretty(&document)
+        .expect("exported JSON Schema document should serialize to json");
+
+      write_document(output).await;
+    }
+    _ => unreachable!("a subcommand is required for export"),
+  }
+}
+
+async fn write_document(output: String) {
+  let mut stdout = stdout();
+  stdout
+    .write_all(output.as_bytes())
+    .await
+    .unwrap_or_else(|err| panic!("could not write exported document to stdout: {}", err));
+  stdout
+    .flush()
+    .await
+    .unwrap_or_else(|err| panic!("could not write exported document to stdout: {}", err));
+}
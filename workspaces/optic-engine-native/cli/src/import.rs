@@ -0,0 +1,136 @@
+use super::events_from_chunks;
+use super::learn::IdGenerator;
+use chrono::Utc;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use optic_engine::streams;
+use optic_engine::{append_batch_to_spec, import_openapi};
+use optic_engine::{CommandContext, SpecChunkEvent, SpecProjection};
+use serde_json::Value as JsonValue;
+use std::path::Path;
+use std::process;
+use tokio::fs;
+use tokio::io::stdout;
+use uuid::Uuid;
+
+pub const SUBCOMMAND_NAME: &'static str = "import";
+const OPENAPI_SUBCOMMAND_NAME: &'static str = "openapi";
+
+pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
+  SubCommand::with_name(SUBCOMMAND_NAME)
+    .about("Imports a document in another format into the spec as a new batch commit")
+    .setting(AppSettings::SubcommandRequiredElseHelp)
+    .subcommand(
+      SubCommand::with_name(OPENAPI_SUBCOMMAND_NAME)
+        .about("Imports the operations of an OpenAPI 3 document, in YAML or JSON")
+        .arg(
+          Arg::with_name("document")
+            .required(true)
+            .value_name("DOCUMENT_PATH")
+            .help("The path to the OpenAPI document to import"),
+        )
+        .arg(
+          Arg::with_name("commit-message")
+            .short("m")
+            .takes_value(true)
+            .value_name("COMMIT_MESSAGE")
+            .default_value("Imported from OpenAPI")
+            .help("The commit message describing the imported changes"),
+        )
+        .arg(
+          Arg::with_name("client-session-id")
+            .long("client-session-id")
+            .required(false)
+            .value_name("CLIENT_SESSION_ID")
+            .takes_value(true)
+            .default_value("unknown-session")
+            .help("The session id of the client requesting the import"),
+        )
+        .arg(
+          Arg::with_name("client-id")
+            .long("client-id")
+            .required(false)
+            .value_name("CLIENT_ID")
+            .takes_value(true)
+            .default_value("anonymous")
+            .help("Unique id of the client requesting the import"),
+        ),
+    )
+}
+
+pub async fn main<'a>(
+  command_matches: &'a ArgMatches<'a>,
+  spec_chunks: Vec<SpecChunkEvent>,
+  spec_path: impl AsRef<Path>,
+) {
+  match command_matches.subcommand() {
+    (OPENAPI_SUBCOMMAND_NAME, Some(openapi_matches)) => {
+      let document_path = openapi_matches
+        .value_of("document")
+        .expect("document is required");
+      let commit_message = openapi_matches
+        .value_of("commit-message")
+        .expect("commit-message has a default value");
+      let client_session_id = openapi_matches
+        .value_of("client-session-id")
+        .expect("client-session-id has a default value");
+      let client_id = openapi_matches
+        .value_of("client-id")
+        .expect("client-id has a default value");
+
+      let document_source = fs::read_to_string(document_path)
+        .await
+        .unwrap_or_else(|err| {
+          eprintln!("Could not read OpenAPI document: {}", err);
+          process::exit(1);
+        });
+      // YAML is a superset of JSON, so either can be parsed as YAML
+      let document: JsonValue = serde_yaml::from_str(&document_source).unwrap_or_else(|err| {
+        eprintln!("OpenAPI document could not be parsed: {}", err);
+        process::exit(1);
+      });
+
+      let spec_projection = SpecProjection::from(events_from_chunks(spec_chunks).await);
+      let commands = import_openapi(&spec_projection, &document, &mut IdGenerator::default())
+        .unwrap_or_else(|err| {
+          eprintln!("OpenAPI document could not be imported: {}", err);
+          process::exit(1);
+        });
+
+      let batch_id = Uuid::new_v4().to_hyphenated().to_string();
+      let batch_command_context = CommandContext::new(
+        batch_id.clone(),
+        String::from(client_id),
+        String::from(client_session_id),
+        Utc::now(),
+      );
+      let mut batch = append_batch_to_spec(
+        spec_projection,
+        String::from(commit_message),
+        batch_command_context,
+      );
+      for command in commands {
+        batch.with_command(command).unwrap_or_else(|err| {
+          eprintln!(
+            "Imported changes could not be applied to the spec: {:?}",
+            err
+          );
+          process::exit(1);
+        });
+      }
+
+      let spec_chunk_event = SpecChunkEvent::batch_from_events(batch_id, batch.commit())
+        .expect("valid batch chunk should have been created");
+
+      streams::spec_chunks::to_api_dir(std::iter::once(&spec_chunk_event), spec_path)
+        .await
+        .unwrap_or_else(|err| {
+          panic!("could not write new spec batch chunk to api dir: {:?}", err);
+        });
+
+      streams::spec_events::write_to_json_array(stdout(), spec_chunk_event.events())
+        .await
+        .unwrap_or_else(|err| panic!("could not write new events to stdout: {}", err))
+    }
+    _ => unreachable!("a subcommand is required for import"),
+  }
+}
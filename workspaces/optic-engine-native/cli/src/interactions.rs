@@ -0,0 +1,86 @@
+use clap::{Arg, ArgMatches};
+use futures::{stream, Stream, StreamExt};
+use optic_engine::streams;
+use optic_engine::HttpInteraction;
+use std::pin::Pin;
+use std::process;
+use tokio::io::stdin;
+
+pub const INPUT_FORMAT_ARG_NAME: &'static str = "input-format";
+
+#[cfg(not(feature = "avro"))]
+const INPUT_FORMATS: &[&str] = &["jsonl", "har"];
+#[cfg(feature = "avro")]
+const INPUT_FORMATS: &[&str] = &["jsonl", "har", "avro"];
+
+pub fn create_input_format_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name(INPUT_FORMAT_ARG_NAME)
+    .long(INPUT_FORMAT_ARG_NAME)
+    .takes_value(true)
+    .possible_values(INPUT_FORMATS)
+    .default_value("jsonl")
+    .help("The format of the interactions piped to stdin: Optic interactions as json lines, a HAR document or an Avro capture file")
+}
+
+/// How interactions read from other formats are written as lines, matching what the
+/// consumer of the lines expects to parse.
+#[derive(Debug, Clone, Copy)]
+pub enum InteractionLineFormat {
+  Interaction,
+  TaggedInteraction,
+}
+
+pub type InteractionLines = Pin<Box<dyn Stream<Item = Result<String, std::io::Error>> + Send>>;
+
+/// Interactions piped to stdin as json lines, in the input format chosen for the command
+pub fn from_stdin(
+  command_matches: Option<&ArgMatches>,
+  line_format: InteractionLineFormat,
+) -> InteractionLines {
+  let input_format = command_matches
+    .and_then(|matches| matches.value_of(INPUT_FORMAT_ARG_NAME))
+    .unwrap_or("jsonl");
+
+  match input_format {
+    "har" => {
+      let interactions = async {
+        streams::http_interaction::from_har(stdin())
+          .await
+          .unwrap_or_else(|err| {
+            eprintln!("Could not read HAR document from stdin: {}", err);
+            process::exit(1);
+          })
+      };
+
+      Box::pin(stream::once(interactions).flat_map(move |interactions| {
+        stream::iter(
+          interactions
+            .into_iter()
+            .map(move |interaction| Ok(interaction_line(interaction, line_format))),
+        )
+      }))
+    }
+    #[cfg(feature = "avro")]
+    "avro" => Box::pin(
+      streams::http_interaction::avro(std::io::stdin()).map(move |interaction| {
+        let interaction = interaction.unwrap_or_else(|err| {
+          eprintln!("Could not read Avro interactions from stdin: {}", err);
+          process::exit(1);
+        });
+        Ok(interaction_line(interaction, line_format))
+      }),
+    ),
+    // TODO: deal with std in never having been attached
+    _ => Box::pin(streams::http_interaction::json_lines(stdin())),
+  }
+}
+
+fn interaction_line(interaction: HttpInteraction, line_format: InteractionLineFormat) -> String {
+  let line = match line_format {
+    InteractionLineFormat::Interaction => serde_json::to_string(&interaction),
+    InteractionLineFormat::TaggedInteraction => {
+      serde_json::to_string(&(interaction, Vec::<String>::new()))
+    }
+  };
+  line.expect("interactions should serialize to json")
+}
@@ -1,20 +1,24 @@
+use super::config;
 use super::events_from_chunks;
+use super::interactions;
 
 use clap::{App, Arg, ArgGroup, ArgMatches, SubCommand};
 use futures::{try_join, SinkExt, Stream, StreamExt, TryStreamExt};
 use nanoid::nanoid;
 use serde_json;
+use std::process;
 use std::sync::Arc;
-use tokio::io::{stdin, stdout, AsyncWrite};
+use tokio::io::{stdout, AsyncWrite};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
 use optic_engine::streams;
 use optic_engine::Aggregate;
 use optic_engine::{
-  analyze_documented_bodies, analyze_undocumented_bodies, AnalyzeUndocumentedBodiesConfig,
+  analyze_documented_bodies, analyze_undocumented_bodies, diff_interaction,
+  AnalyzeDocumentedBodiesConfig, AnalyzeUndocumentedBodiesConfig, DiffInteractionConfig,
   InteractionDiffResult, LearnedShapeDiffAffordancesProjection,
-  LearnedUndocumentedBodiesProjection,
+  LearnedUndocumentedBodiesProjection, LearnedUndocumentedPathsProjection,
 };
 use optic_engine::{
   HttpInteraction, SpecChunkEvent, SpecEvent, SpecIdGenerator, SpecProjection, TaggedInput,
@@ -31,6 +35,12 @@ pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
         .takes_value(false)
         .help("Learn shapes of undocumented bodies from interactions piped to stdin"),
     )
+    .arg(
+      Arg::with_name("undocumented-endpoints")
+        .long("undocumented-endpoints")
+        .takes_value(false)
+        .help("Learn paths, path parameters and bodies of undocumented endpoints from interactions piped to stdin"),
+    )
     .arg(
       Arg::with_name("shape-diffs-affordances")
         .long("shape-diffs-affordances")
@@ -46,10 +56,16 @@ pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
     )
     .group(
       ArgGroup::with_name("subject")
-        .args(&["undocumented-bodies", "shape-diffs-affordances"])
+        .args(&[
+          "undocumented-bodies",
+          "undocumented-endpoints",
+          "shape-diffs-affordances",
+        ])
         .multiple(false)
         .required(true),
     )
+    .arg(interactions::create_input_format_arg())
+    .arg(config::create_config_arg())
 }
 
 pub async fn main<'a>(
@@ -60,11 +76,15 @@ pub async fn main<'a>(
   let spec_events = events_from_chunks(spec_chunks).await;
 
   if command_matches.is_present("undocumented-bodies") {
-    let stdin = stdin();
-    let interaction_lines = streams::http_interaction::json_lines(stdin);
+    let interaction_lines = interactions::from_stdin(
+      Some(command_matches),
+      interactions::InteractionLineFormat::Interaction,
+    );
     let sink = stdout();
 
-    let learner_config = AnalyzeUndocumentedBodiesConfig::default();
+    let learner_config = AnalyzeUndocumentedBodiesConfig {
+      ignore: config::from_matches(Some(command_matches)).await.ignore,
+    };
 
     learn_undocumented_bodies(
       spec_events,
@@ -74,13 +94,27 @@ pub async fn main<'a>(
       sink,
     )
     .await;
+  } else if command_matches.is_present("undocumented-endpoints") {
+    let interaction_lines = interactions::from_stdin(
+      Some(command_matches),
+      interactions::InteractionLineFormat::Interaction,
+    );
+    let sink = stdout();
+
+    let learner_config = AnalyzeUndocumentedBodiesConfig {
+      ignore: config::from_matches(Some(command_matches)).await.ignore,
+    };
+
+    learn_undocumented_endpoints(spec_events, interaction_lines, learner_config, sink).await;
   } else if command_matches.is_present("shape-diffs-affordances") {
     let diffs_path = command_matches
       .value_of("tagged-diff-results")
       .expect("tagged-diff-results is required for shape-diffs learning subject");
 
-    let stdin = stdin();
-    let interaction_lines = streams::http_interaction::json_lines(stdin);
+    let interaction_lines = interactions::from_stdin(
+      Some(command_matches),
+      interactions::InteractionLineFormat::TaggedInteraction,
+    );
     let diffs = streams::diff::tagged_from_json_line_file(diffs_path)
       .await
       .expect("could not read diffs")
@@ -89,11 +123,16 @@ pub async fn main<'a>(
 
     let sink = stdout();
 
+    let learner_config = AnalyzeDocumentedBodiesConfig {
+      ignore: config::from_matches(Some(command_matches)).await.ignore,
+    };
+
     learn_shape_diff_affordances(
       spec_events,
       diffs,
       input_queue_size,
       interaction_lines,
+      learner_config,
       sink,
     )
     .await;
@@ -178,14 +217,120 @@ async fn learn_undocumented_bodies<S: 'static + AsyncWrite + Unpin + Send>(
   try_join!(analyzing_bodies, aggregating_results).expect("essential worker task panicked");
 }
 
+async fn learn_undocumented_endpoints<S: AsyncWrite + Unpin>(
+  spec_events: Vec<SpecEvent>,
+  interaction_lines: impl Stream<Item = Result<String, std::io::Error>>,
+  learner_config: AnalyzeUndocumentedBodiesConfig,
+  sink: S,
+) {
+  let interactions = interaction_lines
+    .map(|interaction_json_result| {
+      let interaction_json =
+        interaction_json_result.expect("can read interaction json line from stdin");
+      serde_json::from_str::<HttpInteraction>(&interaction_json)
+        .expect("could not parse interaction json")
+    })
+    .collect::<Vec<_>>()
+    .await;
+
+  // paths have to be learned from all interactions before their bodies can be, as the bodies are
+  // analyzed against the spec with the learned paths
+  let learning = tokio::task::spawn_blocking(move || {
+    let spec_projection = SpecProjection::from(spec_events);
+    let diff_config = DiffInteractionConfig {
+      ignore: learner_config.ignore.clone(),
+    };
+
+    let mut learned_undocumented_paths = LearnedUndocumentedPathsProjection::default();
+    let mut undocumented_interactions = vec![];
+    for interaction in interactions {
+      let mut is_undocumented = false;
+      for result in diff_interaction(&spec_projection, interaction.clone(), &diff_config) {
+        match result {
+          InteractionDiffResult::UnmatchedRequestUrl(diff) => {
+            learned_undocumented_paths.apply(diff);
+            is_undocumented = true;
+          }
+          // the url is documented, just not unambiguously, so there's no path to learn nor an
+          // endpoint to learn its bodies for
+          InteractionDiffResult::AmbiguousRequestUrl(diff) => eprintln!(
+            "skipped interaction: {} {} matches several documented paths: {}",
+            interaction.request.method,
+            interaction.request.path,
+            diff.path_ids.join(", ")
+          ),
+          _ => {}
+        }
+      }
+      if is_undocumented {
+        undocumented_interactions.push(interaction);
+      }
+    }
+
+    let mut id_generator = IdGenerator::default();
+    let learned_paths =
+      learned_undocumented_paths.into_learned_paths(spec_projection.endpoint(), &mut id_generator);
+
+    let mut learned_spec_projection = spec_projection;
+    for command in learned_paths.commands.iter().cloned() {
+      let events = learned_spec_projection
+        .execute(command.clone())
+        .map_err(|err| format!("learned command {:?} is invalid: {:?}", command, err))?;
+      for event in events {
+        learned_spec_projection.apply(event);
+      }
+    }
+
+    let mut learned_undocumented_bodies = LearnedUndocumentedBodiesProjection::default();
+    for interaction in undocumented_interactions {
+      let analysis_results =
+        analyze_undocumented_bodies(&learned_spec_projection, interaction, &learner_config);
+      for analysis in analysis_results {
+        learned_undocumented_bodies.apply(analysis);
+      }
+    }
+
+    let body_commands = learned_undocumented_bodies
+      .into_endpoint_bodies(&mut id_generator)
+      .flat_map(|endpoint_bodies| endpoint_bodies.into_commands());
+
+    Ok((
+      learned_paths.path_patterns,
+      learned_paths
+        .commands
+        .into_iter()
+        .chain(body_commands)
+        .collect::<Vec<_>>(),
+    ))
+  });
+
+  let (path_patterns, commands) = learning
+    .await
+    .expect("essential worker task panicked")
+    .unwrap_or_else(|err: String| {
+      eprintln!("Could not learn undocumented endpoints: {}", err);
+      process::exit(1);
+    });
+
+  for path_pattern in path_patterns {
+    eprintln!("learned path: {}", path_pattern);
+  }
+
+  streams::write_to_json_lines(sink, commands.iter())
+    .await
+    .expect("could not write learned commands to stdout");
+}
+
 async fn learn_shape_diff_affordances<S: 'static + AsyncWrite + Unpin + Send>(
   spec_events: Vec<SpecEvent>,
   diffs: impl Iterator<Item = InteractionDiffResult>,
   input_queue_size: usize,
   interaction_lines: impl Stream<Item = Result<String, std::io::Error>>,
+  learner_config: AnalyzeDocumentedBodiesConfig,
   sink: S,
 ) {
   let spec_projection = Arc::new(SpecProjection::from(spec_events));
+  let learner_config = Arc::new(learner_config);
   let mut learned_shape_diff_affordances: LearnedShapeDiffAffordancesProjection = diffs.collect();
 
   let (analysis_sender, analysis_receiver) = mpsc::channel(32);
@@ -199,6 +344,7 @@ async fn learn_shape_diff_affordances<S: 'static + AsyncWrite + Unpin + Send>(
         .try_for_each_concurrent(input_queue_size, |interaction_json_result| {
           let analysis_sender = analysis_sender.clone();
           let spec_projection = spec_projection.clone();
+          let learner_config = learner_config.clone();
 
           let analyze_task = tokio::spawn(async move {
             let analyze_comp = tokio::task::spawn_blocking(move || {
@@ -209,7 +355,7 @@ async fn learn_shape_diff_affordances<S: 'static + AsyncWrite + Unpin + Send>(
                 serde_json::from_str(&interaction_json).expect("could not parse interaction json");
 
               (
-                analyze_documented_bodies(&spec_projection, interaction),
+                analyze_documented_bodies(&spec_projection, interaction, &learner_config),
                 interaction_tags,
               )
             });
@@ -261,7 +407,7 @@ async fn learn_shape_diff_affordances<S: 'static + AsyncWrite + Unpin + Send>(
 }
 
 #[derive(Debug, Default)]
-struct IdGenerator;
+pub struct IdGenerator;
 
 impl SpecIdGenerator for IdGenerator {
   fn generate_id(&mut self, prefix: &str) -> String {
@@ -276,8 +422,9 @@ impl SpecIdGenerator for IdGenerator {
 #[cfg(test)]
 mod test {
   use super::*;
+  use optic_engine::{EndpointCommand, SpecCommand};
   use path_absolutize::*;
-  use serde_json::json;
+  use serde_json::{json, Value as JsonValue};
   use std::path::Path;
   use tokio::fs;
 
@@ -298,6 +445,114 @@ mod test {
     learn_undocumented_bodies(spec_events, 1, interaction_lines, learner_config, sink).await;
   }
 
+  #[tokio::main]
+  #[test]
+  async fn can_learn_undocumented_endpoints_from_interactions() {
+    let spec_events: Vec<SpecEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded":{"pathId":"path_id_1","parentPathId":"root","name":"todos"}}
+    ]))
+    .expect("initial spec events should be valid events");
+
+    let interaction = |method: &str, path: &str, response_body: JsonValue| {
+      let empty = json!({ "asJsonString": null, "asText": null, "asShapeHashBytes": null });
+      json!({
+        "uuid": "1",
+        "request": {
+          "host": "localhost",
+          "method": method,
+          "path": path,
+          "query": empty,
+          "headers": empty,
+          "body": { "contentType": null, "value": empty }
+        },
+        "response": {
+          "statusCode": 200,
+          "headers": empty,
+          "body": {
+            "contentType": "application/json",
+            "value": {
+              "asJsonString": response_body.to_string(),
+              "asText": null,
+              "asShapeHashBytes": null
+            }
+          }
+        },
+        "tags": []
+      })
+    };
+    let interactions = vec![
+      interaction("GET", "/todos", json!([{ "title": "write tests" }])),
+      interaction("GET", "/users", json!([{ "name": "someone" }])),
+    ];
+    let interactions_jsonl = interactions
+      .iter()
+      .map(|interaction| interaction.to_string())
+      .collect::<Vec<_>>()
+      .join("\n");
+    let interaction_lines = streams::http_interaction::json_lines(interactions_jsonl.as_bytes());
+
+    let learner_config = AnalyzeUndocumentedBodiesConfig::default();
+
+    let mut output = vec![];
+    learn_undocumented_endpoints(
+      spec_events.clone(),
+      interaction_lines,
+      learner_config,
+      &mut output,
+    )
+    .await;
+
+    let commands = String::from_utf8(output)
+      .unwrap()
+      .lines()
+      .map(|line| serde_json::from_str::<SpecCommand>(line).expect("commands should be written"))
+      .collect::<Vec<_>>();
+    let learned_path_ids = commands
+      .iter()
+      .filter_map(|command| match command {
+        SpecCommand::EndpointCommand(EndpointCommand::AddPathComponent(add_path)) => {
+          Some((add_path.name.as_str(), add_path.path_id.as_str()))
+        }
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    let users_path_id = match learned_path_ids.as_slice() {
+      [("users", path_id)] => *path_id,
+      _ => panic!(
+        "only the users path should be learned: {:?}",
+        learned_path_ids
+      ),
+    };
+    assert!(commands.iter().any(|command| matches!(
+      command,
+      SpecCommand::EndpointCommand(EndpointCommand::AddResponseByPathAndMethod(add_response))
+        if add_response.path_id == users_path_id && add_response.http_status_code == 200
+    )));
+    assert!(commands.iter().any(|command| matches!(
+      command,
+      SpecCommand::EndpointCommand(EndpointCommand::SetResponseBodyShape(_))
+    )));
+
+    // the spec with the learned commands documents the interaction with the undocumented path
+    let mut spec_projection = SpecProjection::from(spec_events);
+    for command in commands {
+      let events = spec_projection
+        .execute(command)
+        .expect("learned commands should be valid for the spec");
+      for event in events {
+        spec_projection.apply(event);
+      }
+    }
+    let users_interaction: HttpInteraction =
+      serde_json::from_value(interactions[1].clone()).unwrap();
+    let results = diff_interaction(
+      &spec_projection,
+      users_interaction,
+      &DiffInteractionConfig::default(),
+    );
+    assert!(results.is_empty(), "unexpected diffs: {:?}", results);
+  }
+
   #[tokio::main]
   #[test]
   async fn can_learn_shape_diffs_affordances_from_interactions() {
@@ -329,6 +584,16 @@ mod test {
     let interaction_lines =
       streams::http_interaction::json_lines(fs::File::open(interactions_path).await.unwrap());
 
-    learn_shape_diff_affordances(spec_events, diffs, 1, interaction_lines, tokio::io::sink()).await;
+    let learner_config = AnalyzeDocumentedBodiesConfig::default();
+
+    learn_shape_diff_affordances(
+      spec_events,
+      diffs,
+      1,
+      interaction_lines,
+      learner_config,
+      tokio::io::sink(),
+    )
+    .await;
   }
 }
@@ -1,7 +1,7 @@
 use clap::{crate_version, App, Arg, ArgGroup, SubCommand};
 use futures::try_join;
 use futures::SinkExt;
-use futures::{StreamExt, TryStreamExt};
+use futures::{Stream, StreamExt, TryStreamExt};
 use num_cpus;
 use optic_engine::errors;
 use optic_engine::streams;
@@ -9,15 +9,23 @@ use optic_engine::HttpInteraction;
 use optic_engine::InteractionDiffResult;
 use optic_engine::SpecProjection;
 use optic_engine::{diff_interaction, DiffInteractionConfig};
-use optic_engine::{SpecChunkEvent, SpecEvent};
+use optic_engine::{SpecAssemblerProjection, SpecChunkEvent, SpecEvent};
 use std::cmp;
 use std::process;
 use std::sync::Arc;
-use tokio::io::{stdin, stdout};
+use tokio::io::stdout;
 use tokio::sync::mpsc;
 
+mod changelog;
 mod commit;
+mod compact;
+mod config;
+mod export;
+mod import;
+mod interactions;
 mod learn;
+mod merge_driver;
+mod revert;
 
 fn main() {
   let cli = App::new("Optic Engine CLI")
@@ -25,10 +33,10 @@ fn main() {
     .author("Optic Labs Corporation")
     .about("A command-line interface into the core Optic domain logic")
     .arg(
+      // required by every subcommand but merge-driver, which clap can't express
       Arg::with_name("specification")
-        .required(true)
         .value_name("SPEC_PATH")
-        .help("The path to the specification that describes the API spec")
+        .help("The path to the specification that describes the API spec (required)")
         .takes_value(true),
     )
     .arg(
@@ -61,22 +69,48 @@ fn main() {
     )
     .subcommand(
       SubCommand::with_name("assemble")
-        .about("Assembles a directory of API spec files into a single events stream"),
+        .about("Assembles a directory of API spec files into a single events stream")
+        .arg(create_at_batch_arg()),
     )
+    .subcommand(changelog::create_subcommand())
     .subcommand(commit::create_subcommand())
+    .subcommand(compact::create_subcommand())
+    .subcommand(export::create_subcommand())
+    .subcommand(import::create_subcommand())
     .subcommand(learn::create_subcommand())
+    .subcommand(merge_driver::create_subcommand())
+    .subcommand(revert::create_subcommand())
     .subcommand(
       SubCommand::with_name("diff")
-        .about("Detects differences between API spec and captured interactions (default)"),
+        .about("Detects differences between API spec and captured interactions (default)")
+        .arg(interactions::create_input_format_arg())
+        .arg(config::create_config_arg())
+        .arg(create_at_batch_arg()),
     );
 
   let matches = cli.get_matches();
 
-  let spec_path = matches
-    .value_of("specification")
-    .expect("SPEC_PATH should be required");
+  if let (merge_driver::SUBCOMMAND_NAME, Some(subcommand_matches)) = matches.subcommand() {
+    // merging versions of a spec file doesn't involve the spec at SPEC_PATH
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .enable_all()
+      .build()
+      .unwrap();
+    runtime.block_on(merge_driver::main(subcommand_matches));
+    return;
+  }
+
+  let spec_path = matches.value_of("specification").unwrap_or_else(|| {
+    clap::Error::with_description(
+      "The following required arguments were not provided:\n    <SPEC_PATH>",
+      clap::ErrorKind::MissingRequiredArgument,
+    )
+    .exit()
+  });
   let spec_path_type = match matches.subcommand_name() {
-    Some("assemble") | Some("commit") => SpecPathType::DIR,
+    Some("assemble") | Some("commit") | Some("compact") | Some("import") | Some("revert") => {
+      SpecPathType::DIR
+    }
     _ => {
       if matches.is_present("use-spec-dir") {
         SpecPathType::DIR
@@ -133,24 +167,53 @@ fn main() {
     };
 
     match matches.subcommand() {
-      ("assemble", Some(_)) => {
+      ("assemble", Some(assemble_matches)) => {
         // eprintln!("assembling spec folder into spec");
-        assemble(spec_chunks).await;
+        assemble(spec_chunks, assemble_matches.value_of("at-batch")).await;
+      }
+      (changelog::SUBCOMMAND_NAME, Some(subcommand_matches)) => {
+        changelog::main(subcommand_matches, spec_chunks).await
       }
       (commit::SUBCOMMAND_NAME, Some(subcommand_matches)) => {
         commit::main(subcommand_matches, spec_chunks, spec_path).await
       }
+      (compact::SUBCOMMAND_NAME, Some(subcommand_matches)) => {
+        compact::main(subcommand_matches, spec_chunks, spec_path).await
+      }
+      (export::SUBCOMMAND_NAME, Some(subcommand_matches)) => {
+        export::main(subcommand_matches, spec_chunks).await
+      }
+      (import::SUBCOMMAND_NAME, Some(subcommand_matches)) => {
+        import::main(subcommand_matches, spec_chunks, spec_path).await
+      }
       (learn::SUBCOMMAND_NAME, Some(subcommand_matches)) => {
         learn::main(subcommand_matches, spec_chunks, input_queue_size).await
       }
+      (revert::SUBCOMMAND_NAME, Some(subcommand_matches)) => {
+        revert::main(subcommand_matches, spec_chunks, spec_path).await
+      }
       _ => {
         eprintln!("diffing interations against a spec");
         eprintln!("using input queue size {}", input_queue_size);
 
-        let diff_config = DiffInteractionConfig::default();
+        let diff_config = DiffInteractionConfig {
+          ignore: config::from_matches(matches.subcommand_matches("diff"))
+            .await
+            .ignore,
+        };
+
+        let interaction_lines = interactions::from_stdin(
+          matches.subcommand_matches("diff"),
+          interactions::InteractionLineFormat::TaggedInteraction,
+        );
+
+        let at_batch_id = matches
+          .subcommand_matches("diff")
+          .and_then(|diff_matches| diff_matches.value_of("at-batch"));
 
         diff(
-          events_from_chunks(spec_chunks).await,
+          events_at_batch(events_from_chunks(spec_chunks).await, at_batch_id),
+          interaction_lines,
           input_queue_size,
           diff_config,
         )
@@ -160,14 +223,15 @@ fn main() {
   });
 }
 
-async fn diff(events: Vec<SpecEvent>, diff_queue_size: usize, diff_config: DiffInteractionConfig) {
+async fn diff(
+  events: Vec<SpecEvent>,
+  interaction_lines: impl Stream<Item = Result<String, std::io::Error>>,
+  diff_queue_size: usize,
+  diff_config: DiffInteractionConfig,
+) {
   let spec_projection = Arc::new(SpecProjection::from(events));
   let diff_config = Arc::new(diff_config);
 
-  let stdin = stdin(); // TODO: deal with std in never having been attached
-
-  let interaction_lines = streams::http_interaction::json_lines(stdin);
-
   let (results_sender, mut results_receiver) = mpsc::channel(32); // buffer 32 results
 
   let results_manager = tokio::spawn(async move {
@@ -247,8 +311,8 @@ async fn diff(events: Vec<SpecEvent>, diff_queue_size: usize, diff_config: DiffI
   try_join!(diffing_interactions, results_manager).expect("essential worker task panicked");
 }
 
-async fn assemble(spec_chunks: Vec<SpecChunkEvent>) {
-  let spec_events = events_from_chunks(spec_chunks).await;
+async fn assemble(spec_chunks: Vec<SpecChunkEvent>, at_batch_id: Option<&str>) {
+  let spec_events = events_at_batch(events_from_chunks(spec_chunks).await, at_batch_id);
 
   let stdout = stdout();
 
@@ -276,9 +340,41 @@ impl From<(InteractionDiffResult, &Tags)> for ResultContainer<InteractionDiffRes
 }
 
 async fn events_from_chunks(chunks: Vec<SpecChunkEvent>) -> Vec<SpecEvent> {
-  streams::spec_events::from_spec_chunks(chunks)
-    .await
-    .unwrap() // TODO: report on these errors in a more user-friendly way (like for single spec files)
+  let (events, conflicts) = SpecAssemblerProjection::from(chunks)
+    .into_events_with_conflicts()
+    .unwrap(); // TODO: report on these errors in a more user-friendly way (like for single spec files)
+
+  for conflict in conflicts {
+    eprintln!(
+      "warning: conflicting changes were merged from batches {}: {}",
+      conflict.batch_ids.join(", "),
+      serde_json::to_string(&conflict.kind).expect("spec chunk conflicts should serialize")
+    );
+  }
+
+  events
+}
+
+fn create_at_batch_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name("at-batch")
+    .long("at-batch")
+    .takes_value(true)
+    .value_name("BATCH_ID")
+    .help("Use the spec as it was right after this batch commit, or 'root' for before any commit")
+}
+
+fn events_at_batch(mut events: Vec<SpecEvent>, at_batch_id: Option<&str>) -> Vec<SpecEvent> {
+  if let Some(batch_id) = at_batch_id {
+    let events_count = SpecEvent::until_batch_commit(&events, batch_id)
+      .unwrap_or_else(|err| {
+        eprintln!("Could not use the spec at batch commit: {}", err);
+        process::exit(1);
+      })
+      .len();
+    events.truncate(events_count);
+  }
+
+  events
 }
 
 #[cfg(test)]
@@ -0,0 +1,87 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+use optic_engine::streams;
+use optic_engine::{SpecChunkEvent, SpecEvent};
+use std::path::Path;
+use std::process;
+use tokio::fs;
+
+pub const SUBCOMMAND_NAME: &'static str = "merge-driver";
+
+pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
+  SubCommand::with_name(SUBCOMMAND_NAME)
+    .about("Merges two versions of a spec file, for use as a git merge driver (%O %A %B)")
+    .long_about(
+      "Merges two versions of a spec file, for use as a git merge driver. Batches appended \
+      to the file on both sides are kept, with theirs re-parented onto ours. Configure it with \
+      `git config merge.optic.driver \"optic_diff merge-driver %O %A %B\"` and mark the spec \
+      files with `merge=optic` in .gitattributes.",
+    )
+    .arg(
+      Arg::with_name("base")
+        .required(true)
+        .value_name("BASE_PATH")
+        .help("The common ancestor version of the file (%O)"),
+    )
+    .arg(
+      Arg::with_name("ours")
+        .required(true)
+        .value_name("OURS_PATH")
+        .help("Our version of the file, to which the merged version is written (%A)"),
+    )
+    .arg(
+      Arg::with_name("theirs")
+        .required(true)
+        .value_name("THEIRS_PATH")
+        .help("Their version of the file (%B)"),
+    )
+}
+
+pub async fn main<'a>(command_matches: &'a ArgMatches<'a>) {
+  let base_path = command_matches.value_of("base").expect("base is required");
+  let ours_path = command_matches.value_of("ours").expect("ours is required");
+  let theirs_path = command_matches
+    .value_of("theirs")
+    .expect("theirs is required");
+
+  let base = read_events(base_path).await;
+  let ours = read_events(ours_path).await;
+  let theirs = read_events(theirs_path).await;
+
+  // a non-zero exit makes git fall back to reporting the file as conflicted
+  let merged = SpecChunkEvent::merge_events(base, ours, theirs).unwrap_or_else(|err| {
+    eprintln!("Could not merge spec events: {}", err);
+    process::exit(1);
+  });
+
+  let file = fs::File::create(ours_path).await.unwrap_or_else(|err| {
+    eprintln!("Could not write merged spec events: {}", err);
+    process::exit(1);
+  });
+  streams::spec_events::write_to_json_array(file, &merged)
+    .await
+    .unwrap_or_else(|err| {
+      eprintln!("Could not write merged spec events: {}", err);
+      process::exit(1);
+    });
+}
+
+async fn read_events(path: impl AsRef<Path>) -> Vec<SpecEvent> {
+  let contents = fs::read_to_string(&path).await.unwrap_or_else(|err| {
+    eprintln!("Could not read spec file {:?}: {}", path.as_ref(), err);
+    process::exit(1);
+  });
+
+  // git passes an empty base for files added on both sides
+  if contents.trim().is_empty() {
+    return vec![];
+  }
+
+  serde_json::from_str(&contents).unwrap_or_else(|err| {
+    eprintln!(
+      "Spec file {:?} could not be parsed as spec events: {}",
+      path.as_ref(),
+      err
+    );
+    process::exit(1);
+  })
+}
@@ -0,0 +1,155 @@
+use super::commit::{create_allow_breaking_arg, justify_breaking_changes};
+use super::events_from_chunks;
+use super::learn::IdGenerator;
+use chrono::Utc;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use optic_engine::append_batch_to_spec;
+use optic_engine::revert_batch_commands;
+use optic_engine::streams;
+use optic_engine::CommandContext;
+use optic_engine::{SpecChunkEvent, SpecEvent, SpecProjection};
+use std::path::Path;
+use std::process;
+use tokio::io::stdout;
+use uuid::Uuid;
+
+pub const SUBCOMMAND_NAME: &'static str = "revert";
+
+pub fn create_subcommand<'a, 'b>() -> App<'a, 'b> {
+  SubCommand::with_name(SUBCOMMAND_NAME)
+    .about("Undoes the changes of a batch commit with a new batch commit")
+    .arg(
+      Arg::with_name("batch-id")
+        .required(true)
+        .value_name("BATCH_ID")
+        .help("The batch commit to revert"),
+    )
+    .arg(
+      Arg::with_name("commit-message")
+        .short("m")
+        .required(false)
+        .value_name("COMMIT_MESSAGE")
+        .takes_value(true)
+        .help(
+          "The commit message of the new batch commit, defaulting to 'Revert batch <BATCH_ID>'",
+        ),
+    )
+    .arg(
+      Arg::with_name("client-session-id")
+        .long("client-session-id")
+        .required(false)
+        .value_name("CLIENT_SESSION_ID")
+        .takes_value(true)
+        .default_value("unknown-session")
+        .help("The session id of the client requesting the revert"),
+    )
+    .arg(
+      Arg::with_name("client-id")
+        .long("client-id")
+        .required(false)
+        .value_name("CLIENT_ID")
+        .takes_value(true)
+        .default_value("anonymous")
+        .help("Unique id of the client requesting the revert"),
+    )
+    .arg(create_allow_breaking_arg())
+}
+
+pub async fn main<'a>(
+  command_matches: &'a ArgMatches<'a>,
+  spec_chunks: Vec<SpecChunkEvent>,
+  spec_path: impl AsRef<Path>,
+) {
+  let reverted_batch_id = command_matches
+    .value_of("batch-id")
+    .expect("batch-id is required");
+
+  let commit_message = command_matches
+    .value_of("commit-message")
+    .map(String::from)
+    .unwrap_or_else(|| format!("Revert batch {}", reverted_batch_id));
+
+  let client_session_id = command_matches
+    .value_of("client-session-id")
+    .expect("client-session-id is required");
+
+  let client_id = command_matches
+    .value_of("client-id")
+    .expect("client-id is required");
+
+  let breaking_change_justification = command_matches.value_of("allow-breaking");
+
+  revert(
+    events_from_chunks(spec_chunks).await,
+    &spec_path,
+    reverted_batch_id,
+    commit_message,
+    client_id,
+    client_session_id,
+    breaking_change_justification,
+  )
+  .await;
+}
+
+async fn revert(
+  spec_events: Vec<SpecEvent>,
+  spec_dir_path: impl AsRef<Path>,
+  reverted_batch_id: &str,
+  commit_message: String,
+  client_id: &str,
+  client_session_id: &str,
+  breaking_change_justification: Option<&str>,
+) {
+  let mut id_generator = IdGenerator::default();
+  let commands = revert_batch_commands(&spec_events, reverted_batch_id, &mut id_generator)
+    .unwrap_or_else(|err| {
+      eprintln!("Could not revert batch commit: {}", err);
+      process::exit(1);
+    });
+
+  let batch_id = Uuid::new_v4().to_hyphenated().to_string();
+  let batch_command_context = CommandContext::new(
+    batch_id.clone(),
+    String::from(client_id),
+    String::from(client_session_id),
+    Utc::now(),
+  );
+
+  let spec_projection = SpecProjection::from(spec_events.clone());
+  let mut batch = append_batch_to_spec(
+    spec_projection.clone(),
+    commit_message,
+    batch_command_context,
+  );
+  for command in commands {
+    batch
+      .with_command(command)
+      .expect("revert command could not be applied");
+  }
+
+  // reverting additions removes what clients might rely on, like any other commit
+  if let Some(contribution) = justify_breaking_changes(
+    &spec_events,
+    &spec_projection,
+    batch.spec_projection(),
+    &batch_id,
+    breaking_change_justification,
+  ) {
+    batch
+      .with_command(contribution)
+      .expect("breaking change justification could not be added to the batch");
+  }
+
+  let spec_chunk_event = SpecChunkEvent::batch_from_events(batch_id, batch.commit())
+    .expect("valid batch chunk should have been created");
+
+  streams::spec_chunks::to_api_dir(std::iter::once(&spec_chunk_event), spec_dir_path)
+    .await
+    .unwrap_or_else(|err| {
+      panic!("could not write new spec batch chunk to api dir: {:?}", err);
+    });
+
+  streams::spec_events::write_to_json_array(stdout(), spec_chunk_event.events())
+    .await
+    .unwrap_or_else(|err| panic!("could not write new events to stdout: {}", err))
+}
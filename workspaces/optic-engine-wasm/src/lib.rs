@@ -3,7 +3,8 @@
 use chrono::Utc;
 use nanoid::nanoid;
 use optic_engine::{
-  analyze_undocumented_bodies, Aggregate, AnalyzeUndocumentedBodiesConfig, Body,
+  analyze_undocumented_bodies, Aggregate, AnalyzeDocumentedBodiesConfig,
+  AnalyzeUndocumentedBodiesConfig, Body,
   BodyAnalysisResult, CommandContext, DiffInteractionConfig, EndpointQueries, HttpInteraction,
   InteractionDiffResult, JsonTrail, JsonType, LearnedShapeDiffAffordancesProjection,
   LearnedUndocumentedBodiesProjection, ResponseBodyDescriptor, ResponseId, ShapeChoiceQueries,
@@ -30,6 +31,18 @@ pub fn spec_from_events(spec_json: String) -> Result<WasmSpecProjection, JsValue
   Ok(WasmSpecProjection::from(spec_projection))
 }
 
+#[wasm_bindgen]
+pub fn spec_from_events_at_batch_commit(
+  spec_json: String,
+  batch_id: String,
+) -> Result<WasmSpecProjection, JsValue> {
+  let spec_events: Vec<SpecEvent> = serde_json::from_str(&spec_json).unwrap();
+  let spec_projection = SpecProjection::at_batch_commit(&spec_events, &batch_id)
+    .map_err(|err| JsValue::from(err.to_string()))?;
+
+  Ok(WasmSpecProjection::from(spec_projection))
+}
+
 #[wasm_bindgen]
 pub fn get_endpoints_projection(spec: &WasmSpecProjection) -> Result<String, JsValue> {
   spec.spectacle_endpoints_projection()
@@ -205,6 +218,8 @@ pub fn learn_shape_diff_affordances(
     .map_err(|err| JsValue::from(format!("could not parse diff results: {}", err)))?;
   let interactions = serde_json::Deserializer::from_str(&tagged_interactions_json).into_iter();
 
+  let learner_config = AnalyzeDocumentedBodiesConfig::default();
+
   let mut learned_shape_diff_affordances =
     LearnedShapeDiffAffordancesProjection::from(diff_results);
 
@@ -214,7 +229,7 @@ pub fn learn_shape_diff_affordances(
         .map_err(|err| JsValue::from(format!("could not parse interaction json: {}", err)))?;
 
     let results = spec
-      .analyze_documented_bodies(interaction)
+      .analyze_documented_bodies(interaction, &learner_config)
       .map(|result| TaggedInput(result, interaction_pointers.clone()));
 
     for result in results {
@@ -258,8 +273,9 @@ impl WasmSpecProjection {
   fn analyze_documented_bodies(
     &self,
     interaction: HttpInteraction,
+    config: &AnalyzeDocumentedBodiesConfig,
   ) -> impl Iterator<Item = BodyAnalysisResult> {
-    optic_engine::analyze_documented_bodies(&self.projection, interaction)
+    optic_engine::analyze_documented_bodies(&self.projection, interaction, config)
   }
 
   pub fn spectacle_endpoints_projection(&self) -> Result<String, JsValue> {
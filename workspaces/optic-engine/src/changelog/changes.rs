@@ -0,0 +1,644 @@
+use super::compatibility::{self, Direction};
+use crate::events::{SpecEvent, UnknownBatchCommitError};
+use crate::projections::endpoint::BodyDescriptor;
+use crate::projections::SpecProjection;
+use crate::queries::{EndpointQueries, ShapeQueries};
+use crate::state::endpoint::{HttpContentType, HttpMethod, HttpStatusCode};
+use crate::state::shape::{ShapeId, ShapeKind};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use thiserror::Error;
+
+/// What changed to the endpoints of a spec between two of its batch commits
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Changelog {
+  pub from_batch_id: String,
+  pub to_batch_id: String,
+  pub endpoints: Vec<EndpointChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+  Added,
+  Removed,
+  Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointChange {
+  pub change: ChangeKind,
+  pub http_method: HttpMethod,
+  pub path_pattern: String,
+  pub requests: Vec<BodyChange>,
+  pub responses: Vec<BodyChange>,
+  pub is_breaking: bool,
+}
+
+/// A change to the body of a request, or the response with a status code. Bodies are
+/// identified by their content type, with `None` for requests and responses without a body.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BodyChange {
+  pub change: ChangeKind,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub http_status_code: Option<HttpStatusCode>,
+  pub content_type: Option<HttpContentType>,
+  pub fields: Vec<FieldChange>,
+  pub is_breaking: bool,
+}
+
+/// A change to a field of a body, identified by its path. Items of lists are written as `[]`
+/// and values of maps as `{}`, so `todos[].title` is the title field of every todo.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+  pub change: ChangeKind,
+  pub path: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub from: Option<FieldSummary>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub to: Option<FieldSummary>,
+  pub is_breaking: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSummary {
+  pub type_name: String,
+  pub is_required: bool,
+  #[serde(skip)]
+  pub(crate) shape_kinds: BTreeSet<ShapeKind>,
+}
+
+#[derive(Debug, Error)]
+pub enum ChangelogError {
+  #[error(transparent)]
+  UnknownBatch(#[from] UnknownBatchCommitError),
+}
+
+impl Changelog {
+  /// Project the spec as it was after both batch commits and compare them. The batch id `root`
+  /// stands for the spec before any batch was committed.
+  pub fn between_batches(
+    events: &[SpecEvent],
+    from_batch_id: &str,
+    to_batch_id: &str,
+  ) -> Result<Self, ChangelogError> {
+    let from_spec = SpecProjection::at_batch_commit(events, from_batch_id)?;
+    let to_spec = SpecProjection::at_batch_commit(events, to_batch_id)?;
+
+    Ok(Self::between_specs(
+      &from_spec,
+      &to_spec,
+      from_batch_id,
+      to_batch_id,
+    ))
+  }
+
+  pub fn between_specs(
+    from_spec: &SpecProjection,
+    to_spec: &SpecProjection,
+    from_batch_id: &str,
+    to_batch_id: &str,
+  ) -> Self {
+    let from_endpoints = endpoint_surfaces(from_spec);
+    let mut to_endpoints = endpoint_surfaces(to_spec);
+
+    let mut endpoints = vec![];
+    for (key, from_endpoint) in from_endpoints {
+      match to_endpoints.remove(&key) {
+        Some(to_endpoint) => {
+          let requests = body_changes(
+            Direction::Request,
+            &from_endpoint.requests,
+            &to_endpoint.requests,
+          );
+          let responses = body_changes(
+            Direction::Response,
+            &from_endpoint.responses,
+            &to_endpoint.responses,
+          );
+          if !requests.is_empty() || !responses.is_empty() {
+            let is_breaking = requests
+              .iter()
+              .chain(responses.iter())
+              .any(|body| body.is_breaking);
+            endpoints.push(EndpointChange {
+              change: ChangeKind::Changed,
+              http_method: key.1,
+              path_pattern: to_endpoint.path_pattern,
+              requests,
+              responses,
+              is_breaking,
+            });
+          }
+        }
+        None => endpoints.push(EndpointChange {
+          change: ChangeKind::Removed,
+          http_method: key.1,
+          path_pattern: from_endpoint.path_pattern,
+          requests: vec![],
+          responses: vec![],
+          is_breaking: true,
+        }),
+      }
+    }
+    for (key, to_endpoint) in to_endpoints {
+      endpoints.push(EndpointChange {
+        change: ChangeKind::Added,
+        http_method: key.1,
+        path_pattern: to_endpoint.path_pattern,
+        requests: vec![],
+        responses: vec![],
+        is_breaking: false,
+      });
+    }
+    endpoints
+      .sort_by(|a, b| (&a.path_pattern, &a.http_method).cmp(&(&b.path_pattern, &b.http_method)));
+
+    Self {
+      from_batch_id: String::from(from_batch_id),
+      to_batch_id: String::from(to_batch_id),
+      endpoints,
+    }
+  }
+
+  /// Whether any of the changes breaks clients of the spec as it was before
+  pub fn has_breaking_changes(&self) -> bool {
+    self.endpoints.iter().any(|endpoint| endpoint.is_breaking)
+  }
+
+  pub fn to_markdown(&self) -> String {
+    super::markdown::render(self)
+  }
+}
+
+// Endpoint surfaces
+// -----------------
+
+type Fields = BTreeMap<String, FieldSummary>;
+
+// endpoints are identified by their path pattern with parameter names left out, so renaming
+// a path parameter doesn't make it a different endpoint
+type EndpointKey = (String, HttpMethod);
+
+struct EndpointSurface {
+  path_pattern: String,
+  requests: BTreeMap<(Option<HttpStatusCode>, Option<HttpContentType>), Fields>,
+  responses: BTreeMap<(Option<HttpStatusCode>, Option<HttpContentType>), Fields>,
+}
+
+fn endpoint_surfaces(spec: &SpecProjection) -> BTreeMap<EndpointKey, EndpointSurface> {
+  let endpoint_queries = EndpointQueries::new(spec.endpoint());
+  let shape_queries = ShapeQueries::new(spec.shape());
+  let body_fields = |body: &Option<BodyDescriptor>| {
+    let mut fields = Fields::new();
+    if let Some(body) = body {
+      if spec
+        .shape()
+        .get_shape_node_index(&body.root_shape_id)
+        .is_some()
+      {
+        collect_fields(
+          &shape_queries,
+          &body.root_shape_id,
+          "",
+          true,
+          &mut fields,
+          &mut vec![],
+        );
+      }
+    }
+    (
+      body.as_ref().map(|body| body.http_content_type.clone()),
+      fields,
+    )
+  };
+
+  let mut surfaces = BTreeMap::new();
+  for (path_id, path_pattern) in endpoint_queries.resolve_path_patterns() {
+    for method in endpoint_queries.resolve_path_methods(path_id) {
+      let requests: BTreeMap<_, _> = endpoint_queries
+        .resolve_requests(path_id, method)
+        .into_iter()
+        .flatten()
+        .map(|(_, request)| {
+          let (content_type, fields) = body_fields(&request.body);
+          ((None, content_type), fields)
+        })
+        .collect();
+      let responses: BTreeMap<_, _> = endpoint_queries
+        .resolve_endpoint_responses(path_id, method)
+        .map(|(status_code, _, response)| {
+          let (content_type, fields) = body_fields(&response.body);
+          ((Some(*status_code), content_type), fields)
+        })
+        .collect();
+      if requests.is_empty() && responses.is_empty() {
+        continue;
+      }
+
+      let key = (
+        path_pattern
+          .split('/')
+          .map(|segment| {
+            if segment.starts_with('{') {
+              "{}"
+            } else {
+              segment
+            }
+          })
+          .collect::<Vec<_>>()
+          .join("/"),
+        method.clone(),
+      );
+      surfaces.insert(
+        key,
+        EndpointSurface {
+          path_pattern: path_pattern.clone(),
+          requests,
+          responses,
+        },
+      );
+    }
+  }
+
+  surfaces
+}
+
+fn body_changes(
+  direction: Direction,
+  from_bodies: &BTreeMap<(Option<HttpStatusCode>, Option<HttpContentType>), Fields>,
+  to_bodies: &BTreeMap<(Option<HttpStatusCode>, Option<HttpContentType>), Fields>,
+) -> Vec<BodyChange> {
+  let keys: BTreeSet<_> = from_bodies.keys().chain(to_bodies.keys()).collect();
+
+  keys
+    .into_iter()
+    .filter_map(|key| {
+      let (change, fields) = match (from_bodies.get(key), to_bodies.get(key)) {
+        (Some(from_fields), Some(to_fields)) => {
+          let fields = field_changes(direction, from_fields, to_fields);
+          if fields.is_empty() {
+            return None;
+          }
+          (ChangeKind::Changed, fields)
+        }
+        (Some(_), None) => (ChangeKind::Removed, vec![]),
+        (None, Some(_)) => (ChangeKind::Added, vec![]),
+        (None, None) => unreachable!("keys should be of either set of bodies"),
+      };
+
+      let mut body = BodyChange {
+        change,
+        http_status_code: key.0,
+        content_type: key.1.clone(),
+        fields,
+        is_breaking: false,
+      };
+      body.is_breaking = compatibility::is_breaking_body_change(&body);
+      Some(body)
+    })
+    .collect()
+}
+
+fn field_changes(
+  direction: Direction,
+  from_fields: &Fields,
+  to_fields: &Fields,
+) -> Vec<FieldChange> {
+  let paths: BTreeSet<_> = from_fields.keys().chain(to_fields.keys()).collect();
+
+  paths
+    .into_iter()
+    .filter_map(|path| {
+      let from = from_fields.get(path);
+      let to = to_fields.get(path);
+      let change = match (from, to) {
+        (Some(from), Some(to)) if from == to => return None,
+        (Some(_), Some(_)) => ChangeKind::Changed,
+        (Some(_), None) => ChangeKind::Removed,
+        (None, Some(_)) => ChangeKind::Added,
+        (None, None) => unreachable!("paths should be of either set of fields"),
+      };
+
+      let mut field = FieldChange {
+        change,
+        path: path.clone(),
+        from: from.cloned(),
+        to: to.cloned(),
+        is_breaking: false,
+      };
+      field.is_breaking = compatibility::is_breaking_field_change(direction, &field);
+      Some(field)
+    })
+    .collect()
+}
+
+/// Flatten the shape of a body into the fields it describes, by path. The root of the body is
+/// included as the field with an empty path.
+fn collect_fields(
+  shape_queries: &ShapeQueries,
+  shape_id: &ShapeId,
+  path: &str,
+  is_required: bool,
+  fields: &mut Fields,
+  collecting_shape_ids: &mut Vec<ShapeId>,
+) {
+  let mut kinds = vec![];
+  collect_value_kinds(shape_queries, shape_id, &mut kinds, &mut vec![]);
+  if kinds.is_empty() {
+    kinds.push(ShapeKind::UnknownKind);
+  }
+  fields.insert(
+    String::from(path),
+    FieldSummary {
+      type_name: kinds.iter().map(type_name).collect::<Vec<_>>().join(" | "),
+      is_required,
+      shape_kinds: kinds.into_iter().collect(),
+    },
+  );
+  collect_child_fields(shape_queries, shape_id, path, fields, collecting_shape_ids);
+}
+
+fn collect_child_fields(
+  shape_queries: &ShapeQueries,
+  shape_id: &ShapeId,
+  path: &str,
+  fields: &mut Fields,
+  collecting_shape_ids: &mut Vec<ShapeId>,
+) {
+  let shape_id = own_shape_id(shape_queries, shape_id);
+  // recursive shapes describe the same fields again, so stop once we're back where we were
+  if collecting_shape_ids.contains(&shape_id) {
+    return;
+  }
+  collecting_shape_ids.push(shape_id.clone());
+
+  let core_shape_kind = shape_queries.resolve_to_own_core_shape(&shape_id);
+  match core_shape_kind {
+    ShapeKind::ObjectKind => {
+      for (field_id, name) in shape_queries.resolve_shape_field_id_and_names(&shape_id) {
+        let field_shape_id = shape_queries
+          .resolve_field_shape_node(field_id)
+          .expect("field node should have an edge to a shape node describing its value");
+        let is_required = !matches!(
+          shape_queries.resolve_to_own_core_shape(&own_shape_id(shape_queries, &field_shape_id)),
+          ShapeKind::OptionalKind
+        );
+        let field_path = if path.is_empty() {
+          name.clone()
+        } else {
+          format!("{}.{}", path, name)
+        };
+        collect_fields(
+          shape_queries,
+          &field_shape_id,
+          &field_path,
+          is_required,
+          fields,
+          collecting_shape_ids,
+        );
+      }
+    }
+    ShapeKind::ListKind | ShapeKind::MapKind => {
+      let inner_path = match core_shape_kind {
+        ShapeKind::ListKind => format!("{}[]", path),
+        _ => format!("{}{{}}", path),
+      };
+      collect_fields(
+        shape_queries,
+        &inner_shape_id(shape_queries, &shape_id, core_shape_kind),
+        &inner_path,
+        true,
+        fields,
+        collecting_shape_ids,
+      );
+    }
+    ShapeKind::OneOfKind => {
+      for (_, item_shape_id) in shape_queries.resolve_parameters_to_shapes(&shape_id) {
+        collect_child_fields(
+          shape_queries,
+          &item_shape_id,
+          path,
+          fields,
+          collecting_shape_ids,
+        );
+      }
+    }
+    ShapeKind::NullableKind
+    | ShapeKind::OptionalKind
+    | ShapeKind::IdentifierKind
+    | ShapeKind::ReferenceKind => {
+      collect_child_fields(
+        shape_queries,
+        &inner_shape_id(shape_queries, &shape_id, core_shape_kind),
+        path,
+        fields,
+        collecting_shape_ids,
+      );
+    }
+    _ => {}
+  }
+
+  collecting_shape_ids.pop();
+}
+
+/// The kinds of shapes a shape allows values of, with `NullableKind` standing for null. Shapes
+/// wrapping themselves don't describe any value and are of `UnknownKind`.
+fn collect_value_kinds(
+  shape_queries: &ShapeQueries,
+  shape_id: &ShapeId,
+  kinds: &mut Vec<ShapeKind>,
+  following_shape_ids: &mut Vec<ShapeId>,
+) {
+  let shape_id = own_shape_id(shape_queries, shape_id);
+  if following_shape_ids.contains(&shape_id) {
+    add_kind(kinds, ShapeKind::UnknownKind);
+    return;
+  }
+
+  let core_shape_kind = shape_queries.resolve_to_own_core_shape(&shape_id);
+  following_shape_ids.push(shape_id.clone());
+  match core_shape_kind {
+    ShapeKind::OneOfKind => {
+      for (_, item_shape_id) in shape_queries.resolve_parameters_to_shapes(&shape_id) {
+        collect_value_kinds(shape_queries, &item_shape_id, kinds, following_shape_ids);
+      }
+    }
+    ShapeKind::NullableKind => {
+      let inner_shape_id = inner_shape_id(shape_queries, &shape_id, core_shape_kind);
+      collect_value_kinds(shape_queries, &inner_shape_id, kinds, following_shape_ids);
+      add_kind(kinds, ShapeKind::NullableKind);
+    }
+    ShapeKind::OptionalKind | ShapeKind::IdentifierKind | ShapeKind::ReferenceKind => {
+      let inner_shape_id = inner_shape_id(shape_queries, &shape_id, core_shape_kind);
+      collect_value_kinds(shape_queries, &inner_shape_id, kinds, following_shape_ids);
+    }
+    kind => add_kind(kinds, kind.clone()),
+  }
+  following_shape_ids.pop();
+}
+
+fn add_kind(kinds: &mut Vec<ShapeKind>, kind: ShapeKind) {
+  if !kinds.contains(&kind) {
+    kinds.push(kind);
+  }
+}
+
+fn type_name(kind: &ShapeKind) -> &'static str {
+  match kind {
+    ShapeKind::ObjectKind | ShapeKind::MapKind => "object",
+    ShapeKind::ListKind => "array",
+    ShapeKind::StringKind => "string",
+    ShapeKind::NumberKind => "number",
+    ShapeKind::BooleanKind => "boolean",
+    ShapeKind::NullableKind => "null",
+    ShapeKind::AnyKind => "any",
+    _ => "unknown",
+  }
+}
+
+/// Follows the shapes a shape descends from, up to the one descending from a core shape
+fn own_shape_id(shape_queries: &ShapeQueries, shape_id: &ShapeId) -> ShapeId {
+  let mut shape_id = shape_id;
+  while let Some(base_shape_id) = shape_queries.resolve_base_shape_id(shape_id) {
+    shape_id = base_shape_id;
+  }
+  shape_id.clone()
+}
+
+fn inner_shape_id(
+  shape_queries: &ShapeQueries,
+  shape_id: &ShapeId,
+  core_shape_kind: &ShapeKind,
+) -> ShapeId {
+  let parameter_id = core_shape_kind
+    .get_parameter_descriptor()
+    .expect("shape kinds wrapping other shapes should have a parameter descriptor")
+    .shape_parameter_id;
+  shape_queries.resolve_parameter_to_shape(shape_id, &String::from(parameter_id))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::events::test_fixtures::todo_batch_commit_events;
+  use insta::{assert_json_snapshot, assert_snapshot};
+  use serde_json::json;
+
+  #[test]
+  pub fn can_describe_changes_between_batch_commits() {
+    let events = todo_batch_commit_events();
+
+    let changelog = Changelog::between_batches(&events, "batch_1", "batch_2")
+      .expect("changelog between known batches should be described");
+    assert_json_snapshot!(
+      "can_describe_changes_between_batch_commits__json",
+      changelog
+    );
+    assert_snapshot!(
+      "can_describe_changes_between_batch_commits__markdown",
+      changelog.to_markdown()
+    );
+
+    assert!(changelog.has_breaking_changes());
+
+    let initial_changelog = Changelog::between_batches(&events, "root", "batch_1")
+      .expect("changelog from the root should be described");
+    assert_eq!(
+      initial_changelog
+        .endpoints
+        .iter()
+        .map(|endpoint| (endpoint.change, endpoint.http_method.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        (ChangeKind::Added, "GET"),
+        (ChangeKind::Added, "DELETE"),
+        (ChangeKind::Added, "GET")
+      ]
+    );
+
+    assert!(matches!(
+      Changelog::between_batches(&events, "batch_1", "batch_3"),
+      Err(ChangelogError::UnknownBatch(_))
+    ));
+  }
+
+  #[test]
+  pub fn can_describe_fields_by_the_kinds_of_their_shapes() {
+    let events: Vec<SpecEvent> = serde_json::from_value(json!([
+      {"ShapeAdded": {"shapeId": "string_shape_1","baseShapeId": "$string","parameters": {"DynamicParameterList": {"shapeParameterIds": []}},"name": ""}},
+      {"ShapeAdded": {"shapeId": "number_shape_1","baseShapeId": "$number","parameters": {"DynamicParameterList": {"shapeParameterIds": []}},"name": ""}},
+      {"ShapeAdded": {"shapeId": "nullable_shape_1","baseShapeId": "$nullable","parameters": {"DynamicParameterList": {"shapeParameterIds": []}},"name": ""}},
+      {"ShapeParameterShapeSet": {"shapeDescriptor": {"ProviderInShape": {"shapeId": "nullable_shape_1","providerDescriptor": {"ShapeProvider": {"shapeId": "number_shape_1"}},"consumingParameterId": "$nullableInner"}}}},
+      {"ShapeAdded": {"shapeId": "map_shape_1","baseShapeId": "$map","parameters": {"DynamicParameterList": {"shapeParameterIds": []}},"name": ""}},
+      {"ShapeParameterShapeSet": {"shapeDescriptor": {"ProviderInShape": {"shapeId": "map_shape_1","providerDescriptor": {"ShapeProvider": {"shapeId": "nullable_shape_1"}},"consumingParameterId": "$mapValue"}}}},
+      {"ShapeAdded": {"shapeId": "one_of_shape_1","baseShapeId": "$oneOf","parameters": {"DynamicParameterList": {"shapeParameterIds": []}},"name": ""}},
+      {"ShapeParameterAdded": {"shapeParameterId": "one_of_parameter_1","shapeId": "one_of_shape_1","name": "","shapeDescriptor": {"ProviderInShape": {"shapeId": "one_of_shape_1","providerDescriptor": {"NoProvider": {}},"consumingParameterId": "one_of_parameter_1"}}}},
+      {"ShapeParameterShapeSet": {"shapeDescriptor": {"ProviderInShape": {"shapeId": "one_of_shape_1","providerDescriptor": {"ShapeProvider": {"shapeId": "string_shape_1"}},"consumingParameterId": "one_of_parameter_1"}}}},
+      {"ShapeParameterAdded": {"shapeParameterId": "one_of_parameter_2","shapeId": "one_of_shape_1","name": "","shapeDescriptor": {"ProviderInShape": {"shapeId": "one_of_shape_1","providerDescriptor": {"NoProvider": {}},"consumingParameterId": "one_of_parameter_2"}}}},
+      {"ShapeParameterShapeSet": {"shapeDescriptor": {"ProviderInShape": {"shapeId": "one_of_shape_1","providerDescriptor": {"ShapeProvider": {"shapeId": "number_shape_1"}},"consumingParameterId": "one_of_parameter_2"}}}},
+      {"ShapeAdded": {"shapeId": "todo_shape","baseShapeId": "$object","parameters": {"DynamicParameterList": {"shapeParameterIds": []}},"name": ""}},
+      {"ShapeAdded": {"shapeId": "reference_shape_1","baseShapeId": "$reference","parameters": {"DynamicParameterList": {"shapeParameterIds": []}},"name": ""}},
+      {"ShapeParameterShapeSet": {"shapeDescriptor": {"ProviderInShape": {"shapeId": "reference_shape_1","providerDescriptor": {"ShapeProvider": {"shapeId": "todo_shape"}},"consumingParameterId": "$referenceInner"}}}},
+      {"FieldAdded": {"fieldId": "field_1","shapeId": "todo_shape","name": "scores","shapeDescriptor": {"FieldShapeFromShape": {"fieldId": "field_1","shapeId": "map_shape_1"}}}},
+      {"FieldAdded": {"fieldId": "field_2","shapeId": "todo_shape","name": "priority","shapeDescriptor": {"FieldShapeFromShape": {"fieldId": "field_2","shapeId": "one_of_shape_1"}}}},
+      {"FieldAdded": {"fieldId": "field_3","shapeId": "todo_shape","name": "parent","shapeDescriptor": {"FieldShapeFromShape": {"fieldId": "field_3","shapeId": "reference_shape_1"}}}},
+    ]))
+    .expect("should be able to deserialize test events");
+    let spec = SpecProjection::from(events);
+    let shape_queries = ShapeQueries::new(spec.shape());
+
+    let mut fields = Fields::new();
+    collect_fields(
+      &shape_queries,
+      &String::from("todo_shape"),
+      "",
+      true,
+      &mut fields,
+      &mut vec![],
+    );
+
+    assert_eq!(
+      fields
+        .iter()
+        .map(|(path, field)| (path.as_str(), field.type_name.as_str(), &field.shape_kinds))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          "",
+          "object",
+          &vec![ShapeKind::ObjectKind].into_iter().collect()
+        ),
+        (
+          "parent",
+          "object",
+          &vec![ShapeKind::ObjectKind].into_iter().collect()
+        ),
+        (
+          "priority",
+          "number | string",
+          &vec![ShapeKind::StringKind, ShapeKind::NumberKind]
+            .into_iter()
+            .collect()
+        ),
+        (
+          "scores",
+          "object",
+          &vec![ShapeKind::MapKind].into_iter().collect()
+        ),
+        (
+          "scores{}",
+          "number | null",
+          &vec![ShapeKind::NumberKind, ShapeKind::NullableKind]
+            .into_iter()
+            .collect()
+        ),
+      ]
+    );
+  }
+}
@@ -0,0 +1,158 @@
+use super::changes::{BodyChange, ChangeKind, FieldChange, FieldSummary};
+use crate::state::shape::ShapeKind;
+use std::collections::BTreeSet;
+
+/// Which side of an interaction a body is produced by. Clients send requests and read
+/// responses, so the same change can break them in one direction but not in the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+  Request,
+  Response,
+}
+
+/// Whether a change to a field breaks existing clients. A response field breaks them when it
+/// can hold values it couldn't before (it became optional, nullable, another kind or gained a
+/// OneOf branch), a request field when it no longer accepts values it did before (it became
+/// required, lost a OneOf branch or changed kind).
+pub fn is_breaking_field_change(direction: Direction, field: &FieldChange) -> bool {
+  match (direction, field.change) {
+    // clients ignore response fields they don't know about, as do servers for request fields
+    // they no longer describe
+    (Direction::Response, ChangeKind::Added) | (Direction::Request, ChangeKind::Removed) => false,
+    _ => {
+      let from_kinds = value_kinds(field.from.as_ref());
+      let to_kinds = value_kinds(field.to.as_ref());
+      match direction {
+        Direction::Response => !accepts_all(&from_kinds, &to_kinds),
+        Direction::Request => !accepts_all(&to_kinds, &from_kinds),
+      }
+    }
+  }
+}
+
+/// Whether a change to a request or response body breaks existing clients. Adding a response
+/// or another request content type is safe, but removing one is not.
+pub fn is_breaking_body_change(body: &BodyChange) -> bool {
+  match body.change {
+    ChangeKind::Added => false,
+    ChangeKind::Removed => true,
+    ChangeKind::Changed => body.fields.iter().any(|field| field.is_breaking),
+  }
+}
+
+// The kinds of values a field can have, with a field that's not there holding the value of an
+// optional one that's been left out
+fn value_kinds(field: Option<&FieldSummary>) -> BTreeSet<ShapeKind> {
+  match field {
+    Some(field) => {
+      let mut kinds = field.shape_kinds.clone();
+      if !field.is_required {
+        kinds.insert(ShapeKind::OptionalKind);
+      }
+      kinds
+    }
+    None => vec![ShapeKind::OptionalKind].into_iter().collect(),
+  }
+}
+
+fn accepts_all(accepted_kinds: &BTreeSet<ShapeKind>, kinds: &BTreeSet<ShapeKind>) -> bool {
+  kinds.iter().all(|kind| {
+    let is_absent = *kind == ShapeKind::OptionalKind;
+    accepted_kinds.contains(kind) || (!is_absent && accepted_kinds.contains(&ShapeKind::AnyKind))
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn field(
+    change: ChangeKind,
+    from: Option<FieldSummary>,
+    to: Option<FieldSummary>,
+  ) -> FieldChange {
+    FieldChange {
+      change,
+      path: String::from("title"),
+      from,
+      to,
+      is_breaking: false,
+    }
+  }
+
+  fn summary(kinds: &[ShapeKind], is_required: bool) -> Option<FieldSummary> {
+    Some(FieldSummary {
+      type_name: String::new(),
+      is_required,
+      shape_kinds: kinds.iter().cloned().collect(),
+    })
+  }
+
+  #[test]
+  pub fn breaking_field_changes_depend_on_direction() {
+    let string = || summary(&[ShapeKind::StringKind], true);
+    let became_optional = field(
+      ChangeKind::Changed,
+      string(),
+      summary(&[ShapeKind::StringKind], false),
+    );
+    let became_nullable = field(
+      ChangeKind::Changed,
+      string(),
+      summary(&[ShapeKind::StringKind, ShapeKind::NullableKind], true),
+    );
+    let one_of_narrowed = field(
+      ChangeKind::Changed,
+      summary(&[ShapeKind::StringKind, ShapeKind::NumberKind], true),
+      string(),
+    );
+    let kind_changed = field(
+      ChangeKind::Changed,
+      string(),
+      summary(&[ShapeKind::NumberKind], true),
+    );
+    let required_added = field(ChangeKind::Added, None, string());
+    let optional_added = field(
+      ChangeKind::Added,
+      None,
+      summary(&[ShapeKind::StringKind], false),
+    );
+    let required_removed = field(ChangeKind::Removed, string(), None);
+    let optional_removed = field(
+      ChangeKind::Removed,
+      summary(&[ShapeKind::StringKind], false),
+      None,
+    );
+    let became_any = field(
+      ChangeKind::Changed,
+      string(),
+      summary(&[ShapeKind::AnyKind], true),
+    );
+
+    let classify = |direction| {
+      vec![
+        &became_optional,
+        &became_nullable,
+        &one_of_narrowed,
+        &kind_changed,
+        &required_added,
+        &optional_added,
+        &required_removed,
+        &optional_removed,
+        &became_any,
+      ]
+      .into_iter()
+      .map(|field| is_breaking_field_change(direction, field))
+      .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+      classify(Direction::Response),
+      vec![true, true, false, true, false, false, true, false, true]
+    );
+    assert_eq!(
+      classify(Direction::Request),
+      vec![false, false, true, true, true, false, false, false, false]
+    );
+  }
+}
@@ -0,0 +1,140 @@
+use super::changes::{BodyChange, ChangeKind, Changelog, EndpointChange, FieldChange};
+use std::fmt::Write;
+
+pub fn render(changelog: &Changelog) -> String {
+  let mut markdown = String::new();
+  writeln!(
+    markdown,
+    "# Changes from `{}` to `{}`",
+    changelog.from_batch_id, changelog.to_batch_id
+  )
+  .unwrap();
+
+  if changelog.endpoints.is_empty() {
+    writeln!(markdown, "\nNo endpoints were changed.").unwrap();
+    return markdown;
+  }
+  if changelog.has_breaking_changes() {
+    writeln!(
+      markdown,
+      "\n**Some of these changes break existing clients.**"
+    )
+    .unwrap();
+  }
+
+  for (change, heading) in &[
+    (ChangeKind::Added, "Added endpoints"),
+    (ChangeKind::Removed, "Removed endpoints"),
+    (ChangeKind::Changed, "Changed endpoints"),
+  ] {
+    let endpoints: Vec<_> = changelog
+      .endpoints
+      .iter()
+      .filter(|endpoint| endpoint.change == *change)
+      .collect();
+    if endpoints.is_empty() {
+      continue;
+    }
+
+    writeln!(markdown, "\n## {}\n", heading).unwrap();
+    for endpoint in endpoints {
+      write_endpoint(&mut markdown, endpoint);
+    }
+  }
+
+  markdown
+}
+
+fn write_endpoint(markdown: &mut String, endpoint: &EndpointChange) {
+  writeln!(
+    markdown,
+    "- `{} {}`{}",
+    endpoint.http_method,
+    endpoint.path_pattern,
+    breaking_marker(endpoint.is_breaking && endpoint.change != ChangeKind::Changed)
+  )
+  .unwrap();
+  for request in &endpoint.requests {
+    write_body(markdown, "Request", request);
+  }
+  for response in &endpoint.responses {
+    write_body(markdown, "Response", response);
+  }
+}
+
+fn write_body(markdown: &mut String, kind: &str, body: &BodyChange) {
+  let mut name = String::from(kind);
+  if let Some(status_code) = body.http_status_code {
+    write!(name, " {}", status_code).unwrap();
+  }
+  match &body.content_type {
+    Some(content_type) => write!(name, " (`{}`)", content_type).unwrap(),
+    None => name.push_str(" without body"),
+  }
+
+  writeln!(
+    markdown,
+    "  - {} {}{}",
+    name,
+    change_verb(body.change),
+    breaking_marker(body.is_breaking && body.change != ChangeKind::Changed)
+  )
+  .unwrap();
+  for field in &body.fields {
+    write_field(markdown, field);
+  }
+}
+
+fn write_field(markdown: &mut String, field: &FieldChange) {
+  let path = if field.path.is_empty() {
+    "body"
+  } else {
+    &field.path
+  };
+  let summary = |summary: &Option<super::FieldSummary>| {
+    summary
+      .as_ref()
+      .map(|summary| {
+        if summary.is_required {
+          summary.type_name.clone()
+        } else {
+          format!("optional {}", summary.type_name)
+        }
+      })
+      .unwrap_or_default()
+  };
+
+  let description = match field.change {
+    ChangeKind::Added => format!("added as {}", summary(&field.to)),
+    ChangeKind::Removed => String::from("removed"),
+    ChangeKind::Changed => format!(
+      "changed from {} to {}",
+      summary(&field.from),
+      summary(&field.to)
+    ),
+  };
+  writeln!(
+    markdown,
+    "    - `{}` {}{}",
+    path,
+    description,
+    breaking_marker(field.is_breaking)
+  )
+  .unwrap();
+}
+
+fn change_verb(change: ChangeKind) -> &'static str {
+  match change {
+    ChangeKind::Added => "added",
+    ChangeKind::Removed => "removed",
+    ChangeKind::Changed => "changed",
+  }
+}
+
+fn breaking_marker(is_breaking: bool) -> &'static str {
+  if is_breaking {
+    " **(breaking)**"
+  } else {
+    ""
+  }
+}
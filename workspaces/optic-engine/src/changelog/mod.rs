@@ -0,0 +1,7 @@
+mod changes;
+mod compatibility;
+mod markdown;
+
+pub use changes::{
+  BodyChange, ChangeKind, Changelog, ChangelogError, EndpointChange, FieldChange, FieldSummary,
+};
@@ -8,6 +8,7 @@ use crate::queries::EndpointQueries;
 use crate::state::endpoint::{
   HttpMethod, PathComponentId, QueryParametersId, QueryParametersShapeDescriptor, RequestId,
   RequestParameterId, ResponseId, ShapedBodyDescriptor, ShapedRequestParameterShapeDescriptor,
+  StringFormat,
 };
 use crate::state::shape::ShapeId;
 use crate::{events::endpoint as endpoint_events, state::body};
@@ -50,6 +51,7 @@ pub enum EndpointCommand {
 
   // Headers
   AddHeaderParameter(AddHeaderParameter),
+  AddResponseHeaderParameter(AddResponseHeaderParameter),
   SetHeaderParameterShape(SetHeaderParameterShape),
   RenameHeaderParameter(RenameHeaderParameter),
   UnsetHeaderParameterShape(UnsetHeaderParameterShape),
@@ -57,16 +59,47 @@ pub enum EndpointCommand {
 }
 
 impl EndpointCommand {
+  pub fn add_path_component(
+    path_id: PathComponentId,
+    parent_path_id: PathComponentId,
+    name: String,
+  ) -> EndpointCommand {
+    EndpointCommand::AddPathComponent(AddPathComponent {
+      path_id,
+      parent_path_id,
+      name,
+    })
+  }
+
   pub fn remove_path_component(path_id: PathComponentId) -> EndpointCommand {
     EndpointCommand::RemovePathComponent(RemovePathComponent { path_id })
   }
 
-  pub fn set_path_parameter_shape(path_id: PathComponentId, shape_id: ShapeId) -> EndpointCommand {
+  pub fn add_path_parameter(
+    path_id: PathComponentId,
+    parent_path_id: PathComponentId,
+    name: String,
+  ) -> EndpointCommand {
+    EndpointCommand::AddPathParameter(AddPathParameter {
+      path_id,
+      parent_path_id,
+      name,
+    })
+  }
+
+  pub fn set_path_parameter_shape(
+    path_id: PathComponentId,
+    shape_id: ShapeId,
+    format: Option<StringFormat>,
+    enum_values: Option<Vec<String>>,
+  ) -> EndpointCommand {
     EndpointCommand::SetPathParameterShape(SetPathParameterShape {
       path_id,
       shaped_request_parameter_shape_descriptor: ShapedRequestParameterShapeDescriptor {
         shape_id,
         is_removed: false,
+        format,
+        enum_values,
       },
     })
   }
@@ -181,6 +214,55 @@ impl EndpointCommand {
   pub fn remove_response(response_id: ResponseId) -> EndpointCommand {
     EndpointCommand::RemoveResponse(RemoveResponse { response_id })
   }
+
+  // Headers
+  // -------
+
+  pub fn add_header_parameter(
+    parameter_id: RequestParameterId,
+    request_id: RequestId,
+    name: String,
+  ) -> EndpointCommand {
+    EndpointCommand::AddHeaderParameter(AddHeaderParameter {
+      parameter_id,
+      request_id,
+      name,
+    })
+  }
+
+  pub fn add_response_header_parameter(
+    parameter_id: RequestParameterId,
+    response_id: ResponseId,
+    name: String,
+  ) -> EndpointCommand {
+    EndpointCommand::AddResponseHeaderParameter(AddResponseHeaderParameter {
+      parameter_id,
+      response_id,
+      name,
+    })
+  }
+
+  pub fn set_header_parameter_shape(
+    parameter_id: RequestParameterId,
+    shape_id: ShapeId,
+    is_removed: bool,
+    format: Option<StringFormat>,
+    enum_values: Option<Vec<String>>,
+  ) -> EndpointCommand {
+    EndpointCommand::SetHeaderParameterShape(SetHeaderParameterShape {
+      parameter_id,
+      parameter_descriptor: ShapedRequestParameterShapeDescriptor {
+        shape_id,
+        is_removed,
+        format,
+        enum_values,
+      },
+    })
+  }
+
+  pub fn remove_header_parameter(parameter_id: RequestParameterId) -> EndpointCommand {
+    EndpointCommand::RemoveHeaderParameter(RemoveHeaderParameter { parameter_id })
+  }
 }
 
 // Path components
@@ -369,35 +451,43 @@ pub struct RemoveQueryParameters {
 #[derive(Deserialize, Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AddHeaderParameter {
-  parameter_id: RequestParameterId,
-  request_id: RequestId,
-  name: String,
+  pub parameter_id: RequestParameterId,
+  pub request_id: RequestId,
+  pub name: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddResponseHeaderParameter {
+  pub parameter_id: RequestParameterId,
+  pub response_id: ResponseId,
+  pub name: String,
 }
 
 #[derive(Deserialize, Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SetHeaderParameterShape {
-  parameter_id: RequestParameterId,
-  parameter_descriptor: ShapedRequestParameterShapeDescriptor,
+  pub parameter_id: RequestParameterId,
+  pub parameter_descriptor: ShapedRequestParameterShapeDescriptor,
 }
 
 #[derive(Deserialize, Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RenameHeaderParameter {
-  parameter_id: RequestParameterId,
-  name: String,
+  pub parameter_id: RequestParameterId,
+  pub name: String,
 }
 
 #[derive(Deserialize, Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UnsetHeaderParameterShape {
-  parameter_id: RequestParameterId,
+  pub parameter_id: RequestParameterId,
 }
 
 #[derive(Deserialize, Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoveHeaderParameter {
-  parameter_id: RequestParameterId,
+  pub parameter_id: RequestParameterId,
 }
 
 // Command handling
@@ -678,6 +768,94 @@ impl AggregateCommand<EndpointProjection> for EndpointCommand {
         ))]
       }
 
+      // Headers
+      // -------
+      EndpointCommand::AddHeaderParameter(command) => {
+        validation.require(
+          !validation.header_parameter_exists(&command.parameter_id),
+          "parameter id must be assignable to add header parameter",
+        )?;
+        validation.require(
+          validation.request_exists(&command.request_id),
+          "request must exist to add header parameter",
+        )?;
+        validation.require(
+          !validation.request_has_header_parameter_named(&command.request_id, &command.name),
+          "request must not have a header parameter with the same name to add header parameter",
+        )?;
+
+        vec![EndpointEvent::from(
+          endpoint_events::HeaderParameterAdded::from(command),
+        )]
+      }
+
+      EndpointCommand::AddResponseHeaderParameter(command) => {
+        validation.require(
+          !validation.header_parameter_exists(&command.parameter_id),
+          "parameter id must be assignable to add response header parameter",
+        )?;
+        validation.require(
+          validation.response_exists(&command.response_id),
+          "response must exist to add response header parameter",
+        )?;
+        validation.require(
+          !validation.response_has_header_parameter_named(&command.response_id, &command.name),
+          "response must not have a header parameter with the same name to add response header parameter",
+        )?;
+
+        vec![EndpointEvent::from(
+          endpoint_events::ResponseHeaderParameterAdded::from(command),
+        )]
+      }
+
+      EndpointCommand::SetHeaderParameterShape(command) => {
+        validation.require(
+          validation.header_parameter_exists(&command.parameter_id),
+          "header parameter must exist to set header parameter shape",
+        )?;
+
+        vec![EndpointEvent::from(
+          endpoint_events::HeaderParameterShapeSet::from(command),
+        )]
+      }
+
+      EndpointCommand::RenameHeaderParameter(command) => {
+        validation.require(
+          validation.header_parameter_exists(&command.parameter_id),
+          "header parameter must exist to rename header parameter",
+        )?;
+        validation.require(
+          !validation.header_parameter_name_conflicts(&command.parameter_id, &command.name),
+          "header parameter name must not be used by another header parameter of the request",
+        )?;
+
+        vec![EndpointEvent::from(
+          endpoint_events::HeaderParameterRenamed::from(command),
+        )]
+      }
+
+      EndpointCommand::UnsetHeaderParameterShape(command) => {
+        validation.require(
+          validation.header_parameter_exists(&command.parameter_id),
+          "header parameter must exist to unset header parameter shape",
+        )?;
+
+        vec![EndpointEvent::from(
+          endpoint_events::HeaderParameterShapeUnset::from(command),
+        )]
+      }
+
+      EndpointCommand::RemoveHeaderParameter(command) => {
+        validation.require(
+          validation.header_parameter_exists(&command.parameter_id),
+          "header parameter must exist to be removed",
+        )?;
+
+        vec![EndpointEvent::from(
+          endpoint_events::HeaderParameterRemoved::from(command),
+        )]
+      }
+
       _ => Err(SpecCommandError::Unimplemented(
         "endpoint command not implemented for endpoint projection",
         SpecCommand::EndpointCommand(self),
@@ -776,6 +954,48 @@ impl<'a> CommandValidationQueries<'a> {
       .get_response_node_index(response_id)
       .is_some()
   }
+
+  pub fn header_parameter_exists(&self, parameter_id: &RequestParameterId) -> bool {
+    self
+      .endpoint_projection
+      .get_header_parameter_node_index(parameter_id)
+      .is_some()
+  }
+
+  pub fn request_has_header_parameter_named(&self, request_id: &RequestId, name: &str) -> bool {
+    self
+      .endpoint_projection
+      .get_header_parameter_nodes(request_id)
+      .expect("request should exist")
+      .any(|(_, descriptor)| descriptor.name.eq_ignore_ascii_case(name))
+  }
+
+  pub fn response_has_header_parameter_named(&self, response_id: &ResponseId, name: &str) -> bool {
+    self
+      .endpoint_projection
+      .get_header_parameter_nodes(response_id)
+      .expect("response should exist")
+      .any(|(_, descriptor)| descriptor.name.eq_ignore_ascii_case(name))
+  }
+
+  pub fn header_parameter_name_conflicts(
+    &self,
+    parameter_id: &RequestParameterId,
+    name: &str,
+  ) -> bool {
+    let parent_id = self
+      .endpoint_projection
+      .get_header_parameter_parent_id(parameter_id)
+      .expect("header parameter should exist");
+
+    self
+      .endpoint_projection
+      .get_header_parameter_nodes(parent_id)
+      .expect("parent of header parameter should exist")
+      .any(|(other_parameter_id, descriptor)| {
+        other_parameter_id != parameter_id && descriptor.name.eq_ignore_ascii_case(name)
+      })
+  }
 }
 
 impl<'a> From<(&'a EndpointProjection, &EndpointCommand)> for CommandValidationQueries<'a> {
@@ -1519,4 +1739,307 @@ mod test {
       projection.apply(event); // verify this doesn't panic goes a long way to verifying the events
     }
   }
+
+  #[test]
+  pub fn can_handle_add_header_parameter_command() {
+    let initial_events: Vec<EndpointEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded": {"pathId": "path_1","parentPathId": "root","name": "todos"}},
+      {"RequestAdded": {"requestId": "request_1", "pathId": "path_1", "httpMethod": "GET"}},
+      {"HeaderParameterAdded": {"parameterId": "header_1", "requestId": "request_1", "name": "X-Request-Id"}}
+    ]))
+    .expect("initial events should be valid endpoint events");
+
+    let mut projection = EndpointProjection::from(initial_events);
+
+    let valid_command: EndpointCommand = serde_json::from_value(json!(
+      {"AddHeaderParameter": {"parameterId": "header_2", "requestId": "request_1", "name": "Authorization"}}
+    ))
+    .expect("example command should be a valid command");
+
+    let new_events = projection
+      .execute(valid_command)
+      .expect("valid command should yield new events");
+    assert_eq!(new_events.len(), 1);
+    assert_debug_snapshot!(
+      "can_handle_add_header_parameter_command__new_events",
+      new_events
+    );
+
+    let unassignable_parameter: EndpointCommand = serde_json::from_value(json!(
+      {"AddHeaderParameter": {"parameterId": "header_1", "requestId": "request_1", "name": "Authorization"}}
+    ))
+    .unwrap();
+    let unassignable_parameter_result = projection.execute(unassignable_parameter);
+    assert!(unassignable_parameter_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_add_header_parameter_command__unassignable_parameter_result",
+      unassignable_parameter_result.unwrap_err()
+    );
+
+    let unexisting_request: EndpointCommand = serde_json::from_value(json!(
+      {"AddHeaderParameter": {"parameterId": "header_2", "requestId": "not-a-request", "name": "Authorization"}}
+    ))
+    .unwrap();
+    let unexisting_request_result = projection.execute(unexisting_request);
+    assert!(unexisting_request_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_add_header_parameter_command__unexisting_request_result",
+      unexisting_request_result.unwrap_err()
+    );
+
+    let duplicate_name: EndpointCommand = serde_json::from_value(json!(
+      {"AddHeaderParameter": {"parameterId": "header_2", "requestId": "request_1", "name": "x-request-id"}}
+    ))
+    .unwrap();
+    let duplicate_name_result = projection.execute(duplicate_name);
+    assert!(duplicate_name_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_add_header_parameter_command__duplicate_name_result",
+      duplicate_name_result.unwrap_err()
+    );
+
+    for event in new_events {
+      projection.apply(event); // verify this doesn't panic goes a long way to verifying the events
+    }
+  }
+
+  #[test]
+  pub fn can_handle_add_response_header_parameter_command() {
+    let initial_events: Vec<EndpointEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded": {"pathId": "path_1","parentPathId": "root","name": "todos"}},
+      {"ResponseAddedByPathAndMethod": {"responseId": "response_1", "pathId": "path_1", "httpMethod": "GET", "httpStatusCode": 200}},
+      {"ResponseHeaderParameterAdded": {"parameterId": "header_1", "responseId": "response_1", "name": "X-Rate-Limit"}}
+    ]))
+    .expect("initial events should be valid endpoint events");
+
+    let mut projection = EndpointProjection::from(initial_events);
+
+    let valid_command: EndpointCommand = serde_json::from_value(json!(
+      {"AddResponseHeaderParameter": {"parameterId": "header_2", "responseId": "response_1", "name": "Location"}}
+    ))
+    .expect("example command should be a valid command");
+
+    let new_events = projection
+      .execute(valid_command)
+      .expect("valid command should yield new events");
+    assert_eq!(new_events.len(), 1);
+    assert_debug_snapshot!(
+      "can_handle_add_response_header_parameter_command__new_events",
+      new_events
+    );
+
+    let unexisting_response: EndpointCommand = serde_json::from_value(json!(
+      {"AddResponseHeaderParameter": {"parameterId": "header_2", "responseId": "not-a-response", "name": "Location"}}
+    ))
+    .unwrap();
+    let unexisting_response_result = projection.execute(unexisting_response);
+    assert!(unexisting_response_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_add_response_header_parameter_command__unexisting_response_result",
+      unexisting_response_result.unwrap_err()
+    );
+
+    let duplicate_name: EndpointCommand = serde_json::from_value(json!(
+      {"AddResponseHeaderParameter": {"parameterId": "header_2", "responseId": "response_1", "name": "x-rate-limit"}}
+    ))
+    .unwrap();
+    let duplicate_name_result = projection.execute(duplicate_name);
+    assert!(duplicate_name_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_add_response_header_parameter_command__duplicate_name_result",
+      duplicate_name_result.unwrap_err()
+    );
+
+    for event in new_events {
+      projection.apply(event); // verify this doesn't panic goes a long way to verifying the events
+    }
+  }
+
+  #[test]
+  pub fn can_handle_set_header_parameter_shape_command() {
+    let initial_events: Vec<EndpointEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded": {"pathId": "path_1","parentPathId": "root","name": "todos"}},
+      {"RequestAdded": {"requestId": "request_1", "pathId": "path_1", "httpMethod": "GET"}},
+      {"HeaderParameterAdded": {"parameterId": "header_1", "requestId": "request_1", "name": "X-Request-Id"}}
+    ]))
+    .expect("initial events should be valid endpoint events");
+
+    let mut projection = EndpointProjection::from(initial_events);
+
+    let valid_command: EndpointCommand = serde_json::from_value(json!(
+      {"SetHeaderParameterShape": {"parameterId": "header_1", "parameterDescriptor": { "shapeId": "shape_1", "isRemoved": false }}}
+    ))
+    .expect("example command should be a valid command");
+
+    let new_events = projection
+      .execute(valid_command)
+      .expect("valid command should yield new events");
+    assert_eq!(new_events.len(), 1);
+    assert_debug_snapshot!(
+      "can_handle_set_header_parameter_shape_command__new_events",
+      new_events
+    );
+
+    let unexisting_parameter: EndpointCommand = serde_json::from_value(json!(
+      {"SetHeaderParameterShape": {"parameterId": "not-a-parameter", "parameterDescriptor": { "shapeId": "shape_1", "isRemoved": false }}}
+    ))
+    .unwrap();
+    let unexisting_parameter_result = projection.execute(unexisting_parameter);
+    assert!(unexisting_parameter_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_set_header_parameter_shape_command__unexisting_parameter_result",
+      unexisting_parameter_result.unwrap_err()
+    );
+
+    for event in new_events {
+      projection.apply(event); // verify this doesn't panic goes a long way to verifying the events
+    }
+  }
+
+  #[test]
+  pub fn can_handle_rename_header_parameter_command() {
+    let initial_events: Vec<EndpointEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded": {"pathId": "path_1","parentPathId": "root","name": "todos"}},
+      {"RequestAdded": {"requestId": "request_1", "pathId": "path_1", "httpMethod": "GET"}},
+      {"HeaderParameterAdded": {"parameterId": "header_1", "requestId": "request_1", "name": "X-Request-Id"}},
+      {"HeaderParameterAdded": {"parameterId": "header_2", "requestId": "request_1", "name": "Authorization"}}
+    ]))
+    .expect("initial events should be valid endpoint events");
+
+    let mut projection = EndpointProjection::from(initial_events);
+
+    let valid_command: EndpointCommand = serde_json::from_value(json!(
+      {"RenameHeaderParameter": {"parameterId": "header_1", "name": "X-Correlation-Id"}}
+    ))
+    .expect("example command should be a valid command");
+
+    let new_events = projection
+      .execute(valid_command)
+      .expect("valid command should yield new events");
+    assert_eq!(new_events.len(), 1);
+    assert_debug_snapshot!(
+      "can_handle_rename_header_parameter_command__new_events",
+      new_events
+    );
+
+    let unexisting_parameter: EndpointCommand = serde_json::from_value(json!(
+      {"RenameHeaderParameter": {"parameterId": "not-a-parameter", "name": "X-Correlation-Id"}}
+    ))
+    .unwrap();
+    let unexisting_parameter_result = projection.execute(unexisting_parameter);
+    assert!(unexisting_parameter_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_rename_header_parameter_command__unexisting_parameter_result",
+      unexisting_parameter_result.unwrap_err()
+    );
+
+    let conflicting_name: EndpointCommand = serde_json::from_value(json!(
+      {"RenameHeaderParameter": {"parameterId": "header_1", "name": "authorization"}}
+    ))
+    .unwrap();
+    let conflicting_name_result = projection.execute(conflicting_name);
+    assert!(conflicting_name_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_rename_header_parameter_command__conflicting_name_result",
+      conflicting_name_result.unwrap_err()
+    );
+
+    for event in new_events {
+      projection.apply(event); // verify this doesn't panic goes a long way to verifying the events
+    }
+  }
+
+  #[test]
+  pub fn can_handle_unset_header_parameter_shape_command() {
+    let initial_events: Vec<EndpointEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded": {"pathId": "path_1","parentPathId": "root","name": "todos"}},
+      {"RequestAdded": {"requestId": "request_1", "pathId": "path_1", "httpMethod": "GET"}},
+      {"HeaderParameterAdded": {"parameterId": "header_1", "requestId": "request_1", "name": "X-Request-Id"}},
+      {"HeaderParameterShapeSet": {"parameterId": "header_1", "parameterDescriptor": { "shapeId": "shape_1", "isRemoved": false }}}
+    ]))
+    .expect("initial events should be valid endpoint events");
+
+    let mut projection = EndpointProjection::from(initial_events);
+
+    let valid_command: EndpointCommand = serde_json::from_value(json!(
+      {"UnsetHeaderParameterShape": {"parameterId": "header_1"}}
+    ))
+    .expect("example command should be a valid command");
+
+    let new_events = projection
+      .execute(valid_command)
+      .expect("valid command should yield new events");
+    assert_eq!(new_events.len(), 1);
+    assert_debug_snapshot!(
+      "can_handle_unset_header_parameter_shape_command__new_events",
+      new_events
+    );
+
+    let unexisting_parameter: EndpointCommand = serde_json::from_value(json!(
+      {"UnsetHeaderParameterShape": {"parameterId": "not-a-parameter"}}
+    ))
+    .unwrap();
+    let unexisting_parameter_result = projection.execute(unexisting_parameter);
+    assert!(unexisting_parameter_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_unset_header_parameter_shape_command__unexisting_parameter_result",
+      unexisting_parameter_result.unwrap_err()
+    );
+
+    for event in new_events {
+      projection.apply(event); // verify this doesn't panic goes a long way to verifying the events
+    }
+  }
+
+  #[test]
+  pub fn can_handle_remove_header_parameter_command() {
+    let initial_events: Vec<EndpointEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded": {"pathId": "path_1","parentPathId": "root","name": "todos"}},
+      {"RequestAdded": {"requestId": "request_1", "pathId": "path_1", "httpMethod": "GET"}},
+      {"HeaderParameterAdded": {"parameterId": "header_1", "requestId": "request_1", "name": "X-Request-Id"}},
+      {"HeaderParameterAdded": {"parameterId": "header_2", "requestId": "request_1", "name": "Authorization"}},
+      {"HeaderParameterRemoved": {"parameterId": "header_2"}}
+    ]))
+    .expect("initial events should be valid endpoint events");
+
+    let mut projection = EndpointProjection::from(initial_events);
+
+    let valid_command: EndpointCommand = serde_json::from_value(json!(
+      {"RemoveHeaderParameter": {"parameterId": "header_1"}}
+    ))
+    .expect("example command should be a valid command");
+
+    let new_events = projection
+      .execute(valid_command)
+      .expect("valid command should yield new events");
+    assert_eq!(new_events.len(), 1);
+    assert_debug_snapshot!(
+      "can_handle_remove_header_parameter_command__new_events",
+      new_events
+    );
+
+    let removed_parameter: EndpointCommand = serde_json::from_value(json!(
+      {"RemoveHeaderParameter": {"parameterId": "header_2"}}
+    ))
+    .unwrap();
+    let removed_parameter_result = projection.execute(removed_parameter);
+    assert!(removed_parameter_result.is_err());
+    assert_debug_snapshot!(
+      "can_handle_remove_header_parameter_command__removed_parameter_result",
+      removed_parameter_result.unwrap_err()
+    );
+
+    for event in new_events {
+      projection.apply(event); // verify this doesn't panic goes a long way to verifying the events
+    }
+
+    let re_added_name: EndpointCommand = serde_json::from_value(json!(
+      {"AddHeaderParameter": {"parameterId": "header_3", "requestId": "request_1", "name": "X-Request-Id"}}
+    ))
+    .unwrap();
+    assert!(
+      projection.execute(re_added_name).is_ok(),
+      "removed header parameters should free up their name"
+    );
+  }
 }
@@ -204,9 +204,13 @@ impl AggregateCommand<SpecProjection> for SpecCommand {
           _ => unreachable!(),
         };
 
-        let path_parameter_shape_set_event = EndpointEvent::from(
-          EndpointCommand::set_path_parameter_shape(path_parameter_added.path_id.clone(), shape_id),
-        );
+        let path_parameter_shape_set_event =
+          EndpointEvent::from(EndpointCommand::set_path_parameter_shape(
+            path_parameter_added.path_id.clone(),
+            shape_id,
+            None,
+            None,
+          ));
 
         vec![
           SpecEvent::from(path_parameter_added_event),
@@ -271,6 +275,20 @@ impl AggregateCommand<SpecProjection> for SpecCommand {
           .collect::<Vec<_>>()
       }
 
+      SpecCommand::EndpointCommand(EndpointCommand::SetHeaderParameterShape(command)) => {
+        spec_projection
+          .shape()
+          .execute(EndpointCommand::SetHeaderParameterShape(command.clone()))?; // validate shape exists
+        let endpoint_events = spec_projection
+          .endpoint()
+          .execute(EndpointCommand::SetHeaderParameterShape(command))?;
+
+        endpoint_events
+          .into_iter()
+          .map(|endpoint_event| SpecEvent::from(endpoint_event))
+          .collect::<Vec<_>>()
+      }
+
       // endpoint commands that can be purely handled by the endpoint projection
       SpecCommand::EndpointCommand(endpoint_command) => spec_projection
         .endpoint()
@@ -248,6 +248,15 @@ impl AggregateCommand<ShapeProjection> for EndpointCommand {
         vec![] // validation only
       }
 
+      EndpointCommand::SetHeaderParameterShape(command) => {
+        validation.require(
+          validation.shape_id_exists(&command.parameter_descriptor.shape_id),
+          "shape must exist to set the header parameter shape",
+        )?;
+
+        vec![] // validation only
+      }
+
       _ => Err(SpecCommandError::Unimplemented(
         "endpoint command not implemented for shape projection",
         SpecCommand::EndpointCommand(self),
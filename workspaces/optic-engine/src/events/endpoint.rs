@@ -35,6 +35,14 @@ pub enum EndpointEvent {
   RequestParameterShapeUnset(RequestParameterShapeUnset),
   RequestParameterRemoved(RequestParameterRemoved),
 
+  // header parameters
+  HeaderParameterAdded(HeaderParameterAdded),
+  ResponseHeaderParameterAdded(ResponseHeaderParameterAdded),
+  HeaderParameterShapeSet(HeaderParameterShapeSet),
+  HeaderParameterRenamed(HeaderParameterRenamed),
+  HeaderParameterShapeUnset(HeaderParameterShapeUnset),
+  HeaderParameterRemoved(HeaderParameterRemoved),
+
   // Request events
   RequestAdded(RequestAdded),
   RequestContentTypeSet(RequestContentTypeSet),
@@ -173,6 +181,54 @@ pub struct RequestParameterRemoved {
   pub event_context: Option<EventContext>,
 }
 
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)] // header parameters
+#[serde(rename_all = "camelCase")]
+pub struct HeaderParameterAdded {
+  pub parameter_id: RequestParameterId,
+  pub request_id: RequestId,
+  pub name: String,
+  pub event_context: Option<EventContext>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseHeaderParameterAdded {
+  pub parameter_id: RequestParameterId,
+  pub response_id: ResponseId,
+  pub name: String,
+  pub event_context: Option<EventContext>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderParameterShapeSet {
+  pub parameter_id: RequestParameterId,
+  pub parameter_descriptor: ShapedRequestParameterShapeDescriptor,
+  pub event_context: Option<EventContext>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderParameterRenamed {
+  pub parameter_id: RequestParameterId,
+  pub name: String,
+  pub event_context: Option<EventContext>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderParameterShapeUnset {
+  pub parameter_id: RequestParameterId,
+  pub event_context: Option<EventContext>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderParameterRemoved {
+  pub parameter_id: RequestParameterId,
+  pub event_context: Option<EventContext>,
+}
+
 #[derive(Deserialize, Debug, PartialEq, Serialize, Clone)] // Request events
 #[serde(rename_all = "camelCase")]
 pub struct RequestAdded {
@@ -285,6 +341,14 @@ impl Event for EndpointEvent {
       EndpointEvent::RequestParameterShapeUnset(evt) => evt.event_type(),
       EndpointEvent::RequestParameterRemoved(evt) => evt.event_type(),
 
+      // header parameters
+      EndpointEvent::HeaderParameterAdded(evt) => evt.event_type(),
+      EndpointEvent::ResponseHeaderParameterAdded(evt) => evt.event_type(),
+      EndpointEvent::HeaderParameterShapeSet(evt) => evt.event_type(),
+      EndpointEvent::HeaderParameterRenamed(evt) => evt.event_type(),
+      EndpointEvent::HeaderParameterShapeUnset(evt) => evt.event_type(),
+      EndpointEvent::HeaderParameterRemoved(evt) => evt.event_type(),
+
       // Request events
       EndpointEvent::RequestAdded(evt) => evt.event_type(),
       EndpointEvent::RequestContentTypeSet(evt) => evt.event_type(),
@@ -330,6 +394,14 @@ impl WithEventContext for EndpointEvent {
       EndpointEvent::RequestParameterShapeUnset(evt) => evt.event_context.replace(event_context),
       EndpointEvent::RequestParameterRemoved(evt) => evt.event_context.replace(event_context),
 
+      // header parameters
+      EndpointEvent::HeaderParameterAdded(evt) => evt.event_context.replace(event_context),
+      EndpointEvent::ResponseHeaderParameterAdded(evt) => evt.event_context.replace(event_context),
+      EndpointEvent::HeaderParameterShapeSet(evt) => evt.event_context.replace(event_context),
+      EndpointEvent::HeaderParameterRenamed(evt) => evt.event_context.replace(event_context),
+      EndpointEvent::HeaderParameterShapeUnset(evt) => evt.event_context.replace(event_context),
+      EndpointEvent::HeaderParameterRemoved(evt) => evt.event_context.replace(event_context),
+
       // Request events
       EndpointEvent::RequestAdded(evt) => evt.event_context.replace(event_context),
       EndpointEvent::RequestContentTypeSet(evt) => evt.event_context.replace(event_context),
@@ -438,6 +510,42 @@ impl Event for RequestParameterRemoved {
   }
 }
 
+impl Event for HeaderParameterAdded {
+  fn event_type(&self) -> &'static str {
+    "HeaderParameterAdded"
+  }
+}
+
+impl Event for ResponseHeaderParameterAdded {
+  fn event_type(&self) -> &'static str {
+    "ResponseHeaderParameterAdded"
+  }
+}
+
+impl Event for HeaderParameterShapeSet {
+  fn event_type(&self) -> &'static str {
+    "HeaderParameterShapeSet"
+  }
+}
+
+impl Event for HeaderParameterRenamed {
+  fn event_type(&self) -> &'static str {
+    "HeaderParameterRenamed"
+  }
+}
+
+impl Event for HeaderParameterShapeUnset {
+  fn event_type(&self) -> &'static str {
+    "HeaderParameterShapeUnset"
+  }
+}
+
+impl Event for HeaderParameterRemoved {
+  fn event_type(&self) -> &'static str {
+    "HeaderParameterRemoved"
+  }
+}
+
 impl Event for RequestAdded {
   fn event_type(&self) -> &'static str {
     "RequestAdded"
@@ -564,6 +672,42 @@ impl From<QueryParametersRemoved> for EndpointEvent {
   }
 }
 
+impl From<HeaderParameterAdded> for EndpointEvent {
+  fn from(event: HeaderParameterAdded) -> Self {
+    Self::HeaderParameterAdded(event)
+  }
+}
+
+impl From<ResponseHeaderParameterAdded> for EndpointEvent {
+  fn from(event: ResponseHeaderParameterAdded) -> Self {
+    Self::ResponseHeaderParameterAdded(event)
+  }
+}
+
+impl From<HeaderParameterShapeSet> for EndpointEvent {
+  fn from(event: HeaderParameterShapeSet) -> Self {
+    Self::HeaderParameterShapeSet(event)
+  }
+}
+
+impl From<HeaderParameterRenamed> for EndpointEvent {
+  fn from(event: HeaderParameterRenamed) -> Self {
+    Self::HeaderParameterRenamed(event)
+  }
+}
+
+impl From<HeaderParameterShapeUnset> for EndpointEvent {
+  fn from(event: HeaderParameterShapeUnset) -> Self {
+    Self::HeaderParameterShapeUnset(event)
+  }
+}
+
+impl From<HeaderParameterRemoved> for EndpointEvent {
+  fn from(event: HeaderParameterRemoved) -> Self {
+    Self::HeaderParameterRemoved(event)
+  }
+}
+
 impl From<RequestAdded> for EndpointEvent {
   fn from(event: RequestAdded) -> Self {
     Self::RequestAdded(event)
@@ -636,6 +780,24 @@ impl From<EndpointCommand> for EndpointEvent {
       EndpointCommand::RemoveQueryParameters(command) => {
         EndpointEvent::from(QueryParametersRemoved::from(command))
       }
+      EndpointCommand::AddHeaderParameter(command) => {
+        EndpointEvent::from(HeaderParameterAdded::from(command))
+      }
+      EndpointCommand::AddResponseHeaderParameter(command) => {
+        EndpointEvent::from(ResponseHeaderParameterAdded::from(command))
+      }
+      EndpointCommand::SetHeaderParameterShape(command) => {
+        EndpointEvent::from(HeaderParameterShapeSet::from(command))
+      }
+      EndpointCommand::RenameHeaderParameter(command) => {
+        EndpointEvent::from(HeaderParameterRenamed::from(command))
+      }
+      EndpointCommand::UnsetHeaderParameterShape(command) => {
+        EndpointEvent::from(HeaderParameterShapeUnset::from(command))
+      }
+      EndpointCommand::RemoveHeaderParameter(command) => {
+        EndpointEvent::from(HeaderParameterRemoved::from(command))
+      }
       EndpointCommand::AddRequest(command) => EndpointEvent::from(RequestAdded::from(command)),
       EndpointCommand::SetRequestBodyShape(command) => {
         EndpointEvent::from(RequestBodySet::from(command))
@@ -759,6 +921,66 @@ impl From<endpoint_commands::RemoveQueryParameters> for QueryParametersRemoved {
   }
 }
 
+impl From<endpoint_commands::AddHeaderParameter> for HeaderParameterAdded {
+  fn from(command: endpoint_commands::AddHeaderParameter) -> Self {
+    Self {
+      parameter_id: command.parameter_id,
+      request_id: command.request_id,
+      name: command.name,
+      event_context: None,
+    }
+  }
+}
+
+impl From<endpoint_commands::AddResponseHeaderParameter> for ResponseHeaderParameterAdded {
+  fn from(command: endpoint_commands::AddResponseHeaderParameter) -> Self {
+    Self {
+      parameter_id: command.parameter_id,
+      response_id: command.response_id,
+      name: command.name,
+      event_context: None,
+    }
+  }
+}
+
+impl From<endpoint_commands::SetHeaderParameterShape> for HeaderParameterShapeSet {
+  fn from(command: endpoint_commands::SetHeaderParameterShape) -> Self {
+    Self {
+      parameter_id: command.parameter_id,
+      parameter_descriptor: command.parameter_descriptor,
+      event_context: None,
+    }
+  }
+}
+
+impl From<endpoint_commands::RenameHeaderParameter> for HeaderParameterRenamed {
+  fn from(command: endpoint_commands::RenameHeaderParameter) -> Self {
+    Self {
+      parameter_id: command.parameter_id,
+      name: command.name,
+      event_context: None,
+    }
+  }
+}
+
+impl From<endpoint_commands::UnsetHeaderParameterShape> for HeaderParameterShapeUnset {
+  fn from(command: endpoint_commands::UnsetHeaderParameterShape) -> Self {
+    Self {
+      parameter_id: command.parameter_id,
+      event_context: None,
+    }
+  }
+}
+
+impl From<endpoint_commands::RemoveHeaderParameter> for HeaderParameterRemoved {
+  fn from(command: endpoint_commands::RemoveHeaderParameter) -> Self {
+    Self {
+      parameter_id: command.parameter_id,
+      event_context: None,
+    }
+  }
+}
+
 impl From<endpoint_commands::AddRequest> for RequestAdded {
   fn from(command: endpoint_commands::AddRequest) -> Self {
     Self {
@@ -1,6 +1,8 @@
 use super::EventLoadingError;
 use crate::shapehash;
-use crate::state::body::{BodyDescriptor, ParsedQueryString};
+use crate::state::body::{
+  BodyDescriptor, ParsedHeaders, ParsedMultipartForm, ParsedQueryString, ParsedXmlDocument,
+};
 use base64;
 use cqrs_core::Event;
 use protobuf::Message;
@@ -53,6 +55,20 @@ pub struct Body {
   pub value: ArbitraryData,
 }
 
+impl Body {
+  /// The content type to document, leaving out the boundary of multipart bodies, which changes
+  /// with every request.
+  pub fn documented_content_type(&self) -> Option<&str> {
+    let content_type = self.content_type.as_deref()?;
+    let essence = content_type_essence(content_type);
+    if essence.eq_ignore_ascii_case("multipart/form-data") {
+      Some(essence)
+    } else {
+      Some(content_type)
+    }
+  }
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ArbitraryData {
@@ -109,6 +125,44 @@ impl From<&ArbitraryData> for Option<BodyDescriptor> {
   }
 }
 
+impl From<&Body> for Option<BodyDescriptor> {
+  fn from(body: &Body) -> Self {
+    // forms and xml are described like their fields, falling back to the text of those failing
+    // to parse
+    let text_body = match (&body.content_type, &body.value.as_text) {
+      (Some(content_type), Some(text)) => {
+        let essence = content_type_essence(content_type);
+        if essence.eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+          ParsedQueryString::from_str(text)
+            .ok()
+            .map(BodyDescriptor::from)
+        } else if essence.eq_ignore_ascii_case("multipart/form-data") {
+          ParsedMultipartForm::from_str(content_type, text).map(BodyDescriptor::from)
+        } else if is_xml_content_type(essence) {
+          ParsedXmlDocument::from_str(text)
+            .ok()
+            .map(BodyDescriptor::from)
+        } else {
+          None
+        }
+      }
+      _ => None,
+    };
+
+    text_body.or_else(|| (&body.value).into())
+  }
+}
+
+/// The media type of a content type, without parameters like its charset or boundary
+fn content_type_essence(content_type: &str) -> &str {
+  content_type.split(';').next().unwrap_or("").trim()
+}
+
+fn is_xml_content_type(essence: &str) -> bool {
+  let essence = essence.to_ascii_lowercase();
+  essence == "application/xml" || essence == "text/xml" || essence.ends_with("+xml")
+}
+
 impl From<&QueryParametersData> for Option<BodyDescriptor> {
   fn from(query_param_data: &QueryParametersData) -> Self {
     let data = &query_param_data.data;
@@ -123,6 +177,20 @@ impl From<&QueryParametersData> for Option<BodyDescriptor> {
   }
 }
 
+impl From<&ArbitraryData> for ParsedHeaders {
+  fn from(data: &ArbitraryData) -> Self {
+    if let Some(json_string) = &data.as_json_string {
+      let json: serde_json::Value = serde_json::from_str(json_string)
+        .expect("as_json_string of headers ArbitraryData should always be valid json");
+      ParsedHeaders::from_json(json)
+    } else if let Some(text) = &data.as_text {
+      ParsedHeaders::from_text(text)
+    } else {
+      ParsedHeaders::default()
+    }
+  }
+}
+
 impl From<shapehash::ShapeDescriptor> for serde_json::value::Value {
   fn from(mut shape_descriptor: shapehash::ShapeDescriptor) -> serde_json::value::Value {
     use serde_json::map::Map;
@@ -183,17 +251,249 @@ impl HttpInteraction {
   }
 }
 
+mod har {
+  use super::*;
+  use uuid::Uuid;
+
+  // Only the parts of HAR 1.2 (http://www.softwareishard.com/blog/har-12-spec/) needed to
+  // describe an interaction, everything else is ignored.
+
+  #[derive(Deserialize)]
+  struct HarDocument {
+    log: HarLog,
+  }
+
+  #[derive(Deserialize)]
+  struct HarLog {
+    entries: Vec<HarEntry>,
+  }
+
+  #[derive(Deserialize)]
+  struct HarEntry {
+    request: HarRequest,
+    response: HarResponse,
+  }
+
+  #[derive(Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: Vec<HarNameValue>,
+    #[serde(default)]
+    query_string: Vec<HarNameValue>,
+    post_data: Option<HarPostData>,
+  }
+
+  #[derive(Deserialize)]
+  struct HarResponse {
+    status: u16,
+    #[serde(default)]
+    headers: Vec<HarNameValue>,
+    content: HarContent,
+  }
+
+  #[derive(Deserialize, Serialize)]
+  struct HarNameValue {
+    name: String,
+    value: String,
+  }
+
+  #[derive(Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct HarPostData {
+    mime_type: String,
+    text: Option<String>,
+    #[serde(default)]
+    params: Vec<HarNameValue>,
+  }
+
+  #[derive(Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct HarContent {
+    mime_type: Option<String>,
+    text: Option<String>,
+    encoding: Option<String>,
+  }
+
+  impl HttpInteraction {
+    /// Read the interactions recorded by the entries of a HAR document. Entries without a
+    /// response (status 0, for example for blocked or aborted requests) are left out.
+    pub fn from_har_str(json: &str) -> Result<Vec<Self>, serde_json::Error> {
+      let document: HarDocument = serde_json::from_str(json)?;
+
+      let interactions = document
+        .log
+        .entries
+        .into_iter()
+        .filter(|entry| entry.response.status != 0)
+        .map(HttpInteraction::from)
+        .collect();
+
+      Ok(interactions)
+    }
+  }
+
+  impl From<HarEntry> for HttpInteraction {
+    fn from(entry: HarEntry) -> Self {
+      let HarEntry { request, response } = entry;
+
+      let (host, path, query_string) = split_url(&request.url);
+      let query_string = query_string
+        .map(String::from)
+        .or_else(|| url_encoded(&request.query_string));
+
+      let request_body = match request.post_data {
+        Some(HarPostData {
+          mime_type,
+          text,
+          params,
+        }) => body(Some(mime_type), text.or_else(|| url_encoded(&params))),
+        None => body(None, None),
+      };
+
+      let HarContent {
+        mime_type,
+        text,
+        encoding,
+      } = response.content;
+      let response_body = match encoding.as_deref() {
+        Some("base64") => {
+          let bytes = text.and_then(|text| base64::decode(text).ok());
+          match bytes.map(String::from_utf8) {
+            Some(Ok(text)) => body(mime_type, Some(text)),
+            // content that isn't text, like images, is kept by its content type only
+            Some(Err(_)) => Body {
+              content_type: mime_type.filter(|mime_type| !mime_type.is_empty()),
+              value: ArbitraryData::default(),
+            },
+            None => body(mime_type, None),
+          }
+        }
+        _ => body(mime_type, text),
+      };
+
+      Self {
+        uuid: Uuid::new_v4().to_hyphenated().to_string(),
+        request: Request {
+          host: String::from(host),
+          method: request.method,
+          path: String::from(path),
+          headers: headers(request.headers),
+          query: QueryParametersData {
+            data: ArbitraryData {
+              as_text: query_string,
+              ..ArbitraryData::default()
+            },
+          },
+          body: request_body,
+        },
+        response: Response {
+          status_code: response.status,
+          headers: headers(response.headers),
+          body: response_body,
+        },
+        tags: vec![],
+      }
+    }
+  }
+
+  /// Split an absolute url into its host, path and query string
+  fn split_url(url: &str) -> (&str, &str, Option<&str>) {
+    let url = url.split('#').next().unwrap_or_default();
+    let without_scheme = url.splitn(2, "://").last().unwrap_or_default();
+    let (authority, path_and_query) = match without_scheme.find('/') {
+      Some(path_start) => without_scheme.split_at(path_start),
+      None => (without_scheme, "/"),
+    };
+    let host = authority.rsplit('@').next().unwrap_or_default();
+
+    let mut path_and_query = path_and_query.splitn(2, '?');
+    let path = path_and_query.next().unwrap_or("/");
+    let query_string = path_and_query.next().filter(|query| !query.is_empty());
+
+    (host, path, query_string)
+  }
+
+  fn url_encoded(pairs: &[HarNameValue]) -> Option<String> {
+    if pairs.is_empty() {
+      return None;
+    }
+    let pairs = pairs
+      .iter()
+      .map(|pair| (pair.name.as_str(), pair.value.as_str()))
+      .collect::<Vec<_>>();
+    serde_urlencoded::to_string(pairs).ok()
+  }
+
+  fn headers(headers: Vec<HarNameValue>) -> ArbitraryData {
+    // HTTP/2 pseudo headers (like `:authority`) describe the request line, not headers
+    let headers = headers
+      .into_iter()
+      .filter(|header| !header.name.starts_with(':'))
+      .collect::<Vec<_>>();
+
+    ArbitraryData {
+      as_json_string: Some(
+        serde_json::to_string(&headers).expect("har headers should serialize to json"),
+      ),
+      ..ArbitraryData::default()
+    }
+  }
+
+  fn body(mime_type: Option<String>, text: Option<String>) -> Body {
+    let text = match text.filter(|text| !text.is_empty()) {
+      Some(text) => text,
+      None => {
+        return Body {
+          content_type: None,
+          value: ArbitraryData::default(),
+        }
+      }
+    };
+
+    let is_json = matches!(&mime_type, Some(mime_type) if mime_type.contains("json"))
+      && serde_json::from_str::<serde_json::Value>(&text).is_ok();
+    let value = if is_json {
+      ArbitraryData {
+        as_json_string: Some(text),
+        ..ArbitraryData::default()
+      }
+    } else {
+      ArbitraryData {
+        as_text: Some(text),
+        ..ArbitraryData::default()
+      }
+    };
+
+    Body {
+      content_type: mime_type.filter(|mime_type| !mime_type.is_empty()),
+      value,
+    }
+  }
+}
+
+#[cfg(feature = "avro")]
+pub use avro::{HttpInteractionAvroError, HttpInteractionAvroReader};
+
 #[cfg(feature = "avro")]
 mod avro {
   use super::*;
   use avro_rs;
+  use avro_rs::types::Value as AvroValue;
+  use avro_rs::{Codec, Schema};
+  use std::convert::TryFrom;
+  use std::io::{Cursor, Read};
+  use std::str::FromStr;
+  use thiserror::Error;
+
+  const CONTAINER_MAGIC: [u8; 4] = [b'O', b'b', b'j', 1u8];
 
   impl HttpInteraction {
     pub fn from_avro() -> HttpInteractionAvroDeserializer {
-      let deserializer = HttpInteractionAvroDeserializer::new(&INTERACTION_AVRO_SCHEMA)
-        .expect("interaction avro schema should be valid");
-
-      deserializer
+      HttpInteractionAvroDeserializer::new(INTERACTION_AVRO_SCHEMA)
+        .expect("interaction avro schema should be valid")
     }
   }
 
@@ -207,13 +507,186 @@ mod avro {
       Ok(Self { schema })
     }
 
-    pub fn reader<'a, R>(&'a mut self, source: R) -> Result<avro_rs::Reader<'a, R>, avro_rs::Error>
+    /// Read the interactions from an Avro object container file. The writer schema of capture
+    /// files refers to named types, which avro_rs can't parse, so every block is decoded with
+    /// our own copy of that schema instead.
+    pub fn reader<'a, R>(
+      &'a mut self,
+      mut source: R,
+    ) -> Result<HttpInteractionAvroReader<'a, R>, HttpInteractionAvroError>
     where
       R: io::Read,
       R: 'a,
     {
-      avro_rs::Reader::with_schema(&self.schema, source)
+      let mut magic = [0u8; 4];
+      source.read_exact(&mut magic)?;
+      if magic != CONTAINER_MAGIC {
+        return Err(HttpInteractionAvroError::Container(
+          "missing object container file magic",
+        ));
+      }
+
+      let metadata =
+        match avro_rs::from_avro_datum(&Schema::Map(Box::new(Schema::Bytes)), &mut source, None)? {
+          AvroValue::Map(metadata) => metadata,
+          _ => unreachable!("metadata should decode as a map"),
+        };
+      let codec = match metadata.get("avro.codec") {
+        Some(AvroValue::Bytes(codec)) => std::str::from_utf8(codec)
+          .ok()
+          .and_then(|codec| Codec::from_str(codec).ok())
+          .ok_or(HttpInteractionAvroError::Container("unsupported codec"))?,
+        _ => Codec::Null,
+      };
+
+      let mut sync_marker = [0u8; 16];
+      source.read_exact(&mut sync_marker)?;
+
+      Ok(HttpInteractionAvroReader {
+        schema: &self.schema,
+        source,
+        codec,
+        sync_marker,
+        block: Cursor::new(vec![]),
+        block_remaining_count: 0,
+        batch_items: vec![].into_iter(),
+        errored: false,
+      })
+    }
+  }
+
+  pub struct HttpInteractionAvroReader<'a, R> {
+    schema: &'a Schema,
+    source: R,
+    codec: Codec,
+    sync_marker: [u8; 16],
+    block: Cursor<Vec<u8>>,
+    block_remaining_count: i64,
+    batch_items: std::vec::IntoIter<HttpInteraction>,
+    errored: bool,
+  }
+
+  #[derive(Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct InteractionBatch {
+    batch_items: Vec<HttpInteraction>,
+  }
+
+  impl<'a, R: io::Read> HttpInteractionAvroReader<'a, R> {
+    fn read_next(&mut self) -> Result<Option<HttpInteraction>, HttpInteractionAvroError> {
+      loop {
+        if let Some(interaction) = self.batch_items.next() {
+          return Ok(Some(interaction));
+        }
+
+        if self.block_remaining_count == 0 && !self.read_block()? {
+          return Ok(None);
+        }
+
+        // going through json, as avro_rs can't deserialize flattened or narrowed fields
+        let value = avro_rs::from_avro_datum(self.schema, &mut self.block, None)?;
+        let batch: InteractionBatch = serde_json::from_value(serde_json::Value::try_from(value)?)?;
+        self.block_remaining_count -= 1;
+        self.batch_items = batch.batch_items.into_iter();
+      }
     }
+
+    /// Read the next non-empty data block, returning false when the source has been exhausted
+    fn read_block(&mut self) -> Result<bool, HttpInteractionAvroError> {
+      loop {
+        let count = match read_long(&mut self.source)? {
+          Some(count) => count,
+          None => return Ok(false),
+        };
+        let size = read_long(&mut self.source)?.ok_or(HttpInteractionAvroError::Container(
+          "data block ended before its size",
+        ))?;
+
+        if size < 0 {
+          return Err(HttpInteractionAvroError::Container(
+            "data block has a negative size",
+          ));
+        }
+
+        // sizes are only trusted as far as the source has the bytes for them
+        let mut block = vec![];
+        (&mut self.source)
+          .take(size as u64)
+          .read_to_end(&mut block)?;
+        if block.len() as u64 != size as u64 {
+          return Err(HttpInteractionAvroError::Container(
+            "data block ended before its size",
+          ));
+        }
+        self.codec.decompress(&mut block)?;
+
+        let mut sync_marker = [0u8; 16];
+        self.source.read_exact(&mut sync_marker)?;
+        if sync_marker != self.sync_marker {
+          return Err(HttpInteractionAvroError::Container(
+            "data block not followed by the sync marker",
+          ));
+        }
+
+        if count > 0 {
+          self.block = Cursor::new(block);
+          self.block_remaining_count = count;
+          return Ok(true);
+        }
+      }
+    }
+  }
+
+  impl<'a, R: io::Read> Iterator for HttpInteractionAvroReader<'a, R> {
+    type Item = Result<HttpInteraction, HttpInteractionAvroError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+      // reading after an error would only produce garbage
+      if self.errored {
+        return None;
+      }
+      match self.read_next() {
+        Ok(interaction) => interaction.map(Ok),
+        Err(err) => {
+          self.errored = true;
+          Some(Err(err))
+        }
+      }
+    }
+  }
+
+  /// Read a zig-zag encoded long, or nothing when the source is exhausted before it starts
+  fn read_long(source: &mut impl io::Read) -> Result<Option<i64>, HttpInteractionAvroError> {
+    let mut encoded: u64 = 0;
+    for (index, shift) in (0..64).step_by(7).enumerate() {
+      let mut byte = [0u8; 1];
+      if source.read(&mut byte)? == 0 {
+        return if index == 0 {
+          Ok(None)
+        } else {
+          Err(HttpInteractionAvroError::Container(
+            "long ended unexpectedly",
+          ))
+        };
+      }
+      encoded |= u64::from(byte[0] & 0x7f) << shift;
+      if byte[0] & 0x80 == 0 {
+        return Ok(Some((encoded >> 1) as i64 ^ -((encoded & 1) as i64)));
+      }
+    }
+    Err(HttpInteractionAvroError::Container("long is too long"))
+  }
+
+  #[derive(Debug, Error)]
+  pub enum HttpInteractionAvroError {
+    #[error("io error: {}", .0)]
+    Io(#[from] io::Error),
+    #[error("avro error: {}", .0)]
+    Avro(#[from] avro_rs::Error),
+    #[error("interaction could not be deserialized: {}", .0)]
+    Json(#[from] serde_json::Error),
+    #[error("invalid avro container file: {}", .0)]
+    Container(&'static str),
   }
 
   const INTERACTION_AVRO_SCHEMA: &str = r#"{
@@ -7,6 +7,7 @@ use serde_json;
 use std::fs;
 use std::io;
 use std::path::Path;
+use thiserror::Error;
 
 pub mod endpoint;
 pub mod http_interaction;
@@ -22,6 +23,9 @@ pub use spec_chunk::SpecChunkEvent;
 
 use crate::CommandContext;
 
+/// Stands in for the id of a batch commit to refer to the spec before any batch was committed
+pub const ROOT_BATCH_ID: &str = "root";
+
 #[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
 #[serde(untagged)]
 pub enum SpecEvent {
@@ -58,6 +62,31 @@ impl SpecEvent {
 
     Ok(events)
   }
+
+  /// The events up to and including the end of the batch commit with the given id, describing
+  /// the spec as it was right after that commit. `root` leaves out every batch commit.
+  pub fn until_batch_commit<'a>(
+    events: &'a [SpecEvent],
+    batch_id: &str,
+  ) -> Result<&'a [SpecEvent], UnknownBatchCommitError> {
+    let end_index = if batch_id == ROOT_BATCH_ID {
+      events
+        .iter()
+        .position(|event| matches!(event, SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(_))))
+        .unwrap_or(events.len())
+    } else {
+      events
+        .iter()
+        .position(|event| match event {
+          SpecEvent::RfcEvent(RfcEvent::BatchCommitEnded(e)) => e.batch_id == batch_id,
+          _ => false,
+        })
+        .map(|index| index + 1)
+        .ok_or_else(|| UnknownBatchCommitError(String::from(batch_id)))?
+    };
+
+    Ok(&events[..end_index])
+  }
 }
 
 impl From<EndpointEvent> for SpecEvent {
@@ -78,6 +107,10 @@ impl From<ShapeEvent> for SpecEvent {
   }
 }
 
+#[derive(Debug, Error)]
+#[error("no batch commit with id '{}' exists in the spec", .0)]
+pub struct UnknownBatchCommitError(pub String);
+
 #[derive(Debug)]
 pub enum EventLoadingError {
   #[cfg(feature = "avro")]
@@ -149,3 +182,18 @@ impl From<CommandContext> for EventContext {
 pub trait WithEventContext {
   fn with_event_context(&mut self, event_context: EventContext);
 }
+
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+  use super::SpecEvent;
+
+  /// Todo endpoints, added in batch commit `batch_1` and changed in `batch_2`
+  pub fn todo_batch_commit_events() -> Vec<SpecEvent> {
+    SpecEvent::from_file(
+      std::env::current_dir()
+        .unwrap()
+        .join("tests/fixtures/todos-batch-commits-spec.json"),
+    )
+    .expect("todo batch commits fixture should contain spec events")
+  }
+}
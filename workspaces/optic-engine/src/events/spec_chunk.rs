@@ -1,4 +1,4 @@
-use super::{RfcEvent, SpecEvent};
+use super::{RfcEvent, SpecEvent, ROOT_BATCH_ID};
 use cqrs_core::Event;
 use serde_json;
 use std::convert::TryFrom;
@@ -45,6 +45,66 @@ impl SpecChunkEvent {
     SpecChunkEvent::from((String::from("root"), true, events))
   }
 
+  /// Merge the events of two versions of a chunk that were both changed since a common base
+  /// version, like when merging git branches. Both versions are expected to only have appended
+  /// events to the base. The batches appended on their side are added after ours, with the first
+  /// of them re-parented onto our last batch, keeping the history of the chunk linear.
+  pub fn merge_events(
+    base: Vec<SpecEvent>,
+    ours: Vec<SpecEvent>,
+    theirs: Vec<SpecEvent>,
+  ) -> Result<Vec<SpecEvent>, &'static str> {
+    if !ours.starts_with(&base) || !theirs.starts_with(&base) {
+      return Err("Chunk events were changed instead of appended to");
+    }
+    if theirs.len() == base.len() || ours == theirs {
+      return Ok(ours);
+    }
+    if ours.len() == base.len() {
+      return Ok(theirs);
+    }
+
+    let base_batch_id = last_batch_id(&base).unwrap_or(ROOT_BATCH_ID).to_owned();
+    let our_batch_ids: Vec<&String> = ours[base.len()..]
+      .iter()
+      .filter_map(|event| match event {
+        SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(e)) => Some(&e.batch_id),
+        _ => None,
+      })
+      .collect();
+    let our_last_batch_id = last_batch_id(&ours).unwrap_or(ROOT_BATCH_ID).to_owned();
+
+    let mut their_events = vec![];
+    let mut skipped_batch_id: Option<String> = None;
+    for event in theirs.into_iter().skip(base.len()) {
+      match (&skipped_batch_id, event) {
+        // batches both sides already have, from having merged the same changes before
+        (None, SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(e)))
+          if our_batch_ids.contains(&&e.batch_id) =>
+        {
+          skipped_batch_id = Some(e.batch_id);
+        }
+        (Some(skipped_id), SpecEvent::RfcEvent(RfcEvent::BatchCommitEnded(e)))
+          if *skipped_id == e.batch_id =>
+        {
+          skipped_batch_id = None;
+        }
+        (Some(_), _) => {}
+        (None, SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(mut e)))
+          if e.parent_id.as_deref() == Some(&base_batch_id) =>
+        {
+          e.parent_id = Some(our_last_batch_id.clone());
+          their_events.push(SpecEvent::from(RfcEvent::BatchCommitStarted(e)));
+        }
+        (None, event) => their_events.push(event),
+      }
+    }
+
+    let mut events = ours;
+    events.append(&mut their_events);
+    Ok(events)
+  }
+
   pub fn events(&self) -> &Vec<SpecEvent> {
     let events = match self {
       SpecChunkEvent::Root(chunk) => &chunk.events,
@@ -124,6 +184,13 @@ impl RootChunkEvent {
   }
 }
 
+fn last_batch_id(events: &[SpecEvent]) -> Option<&str> {
+  events.iter().rev().find_map(|event| match event {
+    SpecEvent::RfcEvent(RfcEvent::BatchCommitEnded(e)) => Some(e.batch_id.as_str()),
+    _ => None,
+  })
+}
+
 // TODO: Implement this for an impl Iterator<Item=SpecEvent> rather than requiring a Vec
 impl From<(String, bool, Vec<SpecEvent>)> for SpecChunkEvent {
   fn from((name, is_root, events): (String, bool, Vec<SpecEvent>)) -> Self {
@@ -366,4 +433,62 @@ mod test {
       nested_batch_event_result.unwrap_err().0
     );
   }
+
+  #[test]
+  pub fn can_merge_events_appended_to_the_same_chunk() {
+    let events = |events_json| serde_json::from_value::<Vec<SpecEvent>>(events_json).unwrap();
+
+    let base = events(json!([
+      {"PathComponentAdded": {"pathId": "path_1","parentPathId": "root","name": "todos"}},
+      {"BatchCommitStarted": {"batchId": "batch-1", "parentId": "root", "commitMessage": "Add GET /todos" }},
+      {"RequestAdded": { "requestId": "request_1","pathId": "path_1","httpMethod": "GET" }},
+      {"BatchCommitEnded": { "batchId": "batch-1" }}
+    ]));
+    let ours_added = events(json!([
+      {"BatchCommitStarted": {"batchId": "batch-2", "parentId": "batch-1", "commitMessage": "Add POST /todos" }},
+      {"RequestAdded": { "requestId": "request_2","pathId": "path_1","httpMethod": "POST" }},
+      {"BatchCommitEnded": { "batchId": "batch-2" }}
+    ]));
+    let theirs_added = events(json!([
+      {"BatchCommitStarted": {"batchId": "batch-3", "parentId": "batch-1", "commitMessage": "Add PUT /todos" }},
+      {"RequestAdded": { "requestId": "request_3","pathId": "path_1","httpMethod": "PUT" }},
+      {"BatchCommitEnded": { "batchId": "batch-3" }},
+      {"BatchCommitStarted": {"batchId": "batch-4", "parentId": "batch-3", "commitMessage": "Add DELETE /todos" }},
+      {"RequestAdded": { "requestId": "request_4","pathId": "path_1","httpMethod": "DELETE" }},
+      {"BatchCommitEnded": { "batchId": "batch-4" }}
+    ]));
+
+    let ours = [base.clone(), ours_added.clone()].concat();
+    let theirs = [base.clone(), theirs_added.clone()].concat();
+
+    let merged = SpecChunkEvent::merge_events(base.clone(), ours.clone(), theirs.clone())
+      .expect("events appended on both sides should merge");
+
+    let reparented_theirs_added = events(json!([
+      {"BatchCommitStarted": {"batchId": "batch-3", "parentId": "batch-2", "commitMessage": "Add PUT /todos" }},
+      {"RequestAdded": { "requestId": "request_3","pathId": "path_1","httpMethod": "PUT" }},
+      {"BatchCommitEnded": { "batchId": "batch-3" }},
+      {"BatchCommitStarted": {"batchId": "batch-4", "parentId": "batch-3", "commitMessage": "Add DELETE /todos" }},
+      {"RequestAdded": { "requestId": "request_4","pathId": "path_1","httpMethod": "DELETE" }},
+      {"BatchCommitEnded": { "batchId": "batch-4" }}
+    ]));
+    assert_eq!(
+      merged,
+      [base.clone(), ours_added.clone(), reparented_theirs_added].concat()
+    );
+
+    // batches already merged into ours aren't added again
+    let remerged = SpecChunkEvent::merge_events(
+      ours.clone(),
+      merged.clone(),
+      [ours.clone(), theirs_added.clone()].concat(),
+    )
+    .expect("events appended on both sides should merge");
+    assert_eq!(remerged, merged);
+
+    let changed_base = events(json!([
+      {"PathComponentAdded": {"pathId": "path_1","parentPathId": "root","name": "tasks"}}
+    ]));
+    assert!(SpecChunkEvent::merge_events(base, changed_base, theirs).is_err());
+  }
 }
@@ -0,0 +1,207 @@
+use crate::events::HttpInteraction;
+use crate::shapes::JsonTrailPattern;
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Interactions and parts of bodies that are never documented and should be left out of diffs,
+/// like health checks or debugging info.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IgnoreRules {
+  /// Globs of request paths, where `*` matches within a path component and `**` across them
+  pub paths: Vec<Glob>,
+  /// Globs of request hosts, where `*` matches within a part of the domain and `**` across them
+  pub hosts: Vec<Glob>,
+  pub methods: Vec<String>,
+  /// Status codes, or ranges of them like `5xx`
+  pub status_codes: Vec<StatusCodePattern>,
+  /// Trails within request and response bodies, like `$.debug`
+  pub body_trails: Vec<JsonTrailPattern>,
+}
+
+impl IgnoreRules {
+  pub fn ignores_interaction(&self, interaction: &HttpInteraction) -> bool {
+    let request = &interaction.request;
+    self
+      .paths
+      .iter()
+      .any(|glob| glob.matches(&request.path, '/'))
+      || self
+        .hosts
+        .iter()
+        .any(|glob| glob.matches(&request.host, '.'))
+      || self
+        .methods
+        .iter()
+        .any(|method| method.eq_ignore_ascii_case(&request.method))
+      || self
+        .status_codes
+        .iter()
+        .any(|pattern| pattern.matches(interaction.response.status_code))
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(from = "String")]
+pub struct Glob(String);
+
+impl Glob {
+  /// Whether the whole of a value matches, with `*` not matching the separator
+  pub fn matches(&self, value: &str, separator: char) -> bool {
+    let pattern: Vec<char> = self.0.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    glob_matches(&pattern, &value, separator)
+  }
+}
+
+impl From<String> for Glob {
+  fn from(glob: String) -> Self {
+    Self(glob)
+  }
+}
+
+fn glob_matches(pattern: &[char], value: &[char], separator: char) -> bool {
+  match pattern {
+    [] => value.is_empty(),
+    // `/metrics/**` matches `/metrics` itself as well
+    [trailing_separator, '*', '*'] if *trailing_separator == separator && value.is_empty() => true,
+    ['*', '*', rest @ ..] => {
+      (0..=value.len()).any(|skipped| glob_matches(rest, &value[skipped..], separator))
+    }
+    ['*', rest @ ..] => (0..=value.len())
+      .take_while(|skipped| !value[..*skipped].contains(&separator))
+      .any(|skipped| glob_matches(rest, &value[skipped..], separator)),
+    [expected, rest @ ..] => match value {
+      [actual, value_rest @ ..] if actual == expected => glob_matches(rest, value_rest, separator),
+      _ => false,
+    },
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "RawStatusCodePattern")]
+pub enum StatusCodePattern {
+  Exact(u16),
+  /// All status codes starting with the digit, like `5xx`
+  Class(u16),
+}
+
+impl StatusCodePattern {
+  pub fn matches(&self, status_code: u16) -> bool {
+    match self {
+      StatusCodePattern::Exact(code) => *code == status_code,
+      StatusCodePattern::Class(class) => status_code / 100 == *class,
+    }
+  }
+}
+
+#[derive(Debug, Error)]
+#[error("invalid status code pattern '{0}', expected a status code or a class like '5xx'")]
+pub struct StatusCodePatternError(String);
+
+impl FromStr for StatusCodePattern {
+  type Err = StatusCodePatternError;
+
+  fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+    let error = || StatusCodePatternError(String::from(pattern));
+    let lowercase_pattern = pattern.to_ascii_lowercase();
+    match lowercase_pattern.strip_suffix("xx") {
+      Some(class) => match class.parse() {
+        Ok(class) if (1..=5).contains(&class) => Ok(StatusCodePattern::Class(class)),
+        _ => Err(error()),
+      },
+      None => match lowercase_pattern.parse() {
+        Ok(code) if (100..600).contains(&code) => Ok(StatusCodePattern::Exact(code)),
+        _ => Err(error()),
+      },
+    }
+  }
+}
+
+// status codes are numbers in most config files, but ranges of them can't be
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawStatusCodePattern {
+  Code(u16),
+  Pattern(String),
+}
+
+impl TryFrom<RawStatusCodePattern> for StatusCodePattern {
+  type Error = StatusCodePatternError;
+
+  fn try_from(raw_pattern: RawStatusCodePattern) -> Result<Self, Self::Error> {
+    match raw_pattern {
+      RawStatusCodePattern::Code(code) => code.to_string().parse(),
+      RawStatusCodePattern::Pattern(pattern) => pattern.parse(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn ignore_rules_match_interactions() {
+    let rules: IgnoreRules = serde_json::from_value(json!({
+      "paths": ["/health", "/metrics/**", "/internal/*/status"],
+      "hosts": ["*.internal.example.com"],
+      "methods": ["options"],
+      "statusCodes": ["5xx", 404],
+    }))
+    .expect("ignore rules should be valid");
+
+    let interaction = |host: &str, method: &str, path: &str, status_code: u16| {
+      serde_json::from_value::<HttpInteraction>(json!({
+        "uuid": "id",
+        "request": {
+          "host": host,
+          "method": method,
+          "path": path,
+          "query": {"shapeHashV1Base64": null, "asJsonString": null, "asText": null},
+          "headers": {"shapeHashV1Base64": null, "asJsonString": null, "asText": null},
+          "body": {"contentType": null, "value": {"shapeHashV1Base64": null, "asJsonString": null, "asText": null}}
+        },
+        "response": {
+          "statusCode": status_code,
+          "headers": {"shapeHashV1Base64": null, "asJsonString": null, "asText": null},
+          "body": {"contentType": null, "value": {"shapeHashV1Base64": null, "asJsonString": null, "asText": null}}
+        },
+        "tags": []
+      }))
+      .expect("interaction should be valid")
+    };
+    let ignores = |host, method, path, status_code| {
+      rules.ignores_interaction(&interaction(host, method, path, status_code))
+    };
+
+    assert!(ignores("example.com", "GET", "/health", 200));
+    assert!(!ignores("example.com", "GET", "/healthy", 200));
+    assert!(ignores("example.com", "GET", "/metrics", 200));
+    assert!(ignores("example.com", "GET", "/metrics/cpu/load", 200));
+    assert!(ignores("example.com", "GET", "/internal/jobs/status", 200));
+    assert!(!ignores(
+      "example.com",
+      "GET",
+      "/internal/jobs/1/status",
+      200
+    ));
+    assert!(ignores("api.internal.example.com", "GET", "/todos", 200));
+    assert!(!ignores(
+      "api.staging.internal.example.com",
+      "GET",
+      "/todos",
+      200
+    ));
+    assert!(ignores("example.com", "OPTIONS", "/todos", 200));
+    assert!(ignores("example.com", "GET", "/todos", 503));
+    assert!(ignores("example.com", "GET", "/todos", 404));
+    assert!(!ignores("example.com", "GET", "/todos", 400));
+
+    assert!(serde_json::from_value::<IgnoreRules>(json!({ "statusCodes": ["6xx"] })).is_err());
+    assert!(serde_json::from_value::<IgnoreRules>(json!({ "bodyTrails": ["debug"] })).is_err());
+  }
+}
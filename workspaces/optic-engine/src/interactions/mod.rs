@@ -1,20 +1,28 @@
 use crate::events::http_interaction::{Body, HttpInteraction};
 use crate::learn_shape::{observe_body_trails, TrailObservationsResult, TrailValues};
+use crate::projections::shape::ShapeProjection;
 use crate::projections::{EndpointProjection, SpecProjection};
 use crate::protos::shapehash::ShapeDescriptor;
 use crate::queries::endpoint::EndpointQueries;
-use crate::shapes::diff as diff_shape;
-use crate::shapes::ShapeDiffResult;
-use crate::state::body::BodyDescriptor;
+use crate::queries::shape::ShapeQueries;
+use crate::shapes::{diff as diff_shape, diff_ignoring_trails as diff_body_shape};
+use crate::shapes::{JsonTrail, ShapeDiffResult, ShapeTrail};
+use crate::state::body::{BodyDescriptor, ParsedHeaders};
+use crate::state::endpoint::StringFormat;
+use crate::state::shape::{ShapeId, ShapeKind};
+use serde::Deserialize;
+use uuid::Uuid;
 
+mod ignore;
 pub mod result;
 mod traverser;
 mod visitors;
 
-use result::InteractionTrail;
+pub use ignore::{Glob, IgnoreRules, StatusCodePattern, StatusCodePatternError};
 pub use result::{
   BodyAnalysisLocation, BodyAnalysisResult, InteractionDiffResult, UnmatchedQueryParameters,
 };
+use result::{InteractionTrail, MatchedPathParameter};
 use visitors::{InteractionVisitors, PathVisitor};
 
 /// Compute diffs based on a spec and an interaction.
@@ -29,7 +37,7 @@ pub fn diff(
 ) -> Vec<InteractionDiffResult> {
   let endpoint_projection = spec_projection.endpoint();
   let endpoint_queries = EndpointQueries::new(endpoint_projection);
-  let interaction_traverser = traverser::Traverser::new(&endpoint_queries);
+  let interaction_traverser = traverser::Traverser::new(&endpoint_queries, &config.ignore);
   let mut diff_visitors = visitors::diff::DiffVisitors::new();
 
   interaction_traverser.traverse(&http_interaction, &mut diff_visitors);
@@ -54,11 +62,25 @@ pub fn diff(
           })
           .collect()
       }
+      InteractionDiffResult::MatchedPathParameter(result) => {
+        diff_path_parameter(spec_projection.shape(), &result)
+          .into_iter()
+          .map(|shape_diff| {
+            InteractionDiffResult::UnmatchedPathParameterShape(
+              result.clone().into_shape_diff(shape_diff),
+            )
+          })
+          .collect()
+      }
       InteractionDiffResult::MatchedRequestBodyContentType(result) => {
         // eprintln!("shape diffing for matched a request body content type");
-        let body = &http_interaction.request.body.value;
-        let shape_diff_results =
-          diff_shape(spec_projection.shape(), body.into(), &result.root_shape_id);
+        let body = &http_interaction.request.body;
+        let shape_diff_results = diff_body_shape(
+          spec_projection.shape(),
+          body.into(),
+          &result.root_shape_id,
+          &config.ignore.body_trails,
+        );
         shape_diff_results
           .into_iter()
           .map(|shape_diff| {
@@ -73,9 +95,13 @@ pub fn diff(
         //   "interaction-diff: shape diffing for matched a response body content type: {:?}",
         //   &http_interaction.response.body
         // );
-        let body = &http_interaction.response.body.value;
-        let shape_diff_results =
-          diff_shape(spec_projection.shape(), body.into(), &result.root_shape_id);
+        let body = &http_interaction.response.body;
+        let shape_diff_results = diff_body_shape(
+          spec_projection.shape(),
+          body.into(),
+          &result.root_shape_id,
+          &config.ignore.body_trails,
+        );
         shape_diff_results
           .into_iter()
           .map(|shape_diff| {
@@ -85,6 +111,36 @@ pub fn diff(
           })
           .collect()
       }
+      InteractionDiffResult::MatchedRequestHeader(result) => {
+        let headers = ParsedHeaders::from(&http_interaction.request.headers);
+        diff_header(
+          spec_projection.shape(),
+          &headers,
+          &result.header_name,
+          &result.root_shape_id,
+        )
+        .into_iter()
+        .map(|shape_diff| {
+          InteractionDiffResult::UnmatchedRequestHeaders(result.clone().into_shape_diff(shape_diff))
+        })
+        .collect()
+      }
+      InteractionDiffResult::MatchedResponseHeader(result) => {
+        let headers = ParsedHeaders::from(&http_interaction.response.headers);
+        diff_header(
+          spec_projection.shape(),
+          &headers,
+          &result.header_name,
+          &result.root_shape_id,
+        )
+        .into_iter()
+        .map(|shape_diff| {
+          InteractionDiffResult::UnmatchedResponseHeaders(
+            result.clone().into_shape_diff(shape_diff),
+          )
+        })
+        .collect()
+      }
       _ => vec![result],
     })
     .filter(|result| {
@@ -97,15 +153,88 @@ pub fn diff(
     .collect()
 }
 
-#[derive(Clone, Debug)]
-pub struct DiffConfig {}
+/// Diff the value of a path parameter against the shape of the parameter, along with its format
+/// and allowed values. Path segments are always text, described like query string values and
+/// headers. Json trails of the results are keyed by parameter name.
+fn diff_path_parameter(
+  shape_projection: &ShapeProjection,
+  path_parameter: &MatchedPathParameter,
+) -> Vec<ShapeDiffResult> {
+  let value = &path_parameter.value;
+  let shape_descriptor = &path_parameter.shape_descriptor;
+
+  let mut shape_diffs = diff_shape(
+    shape_projection,
+    Some(BodyDescriptor::from_text(value)),
+    &shape_descriptor.shape_id,
+  );
+
+  let violates_format = match shape_descriptor.format {
+    Some(StringFormat::Uuid) => Uuid::parse_str(value).is_err(),
+    None => false,
+  };
+  let violates_enum = matches!(
+    &shape_descriptor.enum_values,
+    Some(enum_values) if !enum_values.contains(value)
+  );
+  if shape_diffs.is_empty() && (violates_format || violates_enum) {
+    shape_diffs.push(ShapeDiffResult::UnmatchedShape {
+      json_trail: JsonTrail::empty(),
+      shape_trail: ShapeTrail::new(shape_descriptor.shape_id.clone()),
+    });
+  }
+
+  let parameter_trail = JsonTrail::empty().with_object_key(path_parameter.parameter_name.clone());
+  shape_diffs
+    .into_iter()
+    .map(|shape_diff| shape_diff.prefixed(&parameter_trail))
+    .collect()
+}
 
-impl Default for DiffConfig {
-  fn default() -> Self {
-    Self {}
+/// Diff a single header of an interaction against the shape of its documented header parameter.
+/// Json trails of the results are relative to the headers as a whole, keyed by header name.
+fn diff_header(
+  shape_projection: &ShapeProjection,
+  headers: &ParsedHeaders,
+  header_name: &str,
+  root_shape_id: &ShapeId,
+) -> Vec<ShapeDiffResult> {
+  let header_trail = JsonTrail::empty().with_object_key(String::from(header_name));
+  match headers.get_descriptor(header_name) {
+    Some(header_value) => {
+      let mut shape_diffs = diff_shape(shape_projection, Some(header_value), root_shape_id);
+      // values taken for booleans could be documented as text as well
+      if !shape_diffs.is_empty() {
+        shape_diffs = diff_shape(
+          shape_projection,
+          headers.get_text_descriptor(header_name),
+          root_shape_id,
+        );
+      }
+      shape_diffs
+        .into_iter()
+        .map(|shape_diff| shape_diff.prefixed(&header_trail))
+        .collect()
+    }
+    None => {
+      let shape_queries = ShapeQueries::new(shape_projection);
+      match shape_queries.resolve_to_core_shape(root_shape_id) {
+        ShapeKind::OptionalKind => vec![],
+        _ => vec![ShapeDiffResult::UnmatchedShape {
+          json_trail: header_trail,
+          shape_trail: ShapeTrail::new(root_shape_id.clone()),
+        }],
+      }
+    }
   }
 }
 
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiffConfig {
+  pub ignore: IgnoreRules,
+}
+
 /// Analysises the shapes of interactions that have request or response bodies with previously
 /// unseen content types. From the result, observed types per json trail, commands can be generated
 /// applyable to the spec that would make the interaction compliant. Results can also be merged with
@@ -113,6 +242,7 @@ impl Default for DiffConfig {
 ///
 /// A diff is performed to find a matching Request / Response, but with missing content types
 /// respectively. When found, a normalized description of the shape of the interaction is traversed.
+/// The headers accompanying those bodies are observed as well, as an object keyed by header name.
 pub fn analyze_undocumented_bodies<'a>(
   spec_projection: &SpecProjection,
   interaction: HttpInteraction,
@@ -120,12 +250,13 @@ pub fn analyze_undocumented_bodies<'a>(
 ) -> impl Iterator<Item = BodyAnalysisResult> {
   let endpoint_projection = spec_projection.endpoint();
   let endpoint_queries = EndpointQueries::new(endpoint_projection);
-  let interaction_traverser = traverser::Traverser::new(&endpoint_queries);
+  let interaction_traverser = traverser::Traverser::new(&endpoint_queries, &config.ignore);
   let mut diff_visitors = visitors::diff::DiffVisitors::new();
 
   interaction_traverser.traverse(&interaction, &mut diff_visitors);
 
   let results = diff_visitors.take_results().unwrap();
+  let ignored_trails = config.ignore.body_trails.clone();
 
   results.into_iter().flat_map(move |result| match result {
     InteractionDiffResult::UnmatchedQueryParameters(diff) => {
@@ -145,48 +276,68 @@ pub fn analyze_undocumented_bodies<'a>(
     }
     InteractionDiffResult::UnmatchedRequestBodyContentType(diff) => {
       let body = &interaction.request.body;
-      let body_trail_observations = observe_body_trails(&body.value);
+      let body_trail_observations = observe_body_trails(body).without_trails(&ignored_trails);
+      let body_location = BodyAnalysisLocation::from(diff.clone());
 
-      vec![BodyAnalysisResult {
-        body_location: BodyAnalysisLocation::from(diff.clone()),
-        trail_observations: body_trail_observations,
-      }]
+      let headers = ParsedHeaders::from(&interaction.request.headers);
+      let headers_trail_observations = observe_body_trails(BodyDescriptor::from(headers));
+
+      vec![
+        BodyAnalysisResult {
+          body_location: body_location.headers_location().unwrap(),
+          trail_observations: headers_trail_observations,
+        },
+        BodyAnalysisResult {
+          body_location,
+          trail_observations: body_trail_observations,
+        },
+      ]
     }
     InteractionDiffResult::UnmatchedResponseBodyContentType(diff) => {
       let body = &interaction.response.body;
-      let trail_observations = observe_body_trails(&body.value);
+      let trail_observations = observe_body_trails(body).without_trails(&ignored_trails);
+      let body_location = BodyAnalysisLocation::from(diff);
 
-      vec![BodyAnalysisResult {
-        body_location: BodyAnalysisLocation::from(diff),
-        trail_observations,
-      }]
+      let headers = ParsedHeaders::from(&interaction.response.headers);
+      let headers_trail_observations = observe_body_trails(BodyDescriptor::from(headers));
+
+      vec![
+        BodyAnalysisResult {
+          body_location: body_location.headers_location().unwrap(),
+          trail_observations: headers_trail_observations,
+        },
+        BodyAnalysisResult {
+          body_location,
+          trail_observations,
+        },
+      ]
     }
     _ => vec![],
   })
 }
 
-#[derive(Clone, Debug)]
-pub struct AnalyzeUndocumentedBodiesConfig {}
-
-impl Default for AnalyzeUndocumentedBodiesConfig {
-  fn default() -> Self {
-    Self {}
-  }
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnalyzeUndocumentedBodiesConfig {
+  /// Interactions and parts of bodies to leave out of learning
+  pub ignore: IgnoreRules,
 }
 
 pub fn analyze_documented_bodies(
   spec_projection: &SpecProjection,
   interaction: HttpInteraction,
+  config: &AnalyzeDocumentedBodiesConfig,
 ) -> impl Iterator<Item = BodyAnalysisResult> {
   let endpoint_rpojection = spec_projection.endpoint();
   let endpoint_queries = EndpointQueries::new(endpoint_rpojection);
 
-  let interaction_traverser = traverser::Traverser::new(&endpoint_queries);
+  let interaction_traverser = traverser::Traverser::new(&endpoint_queries, &config.ignore);
   let mut diff_visitors = visitors::diff::DiffVisitors::new();
 
   interaction_traverser.traverse(&interaction, &mut diff_visitors);
 
   let results = diff_visitors.take_results().unwrap();
+  let ignored_trails = config.ignore.body_trails.clone();
 
   results.into_iter().filter_map(move |result| match result {
     InteractionDiffResult::MatchedQueryParameters(diff) => {
@@ -202,7 +353,7 @@ pub fn analyze_documented_bodies(
 
     InteractionDiffResult::MatchedRequestBodyContentType(diff) => {
       let body = &interaction.request.body;
-      let trail_observations = observe_body_trails(&body.value);
+      let trail_observations = observe_body_trails(body).without_trails(&ignored_trails);
 
       Some(BodyAnalysisResult {
         body_location: BodyAnalysisLocation::from(diff),
@@ -211,7 +362,7 @@ pub fn analyze_documented_bodies(
     }
     InteractionDiffResult::MatchedResponseBodyContentType(diff) => {
       let body = &interaction.response.body;
-      let trail_observations = observe_body_trails(&body.value);
+      let trail_observations = observe_body_trails(body).without_trails(&ignored_trails);
 
       Some(BodyAnalysisResult {
         body_location: BodyAnalysisLocation::from(diff),
@@ -221,3 +372,10 @@ pub fn analyze_documented_bodies(
     _ => None,
   })
 }
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnalyzeDocumentedBodiesConfig {
+  /// Interactions and parts of bodies to leave out of learning
+  pub ignore: IgnoreRules,
+}
@@ -1,7 +1,10 @@
 use crate::events::http_interaction::HttpInteraction;
 use crate::learn_shape::TrailObservationsResult;
 use crate::shapes::{JsonTrail, ShapeDiffResult};
-use crate::state::endpoint::{PathComponentId, QueryParametersId, RequestId, ResponseId, ShapeId};
+use crate::state::endpoint::{
+  PathComponentId, QueryParametersId, RequestId, RequestParameterId, ResponseId, ShapeId,
+  ShapedRequestParameterShapeDescriptor,
+};
 use seahash::hash;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::{DefaultHasher, HashMap};
@@ -11,19 +14,29 @@ pub enum InteractionDiffResult {
   UnmatchedQueryParameters(UnmatchedQueryParameters),
   UnmatchedQueryParametersShape(UnmatchedQueryParametersShape),
   UnmatchedRequestUrl(UnmatchedRequestUrl),
+  AmbiguousRequestUrl(AmbiguousRequestUrl),
+  UnmatchedPathParameterShape(UnmatchedPathParameterShape),
   UnmatchedRequestBodyContentType(UnmatchedRequestBodyContentType),
   UnmatchedRequestBodyShape(UnmatchedRequestBodyShape),
   UnmatchedResponseBodyContentType(UnmatchedResponseBodyContentType),
   UnmatchedResponseBodyShape(UnmatchedResponseBodyShape),
+  UnmatchedRequestHeaders(UnmatchedRequestHeaders),
+  UnmatchedResponseHeaders(UnmatchedResponseHeaders),
   //
   // Matches
   // -------
   #[serde(skip)]
+  MatchedPathParameter(MatchedPathParameter),
+  #[serde(skip)]
   MatchedQueryParameters(MatchedQueryParameters),
   #[serde(skip)]
   MatchedRequestBodyContentType(MatchedRequestBodyContentType),
   #[serde(skip)]
   MatchedResponseBodyContentType(MatchedResponseBodyContentType),
+  #[serde(skip)]
+  MatchedRequestHeader(MatchedRequestHeader),
+  #[serde(skip)]
+  MatchedResponseHeader(MatchedResponseHeader),
 }
 
 impl InteractionDiffResult {
@@ -38,13 +51,20 @@ impl InteractionDiffResult {
       InteractionDiffResult::UnmatchedQueryParameters(diff) => diff.interaction_trail(),
       InteractionDiffResult::UnmatchedQueryParametersShape(diff) => &diff.interaction_trail,
       InteractionDiffResult::UnmatchedRequestUrl(diff) => &diff.interaction_trail,
+      InteractionDiffResult::AmbiguousRequestUrl(diff) => &diff.interaction_trail,
+      InteractionDiffResult::UnmatchedPathParameterShape(diff) => &diff.interaction_trail,
       InteractionDiffResult::UnmatchedRequestBodyContentType(diff) => &diff.interaction_trail,
       InteractionDiffResult::UnmatchedRequestBodyShape(diff) => &diff.interaction_trail,
       InteractionDiffResult::UnmatchedResponseBodyContentType(diff) => &diff.interaction_trail,
       InteractionDiffResult::UnmatchedResponseBodyShape(diff) => &diff.interaction_trail,
+      InteractionDiffResult::UnmatchedRequestHeaders(diff) => &diff.interaction_trail,
+      InteractionDiffResult::UnmatchedResponseHeaders(diff) => &diff.interaction_trail,
+      InteractionDiffResult::MatchedPathParameter(diff) => &diff.interaction_trail,
       InteractionDiffResult::MatchedQueryParameters(diff) => &diff.interaction_trail,
       InteractionDiffResult::MatchedRequestBodyContentType(diff) => &diff.interaction_trail,
       InteractionDiffResult::MatchedResponseBodyContentType(diff) => &diff.interaction_trail,
+      InteractionDiffResult::MatchedRequestHeader(diff) => &diff.interaction_trail,
+      InteractionDiffResult::MatchedResponseHeader(diff) => &diff.interaction_trail,
     }
   }
 
@@ -53,21 +73,31 @@ impl InteractionDiffResult {
       InteractionDiffResult::UnmatchedQueryParameters(diff) => diff.requests_trail(),
       InteractionDiffResult::UnmatchedQueryParametersShape(diff) => &diff.requests_trail,
       InteractionDiffResult::UnmatchedRequestUrl(diff) => &diff.requests_trail,
+      InteractionDiffResult::AmbiguousRequestUrl(diff) => &diff.requests_trail,
+      InteractionDiffResult::UnmatchedPathParameterShape(diff) => &diff.requests_trail,
       InteractionDiffResult::UnmatchedRequestBodyContentType(diff) => &diff.requests_trail,
       InteractionDiffResult::UnmatchedRequestBodyShape(diff) => &diff.requests_trail,
       InteractionDiffResult::UnmatchedResponseBodyContentType(diff) => &diff.requests_trail,
       InteractionDiffResult::UnmatchedResponseBodyShape(diff) => &diff.requests_trail,
+      InteractionDiffResult::UnmatchedRequestHeaders(diff) => &diff.requests_trail,
+      InteractionDiffResult::UnmatchedResponseHeaders(diff) => &diff.requests_trail,
+      InteractionDiffResult::MatchedPathParameter(diff) => &diff.requests_trail,
       InteractionDiffResult::MatchedQueryParameters(diff) => &diff.requests_trail,
       InteractionDiffResult::MatchedRequestBodyContentType(diff) => &diff.requests_trail,
       InteractionDiffResult::MatchedResponseBodyContentType(diff) => &diff.requests_trail,
+      InteractionDiffResult::MatchedRequestHeader(diff) => &diff.requests_trail,
+      InteractionDiffResult::MatchedResponseHeader(diff) => &diff.requests_trail,
     }
   }
 
   pub fn json_trail(&self) -> Option<&JsonTrail> {
     let shape_diff_result = match self {
+      InteractionDiffResult::UnmatchedPathParameterShape(diff) => Some(&diff.shape_diff_result),
       InteractionDiffResult::UnmatchedQueryParametersShape(diff) => Some(&diff.shape_diff_result),
       InteractionDiffResult::UnmatchedRequestBodyShape(diff) => Some(&diff.shape_diff_result),
       InteractionDiffResult::UnmatchedResponseBodyShape(diff) => Some(&diff.shape_diff_result),
+      InteractionDiffResult::UnmatchedRequestHeaders(diff) => Some(&diff.shape_diff_result),
+      InteractionDiffResult::UnmatchedResponseHeaders(diff) => Some(&diff.shape_diff_result),
       _ => None,
     }?;
 
@@ -95,6 +125,88 @@ impl UnmatchedRequestUrl {
   }
 }
 
+/// A url matching multiple equally specific paths of the spec
+#[derive(Debug, Deserialize, Serialize, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct AmbiguousRequestUrl {
+  pub interaction_trail: InteractionTrail,
+  pub requests_trail: RequestSpecTrail,
+  pub path_ids: Vec<PathComponentId>,
+}
+
+impl AmbiguousRequestUrl {
+  pub fn new(
+    interaction_trail: InteractionTrail,
+    requests_trail: RequestSpecTrail,
+    path_ids: Vec<PathComponentId>,
+  ) -> Self {
+    AmbiguousRequestUrl {
+      interaction_trail,
+      requests_trail,
+      path_ids,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Hash)]
+pub struct MatchedPathParameter {
+  pub interaction_trail: InteractionTrail,
+  pub requests_trail: RequestSpecTrail,
+  pub parameter_name: String,
+  pub value: String,
+  pub shape_descriptor: ShapedRequestParameterShapeDescriptor,
+}
+
+impl MatchedPathParameter {
+  pub fn new(
+    interaction_trail: InteractionTrail,
+    requests_trail: RequestSpecTrail,
+    parameter_name: String,
+    value: String,
+    shape_descriptor: ShapedRequestParameterShapeDescriptor,
+  ) -> Self {
+    MatchedPathParameter {
+      interaction_trail,
+      requests_trail,
+      parameter_name,
+      value,
+      shape_descriptor,
+    }
+  }
+
+  pub fn into_shape_diff(self, shape_diff_result: ShapeDiffResult) -> UnmatchedPathParameterShape {
+    UnmatchedPathParameterShape::new(
+      self.interaction_trail,
+      self.requests_trail,
+      shape_diff_result,
+    )
+  }
+}
+
+/// A path parameter value not matching the shape of the path parameter. Json trails are relative
+/// to the path parameters as a whole, keyed by parameter name.
+#[derive(Debug, Deserialize, Serialize, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedPathParameterShape {
+  pub interaction_trail: InteractionTrail,
+  pub requests_trail: RequestSpecTrail,
+  pub shape_diff_result: ShapeDiffResult,
+}
+
+impl UnmatchedPathParameterShape {
+  pub fn new(
+    interaction_trail: InteractionTrail,
+    requests_trail: RequestSpecTrail,
+    shape_diff_result: ShapeDiffResult,
+  ) -> Self {
+    UnmatchedPathParameterShape {
+      interaction_trail,
+      requests_trail,
+      shape_diff_result,
+    }
+  }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct UnmatchedQueryParametersDescriptor {
@@ -330,6 +442,114 @@ impl UnmatchedResponseBodyShape {
   }
 }
 
+#[derive(Clone, Debug, Serialize, Hash)]
+pub struct MatchedRequestHeader {
+  pub interaction_trail: InteractionTrail,
+  pub requests_trail: RequestSpecTrail,
+  pub header_name: String,
+  pub root_shape_id: ShapeId,
+}
+
+impl MatchedRequestHeader {
+  pub fn new(
+    interaction_trail: InteractionTrail,
+    requests_trail: RequestSpecTrail,
+    header_name: String,
+    root_shape_id: ShapeId,
+  ) -> Self {
+    MatchedRequestHeader {
+      interaction_trail,
+      requests_trail,
+      header_name,
+      root_shape_id,
+    }
+  }
+
+  pub fn into_shape_diff(self, shape_diff_result: ShapeDiffResult) -> UnmatchedRequestHeaders {
+    UnmatchedRequestHeaders::new(
+      self.interaction_trail,
+      self.requests_trail,
+      shape_diff_result,
+    )
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedRequestHeaders {
+  pub interaction_trail: InteractionTrail,
+  pub requests_trail: RequestSpecTrail,
+  pub shape_diff_result: ShapeDiffResult,
+}
+
+impl UnmatchedRequestHeaders {
+  pub fn new(
+    interaction_trail: InteractionTrail,
+    requests_trail: RequestSpecTrail,
+    shape_diff_result: ShapeDiffResult,
+  ) -> Self {
+    UnmatchedRequestHeaders {
+      interaction_trail,
+      requests_trail,
+      shape_diff_result,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Hash)]
+pub struct MatchedResponseHeader {
+  pub interaction_trail: InteractionTrail,
+  pub requests_trail: RequestSpecTrail,
+  pub header_name: String,
+  pub root_shape_id: ShapeId,
+}
+
+impl MatchedResponseHeader {
+  pub fn new(
+    interaction_trail: InteractionTrail,
+    requests_trail: RequestSpecTrail,
+    header_name: String,
+    root_shape_id: ShapeId,
+  ) -> Self {
+    MatchedResponseHeader {
+      interaction_trail,
+      requests_trail,
+      header_name,
+      root_shape_id,
+    }
+  }
+
+  pub fn into_shape_diff(self, shape_diff_result: ShapeDiffResult) -> UnmatchedResponseHeaders {
+    UnmatchedResponseHeaders::new(
+      self.interaction_trail,
+      self.requests_trail,
+      shape_diff_result,
+    )
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedResponseHeaders {
+  pub interaction_trail: InteractionTrail,
+  pub requests_trail: RequestSpecTrail,
+  pub shape_diff_result: ShapeDiffResult,
+}
+
+impl UnmatchedResponseHeaders {
+  pub fn new(
+    interaction_trail: InteractionTrail,
+    requests_trail: RequestSpecTrail,
+    shape_diff_result: ShapeDiffResult,
+  ) -> Self {
+    UnmatchedResponseHeaders {
+      interaction_trail,
+      requests_trail,
+      shape_diff_result,
+    }
+  }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 #[derive(Clone, Debug)]
 pub struct BodyAnalysisResult {
@@ -354,6 +574,17 @@ pub enum BodyAnalysisLocation {
     content_type: Option<String>,
     status_code: u16,
   },
+  UnmatchedRequestHeaders {
+    path_id: PathComponentId,
+    method: String,
+    content_type: Option<String>,
+  },
+  UnmatchedResponseHeaders {
+    path_id: PathComponentId,
+    method: String,
+    content_type: Option<String>,
+    status_code: u16,
+  },
   MatchedQueryParameters {
     query_parameters_id: QueryParametersId,
   },
@@ -374,11 +605,40 @@ impl BodyAnalysisLocation {
       BodyAnalysisLocation::UnmatchedRequest { content_type, .. } => content_type.as_ref(),
       BodyAnalysisLocation::UnmatchedQueryParameters { .. } => None,
       BodyAnalysisLocation::UnmatchedResponse { content_type, .. } => content_type.as_ref(),
+      BodyAnalysisLocation::UnmatchedRequestHeaders { content_type, .. } => content_type.as_ref(),
+      BodyAnalysisLocation::UnmatchedResponseHeaders { content_type, .. } => content_type.as_ref(),
       BodyAnalysisLocation::MatchedQueryParameters { .. } => None,
       BodyAnalysisLocation::MatchedRequest { content_type, .. } => content_type.as_ref(),
       BodyAnalysisLocation::MatchedResponse { content_type, .. } => content_type.as_ref(),
     }
   }
+
+  /// The location of the headers that accompany an unmatched request or response body.
+  pub fn headers_location(&self) -> Option<Self> {
+    match self {
+      BodyAnalysisLocation::UnmatchedRequest {
+        path_id,
+        method,
+        content_type,
+      } => Some(BodyAnalysisLocation::UnmatchedRequestHeaders {
+        path_id: path_id.clone(),
+        method: method.clone(),
+        content_type: content_type.clone(),
+      }),
+      BodyAnalysisLocation::UnmatchedResponse {
+        path_id,
+        method,
+        content_type,
+        status_code,
+      } => Some(BodyAnalysisLocation::UnmatchedResponseHeaders {
+        path_id: path_id.clone(),
+        method: method.clone(),
+        content_type: content_type.clone(),
+        status_code: *status_code,
+      }),
+      _ => None,
+    }
+  }
 }
 
 impl From<UnmatchedQueryParameters> for BodyAnalysisLocation {
@@ -534,6 +794,25 @@ impl InteractionTrail {
     })
   }
 
+  pub fn with_request_headers(&mut self) {
+    self
+      .path
+      .push(InteractionTrailPathComponent::RequestHeaders)
+  }
+
+  pub fn with_response_headers(&mut self, status_code: u16) {
+    self
+      .path
+      .push(InteractionTrailPathComponent::ResponseHeaders { status_code })
+  }
+
+  pub fn get_url_path(&self) -> Option<&String> {
+    self.path.iter().find_map(|component| match component {
+      InteractionTrailPathComponent::Url { path } => Some(path),
+      _ => None,
+    })
+  }
+
   pub fn get_method(&self) -> Option<&String> {
     self.path.iter().find_map(|component| match component {
       InteractionTrailPathComponent::Method { method } => Some(method),
@@ -559,9 +838,11 @@ impl InteractionTrail {
     self.path.iter().find_map(|component| match component {
       InteractionTrailPathComponent::ResponseBody { status_code, .. } => Some(*status_code),
       InteractionTrailPathComponent::ResponseStatusCode { status_code } => Some(*status_code),
+      InteractionTrailPathComponent::ResponseHeaders { status_code } => Some(*status_code),
       InteractionTrailPathComponent::Method { .. }
       | InteractionTrailPathComponent::QueryParameters
       | InteractionTrailPathComponent::RequestBody { .. }
+      | InteractionTrailPathComponent::RequestHeaders
       | InteractionTrailPathComponent::Url { .. } => None,
     })
   }
@@ -632,6 +913,7 @@ pub enum RequestSpecTrail {
   SpecRequestBody(SpecRequestBody),
   SpecResponseRoot(SpecResponseRoot),
   SpecResponseBody(SpecResponseBody),
+  SpecHeaderParameter(SpecHeaderParameter),
 }
 
 impl RequestSpecTrail {
@@ -666,6 +948,13 @@ impl RequestSpecTrail {
       _ => None,
     }
   }
+
+  pub fn get_header_parameter_id(&self) -> Option<&String> {
+    match self {
+      RequestSpecTrail::SpecHeaderParameter(spec_header) => Some(&spec_header.parameter_id),
+      _ => None,
+    }
+  }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Hash)]
@@ -706,6 +995,11 @@ pub struct SpecResponseRoot {
 pub struct SpecResponseBody {
   pub response_id: ResponseId,
 }
+#[derive(Clone, Debug, Deserialize, Serialize, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecHeaderParameter {
+  pub parameter_id: RequestParameterId,
+}
 //@GOTCHA make sure these serialize matching the existing scala code
 #[derive(Clone, Debug, Deserialize, Serialize, Hash)]
 pub enum InteractionTrailPathComponent {
@@ -729,4 +1023,9 @@ pub enum InteractionTrailPathComponent {
     content_type: String,
     status_code: u16,
   },
+  RequestHeaders,
+  #[serde(rename_all = "camelCase")]
+  ResponseHeaders {
+    status_code: u16,
+  },
 }
@@ -1,20 +1,26 @@
+use super::ignore::IgnoreRules;
 use super::visitors::{
-  InteractionVisitors, PathVisitor, PathVisitorContext, QueryParametersVisitor,
-  QueryParametersVisitorContext, RequestBodyVisitor, RequestBodyVisitorContext,
-  ResponseBodyVisitor, ResponseBodyVisitorContext,
+  HeadersLocation, HeadersVisitor, HeadersVisitorContext, InteractionVisitors, PathVisitor,
+  PathVisitorContext, QueryParametersVisitor, QueryParametersVisitorContext, RequestBodyVisitor,
+  RequestBodyVisitorContext, ResponseBodyVisitor, ResponseBodyVisitorContext,
 };
+use crate::events::http_interaction::Body;
 use crate::events::HttpInteraction;
-use crate::projections::endpoint::ROOT_PATH_ID;
+use crate::projections::endpoint::{BodyDescriptor, ROOT_PATH_ID};
 use crate::queries::endpoint::EndpointQueries;
 use crate::state::endpoint::PathComponentId;
 
 pub struct Traverser<'a> {
   endpoint_queries: &'a EndpointQueries<'a>,
+  ignore_rules: &'a IgnoreRules,
 }
 
 impl<'a> Traverser<'a> {
-  pub fn new(endpoint_queries: &'a EndpointQueries) -> Self {
-    Traverser { endpoint_queries }
+  pub fn new(endpoint_queries: &'a EndpointQueries, ignore_rules: &'a IgnoreRules) -> Self {
+    Traverser {
+      endpoint_queries,
+      ignore_rules,
+    }
   }
 
   pub fn traverse<R>(
@@ -22,10 +28,29 @@ impl<'a> Traverser<'a> {
     interaction: &HttpInteraction,
     visitors: &mut impl InteractionVisitors<R>,
   ) {
+    if self.ignore_rules.ignores_interaction(interaction) {
+      return;
+    }
+
     let path_visitor = visitors.path();
-    let resolved_path = self.endpoint_queries.resolve_interaction_path(&interaction);
+    let resolved_paths = self
+      .endpoint_queries
+      .resolve_most_specific_paths(&interaction.request.path);
+    let (resolved_path, ambiguous_paths) = match resolved_paths.as_slice() {
+      [path_id] => (Some(*path_id), vec![]),
+      [] => (None, vec![]),
+      _ => (None, resolved_paths),
+    };
+    let path_parameters = match resolved_path {
+      Some(path_id) => self
+        .endpoint_queries
+        .resolve_path_parameter_values(path_id, &interaction.request.path),
+      None => vec![],
+    };
     let path_context = PathVisitorContext {
       path: resolved_path,
+      ambiguous_paths,
+      path_parameters,
     };
     path_visitor.visit(interaction, &path_context);
 
@@ -97,6 +122,66 @@ impl<'a> Traverser<'a> {
     };
     response_body_visitor.end(interaction, &path_context);
     // eprintln!("ended response body visiting");
+
+    let headers_visitor = visitors.headers();
+    headers_visitor.begin();
+    if let Some(path_id) = resolved_path {
+      // only requests and responses that match the interaction's bodies own its headers
+      let operations = self
+        .endpoint_queries
+        .resolve_operations(interaction, path_id)
+        .filter(|(_, request_descriptor)| {
+          body_matches(request_descriptor.body.as_ref(), &interaction.request.body)
+        });
+      for (request_id, _) in operations {
+        headers_visitor.visit(
+          interaction,
+          &HeadersVisitorContext {
+            location: HeadersLocation::Request(request_id),
+            header_parameters: self
+              .endpoint_queries
+              .resolve_header_parameters(request_id)
+              .collect(),
+          },
+        );
+      }
+
+      let responses = self
+        .endpoint_queries
+        .resolve_responses_by_method_and_status_code(
+          &interaction.request.method,
+          interaction.response.status_code,
+          path_id,
+        )
+        .filter(|(_, response_descriptor)| {
+          body_matches(
+            response_descriptor.body.as_ref(),
+            &interaction.response.body,
+          )
+        });
+      for (response_id, _) in responses {
+        headers_visitor.visit(
+          interaction,
+          &HeadersVisitorContext {
+            location: HeadersLocation::Response(response_id),
+            header_parameters: self
+              .endpoint_queries
+              .resolve_header_parameters(response_id)
+              .collect(),
+          },
+        );
+      }
+    }
+    headers_visitor.end();
+  }
+}
+
+fn body_matches(spec_body: Option<&BodyDescriptor>, interaction_body: &Body) -> bool {
+  match spec_body {
+    Some(spec_body) => {
+      Some(spec_body.http_content_type.as_str()) == interaction_body.documented_content_type()
+    }
+    None => true,
   }
 }
 
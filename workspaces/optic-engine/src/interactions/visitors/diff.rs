@@ -1,16 +1,18 @@
 use super::{
-  InteractionVisitor, InteractionVisitors, PathVisitor, PathVisitorContext, QueryParametersVisitor,
-  QueryParametersVisitorContext, RequestBodyVisitor, RequestBodyVisitorContext,
-  ResponseBodyVisitor, ResponseBodyVisitorContext, VisitorResults,
+  HeadersLocation, HeadersVisitor, HeadersVisitorContext, InteractionVisitor, InteractionVisitors,
+  PathVisitor, PathVisitorContext, QueryParametersVisitor, QueryParametersVisitorContext,
+  RequestBodyVisitor, RequestBodyVisitorContext, ResponseBodyVisitor, ResponseBodyVisitorContext,
+  VisitorResults,
 };
 use crate::interactions::result::{
-  InteractionDiffResult, MatchedQueryParameters, MatchedRequestBodyContentType,
-  MatchedResponseBodyContentType, SpecQueryParameters, SpecRoot, UnmatchedQueryParameters,
+  AmbiguousRequestUrl, InteractionDiffResult, MatchedPathParameter, MatchedQueryParameters,
+  MatchedRequestBodyContentType, MatchedRequestHeader, MatchedResponseBodyContentType,
+  MatchedResponseHeader, SpecQueryParameters, SpecRoot, UnmatchedQueryParameters,
   UnmatchedRequestBodyContentType, UnmatchedRequestUrl, UnmatchedResponseBodyContentType,
 };
 use crate::interactions::result::{
-  InteractionTrail, InteractionTrailPathComponent, RequestSpecTrail, SpecPath, SpecRequestBody,
-  SpecResponseBody,
+  InteractionTrail, InteractionTrailPathComponent, RequestSpecTrail, SpecHeaderParameter, SpecPath,
+  SpecRequestBody, SpecResponseBody,
 };
 use crate::state::body::BodyDescriptor;
 use crate::state::endpoint::{HttpContentType, RequestId, ResponseId};
@@ -21,6 +23,7 @@ pub struct DiffVisitors {
   query_params: DiffQueryParametersVisitor,
   request_body: DiffRequestBodyVisitor,
   response_body: DiffResponseBodyVisitor,
+  headers: DiffHeadersVisitor,
 }
 
 impl DiffVisitors {
@@ -30,6 +33,7 @@ impl DiffVisitors {
       query_params: DiffQueryParametersVisitor::new(),
       request_body: DiffRequestBodyVisitor::new(),
       response_body: DiffResponseBodyVisitor::new(),
+      headers: DiffHeadersVisitor::new(),
     }
   }
 }
@@ -41,6 +45,7 @@ impl InteractionVisitors<InteractionDiffResult> for DiffVisitors {
   type QueryParameters = DiffQueryParametersVisitor;
   type RequestBody = DiffRequestBodyVisitor;
   type ResponseBody = DiffResponseBodyVisitor;
+  type Headers = DiffHeadersVisitor;
 
   fn path(&mut self) -> &mut DiffPathVisitor {
     &mut self.path
@@ -54,6 +59,9 @@ impl InteractionVisitors<InteractionDiffResult> for DiffVisitors {
   fn response_body(&mut self) -> &mut DiffResponseBodyVisitor {
     &mut self.response_body
   }
+  fn headers(&mut self) -> &mut DiffHeadersVisitor {
+    &mut self.headers
+  }
 }
 ///////////////////////////////////////////////////////////////////////////////
 
@@ -82,12 +90,47 @@ impl PathVisitor<InteractionDiffResult> for DiffPathVisitor {
       interaction_trail.with_url(interaction.request.path.clone());
       interaction_trail.with_method(interaction.request.method.clone());
       let requests_trail = RequestSpecTrail::SpecRoot(SpecRoot {});
-      let diff = InteractionDiffResult::UnmatchedRequestUrl(UnmatchedRequestUrl::new(
-        interaction_trail,
-        requests_trail,
-      ));
+      let diff = if context.ambiguous_paths.is_empty() {
+        InteractionDiffResult::UnmatchedRequestUrl(UnmatchedRequestUrl::new(
+          interaction_trail,
+          requests_trail,
+        ))
+      } else {
+        InteractionDiffResult::AmbiguousRequestUrl(AmbiguousRequestUrl::new(
+          interaction_trail,
+          requests_trail,
+          context
+            .ambiguous_paths
+            .iter()
+            .map(|path_id| String::from(*path_id))
+            .collect(),
+        ))
+      };
       self.push(diff);
     }
+
+    for path_parameter in &context.path_parameters {
+      // path parameters without a shape aren't documented enough to be diffed yet
+      let shape_descriptor = match path_parameter.shape {
+        Some(shape_descriptor) if !shape_descriptor.is_removed => shape_descriptor,
+        _ => continue,
+      };
+      let mut interaction_trail = InteractionTrail::empty();
+      interaction_trail.with_url(interaction.request.path.clone());
+      interaction_trail.with_method(interaction.request.method.clone());
+      let requests_trail = RequestSpecTrail::SpecPath(SpecPath {
+        path_id: path_parameter.path_id.clone(),
+      });
+      self.push(InteractionDiffResult::MatchedPathParameter(
+        MatchedPathParameter::new(
+          interaction_trail,
+          requests_trail,
+          String::from(path_parameter.name),
+          String::from(path_parameter.value),
+          shape_descriptor.clone(),
+        ),
+      ));
+    }
   }
 }
 ///////////////////////////////////////////////////////////////////////////////
@@ -196,9 +239,9 @@ impl RequestBodyVisitor<InteractionDiffResult> for DiffRequestBodyVisitor {
 
   fn visit(&mut self, interaction: &HttpInteraction, context: &RequestBodyVisitorContext) {
     if let Some(operation) = context.operation {
-      let maybe_interaction_content_type = &interaction.request.body.content_type;
+      let maybe_interaction_content_type = interaction.request.body.documented_content_type();
       let maybe_interaction_body_descriptor: Option<BodyDescriptor> =
-        (&interaction.request.body.value).into();
+        (&interaction.request.body).into();
       let (request_id, request_descriptor) = operation;
       //dbg!( maybe_interaction_content_type);
       //dbg!(&request_descriptor);
@@ -260,7 +303,7 @@ impl RequestBodyVisitor<InteractionDiffResult> for DiffRequestBodyVisitor {
   fn end(&mut self, interaction: &HttpInteraction, context: &PathVisitorContext) {
     if let Some(path_id) = context.path {
       if self.visited_with_matched_content_types.is_empty() {
-        let maybe_interaction_content_type = &interaction.request.body.content_type;
+        let maybe_interaction_content_type = interaction.request.body.documented_content_type();
         let mut interaction_trail_components = vec![
           InteractionTrailPathComponent::Url {
             path: interaction.request.path.clone(),
@@ -271,7 +314,7 @@ impl RequestBodyVisitor<InteractionDiffResult> for DiffRequestBodyVisitor {
         ];
         if let Some(content_type) = maybe_interaction_content_type {
           interaction_trail_components.push(InteractionTrailPathComponent::RequestBody {
-            content_type: content_type.clone(),
+            content_type: String::from(content_type),
           });
         }
         let interaction_trail = InteractionTrail::new(interaction_trail_components);
@@ -317,9 +360,9 @@ impl ResponseBodyVisitor<InteractionDiffResult> for DiffResponseBodyVisitor {
   fn visit(&mut self, interaction: &HttpInteraction, context: &ResponseBodyVisitorContext) {
     //dbg!("visit response body");
     if let Some(response) = context.response {
-      let maybe_interaction_content_type = &interaction.response.body.content_type;
+      let maybe_interaction_content_type = interaction.response.body.documented_content_type();
       let maybe_interaction_body_descriptor: Option<BodyDescriptor> =
-        (&interaction.response.body.value).into();
+        (&interaction.response.body).into();
       let (response_id, response_descriptor) = response;
       //dbg!("actual response content type", maybe_interaction_content_type);
       // dbg!(
@@ -387,7 +430,7 @@ impl ResponseBodyVisitor<InteractionDiffResult> for DiffResponseBodyVisitor {
   fn end(&mut self, interaction: &HttpInteraction, context: &PathVisitorContext) {
     if let Some(path_id) = context.path {
       if self.visited_with_matched_content_types.is_empty() {
-        let actual_content_type = &interaction.response.body.content_type;
+        let actual_content_type = interaction.response.body.documented_content_type();
         let mut interaction_trail_components = vec![
           //InteractionTrailPathComponent::Url(),
           InteractionTrailPathComponent::Method {
@@ -396,7 +439,7 @@ impl ResponseBodyVisitor<InteractionDiffResult> for DiffResponseBodyVisitor {
         ];
         if let Some(content_type) = actual_content_type {
           interaction_trail_components.push(InteractionTrailPathComponent::ResponseBody {
-            content_type: content_type.clone(),
+            content_type: String::from(content_type),
             status_code: interaction.response.status_code,
           });
         } else {
@@ -416,3 +459,67 @@ impl ResponseBodyVisitor<InteractionDiffResult> for DiffResponseBodyVisitor {
     }
   }
 }
+///////////////////////////////////////////////////////////////////////////////
+
+pub struct DiffHeadersVisitor {
+  results: DiffResults,
+}
+
+impl DiffHeadersVisitor {
+  fn new() -> Self {
+    DiffHeadersVisitor {
+      results: DiffResults::new(),
+    }
+  }
+}
+
+impl InteractionVisitor<InteractionDiffResult> for DiffHeadersVisitor {
+  fn results(&mut self) -> Option<&mut DiffResults> {
+    Some(&mut self.results)
+  }
+}
+impl HeadersVisitor<InteractionDiffResult> for DiffHeadersVisitor {
+  fn begin(&mut self) {}
+
+  fn visit(&mut self, interaction: &HttpInteraction, context: &HeadersVisitorContext) {
+    for (parameter_id, header_descriptor) in &context.header_parameters {
+      // headers without a shape aren't documented enough to be diffed yet
+      let root_shape_id = match &header_descriptor.shape {
+        Some(shape_descriptor) => shape_descriptor.shape_id.clone(),
+        None => continue,
+      };
+      let header_name = header_descriptor.name.to_lowercase();
+      let requests_trail = RequestSpecTrail::SpecHeaderParameter(SpecHeaderParameter {
+        parameter_id: String::from(*parameter_id),
+      });
+
+      let diff = match context.location {
+        HeadersLocation::Request(_) => {
+          let interaction_trail =
+            InteractionTrail::new(vec![InteractionTrailPathComponent::RequestHeaders]);
+          InteractionDiffResult::MatchedRequestHeader(MatchedRequestHeader::new(
+            interaction_trail,
+            requests_trail,
+            header_name,
+            root_shape_id,
+          ))
+        }
+        HeadersLocation::Response(_) => {
+          let interaction_trail =
+            InteractionTrail::new(vec![InteractionTrailPathComponent::ResponseHeaders {
+              status_code: interaction.response.status_code,
+            }]);
+          InteractionDiffResult::MatchedResponseHeader(MatchedResponseHeader::new(
+            interaction_trail,
+            requests_trail,
+            header_name,
+            root_shape_id,
+          ))
+        }
+      };
+      self.push(diff);
+    }
+  }
+
+  fn end(&mut self) {}
+}
@@ -1,10 +1,11 @@
 pub mod diff;
 
 use crate::projections::endpoint::{
-  QueryParametersDescriptor, RequestDescriptor, ResponseBodyDescriptor,
+  HeaderParameterDescriptor, QueryParametersDescriptor, RequestDescriptor, ResponseBodyDescriptor,
 };
+use crate::queries::endpoint::PathParameterValue;
 use crate::state::endpoint::{
-  PathComponentId, PathComponentIdRef, QueryParametersId, RequestId, ResponseId,
+  PathComponentId, PathComponentIdRef, QueryParametersId, RequestId, RequestParameterId, ResponseId,
 };
 use crate::HttpInteraction;
 
@@ -13,6 +14,7 @@ pub trait InteractionVisitors<R> {
   type QueryParameters: QueryParametersVisitor<R>;
   type RequestBody: RequestBodyVisitor<R>;
   type ResponseBody: ResponseBodyVisitor<R>;
+  type Headers: HeadersVisitor<R>;
 
   fn path(&mut self) -> &mut Self::Path;
 
@@ -22,12 +24,15 @@ pub trait InteractionVisitors<R> {
 
   fn response_body(&mut self) -> &mut Self::ResponseBody;
 
+  fn headers(&mut self) -> &mut Self::Headers;
+
   fn take_results(&mut self) -> Option<Vec<R>> {
     let flattened = vec![
       self.path().take_results(),
       self.query_params().take_results(),
       self.request_body().take_results(),
       self.response_body().take_results(),
+      self.headers().take_results(),
     ]
     .into_iter()
     .filter_map(|x| x)
@@ -73,12 +78,21 @@ pub trait ResponseBodyVisitor<R>: InteractionVisitor<R> {
   fn end(&mut self, interaction: &HttpInteraction, context: &PathVisitorContext);
 }
 
+pub trait HeadersVisitor<R>: InteractionVisitor<R> {
+  fn begin(&mut self);
+  fn visit(&mut self, interaction: &HttpInteraction, context: &HeadersVisitorContext);
+  fn end(&mut self);
+}
+
 pub trait PathVisitor<R>: InteractionVisitor<R> {
   fn visit(&mut self, interaction: &HttpInteraction, context: &PathVisitorContext);
 }
 
 pub struct PathVisitorContext<'a> {
   pub path: Option<PathComponentIdRef<'a>>,
+  /// Equally specific paths matching the interaction, when it can't be resolved to one
+  pub ambiguous_paths: Vec<PathComponentIdRef<'a>>,
+  pub path_parameters: Vec<PathParameterValue<'a>>,
 }
 pub struct QueryParametersVisitorContext<'a> {
   pub path: PathComponentIdRef<'a>,
@@ -93,6 +107,15 @@ pub struct ResponseBodyVisitorContext<'a> {
   pub response: Option<(&'a ResponseId, &'a ResponseBodyDescriptor)>,
 }
 
+pub enum HeadersLocation<'a> {
+  Request(&'a RequestId),
+  Response(&'a ResponseId),
+}
+pub struct HeadersVisitorContext<'a> {
+  pub location: HeadersLocation<'a>,
+  pub header_parameters: Vec<(&'a RequestParameterId, &'a HeaderParameterDescriptor)>,
+}
+
 // Results
 // -------
 
@@ -0,0 +1,119 @@
+use super::schema::{SchemaBuilder, SchemaDialect};
+use crate::projections::ShapeProjection;
+use crate::queries::ShapeQueries;
+use crate::state::shape::ShapeId;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Describe a shape as a JSON Schema (Draft 2020-12) document. Referenced shapes, and shapes
+/// containing themselves, are described once under `$defs`, every other shape is described
+/// inline. References back to the described shape itself point to the root of the document.
+/// Returns `None` when no shape with the given id exists.
+pub fn export_json_schema(
+  shape_projection: &ShapeProjection,
+  shape_id: &ShapeId,
+) -> Option<JsonValue> {
+  shape_projection.get_shape_node_index(shape_id)?;
+
+  let mut schemas = SchemaBuilder::new(
+    ShapeQueries::new(shape_projection),
+    JsonSchemaDialect {
+      root_shape_id: shape_id,
+    },
+  );
+  let mut document = JsonMap::new();
+  document.insert(
+    String::from("$schema"),
+    JsonValue::from(JSON_SCHEMA_DIALECT),
+  );
+  match schemas.schema(shape_id) {
+    JsonValue::Object(schema) => document.extend(schema),
+    _ => unreachable!("schemas of shapes should be objects"),
+  }
+  if !schemas.components().is_empty() {
+    document.insert(String::from("$defs"), json!(schemas.components()));
+  }
+
+  Some(JsonValue::Object(document))
+}
+
+struct JsonSchemaDialect<'a> {
+  root_shape_id: &'a ShapeId,
+}
+
+impl<'a> SchemaDialect for JsonSchemaDialect<'a> {
+  fn reference(&self, name: &str) -> JsonValue {
+    json!({ "$ref": format!("#/$defs/{}", name) })
+  }
+
+  fn nullable(&self, mut schema: JsonValue) -> JsonValue {
+    match schema.get("type").cloned() {
+      // a single type can be turned into a union with null
+      Some(JsonValue::String(inner_type)) => {
+        schema["type"] = json!([inner_type, "null"]);
+        schema
+      }
+      // a schema allowing anything already allows null
+      _ if schema == json!({}) => schema,
+      _ => json!({ "anyOf": [schema, { "type": "null" }] }),
+    }
+  }
+
+  fn self_reference(&self, shape_id: &ShapeId) -> Option<JsonValue> {
+    if shape_id == self.root_shape_id {
+      Some(json!({ "$ref": "#" }))
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::events::ShapeEvent;
+  use insta::assert_json_snapshot;
+  use serde_json::json;
+
+  #[test]
+  pub fn can_export_shapes_as_json_schema() {
+    let events: Vec<ShapeEvent> = serde_json::from_value(json!([
+      {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"number_shape_1","baseShapeId":"$number","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"nullable_shape_1","baseShapeId":"$nullable","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"nullable_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"string_shape_1"}},"consumingParameterId":"$nullableInner"}}}},
+      {"ShapeAdded":{"shapeId":"optional_shape_1","baseShapeId":"$optional","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"optional_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"nullable_shape_1"}},"consumingParameterId":"$optionalInner"}}}},
+      {"ShapeAdded":{"shapeId":"one_of_shape_1","baseShapeId":"$oneOf","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterAdded":{"shapeParameterId":"one_of_parameter_1","shapeId":"one_of_shape_1","name":"","shapeDescriptor":{"ProviderInShape":{"shapeId":"one_of_shape_1","providerDescriptor":{"NoProvider":{}},"consumingParameterId":"one_of_parameter_1"}}}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"one_of_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"number_shape_1"}},"consumingParameterId":"one_of_parameter_1"}}}},
+      {"ShapeParameterAdded":{"shapeParameterId":"one_of_parameter_2","shapeId":"one_of_shape_1","name":"","shapeDescriptor":{"ProviderInShape":{"shapeId":"one_of_shape_1","providerDescriptor":{"NoProvider":{}},"consumingParameterId":"one_of_parameter_2"}}}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"one_of_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"string_shape_1"}},"consumingParameterId":"one_of_parameter_2"}}}},
+      {"ShapeAdded":{"shapeId":"user_shape","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":"User"}},
+      {"ShapeAdded":{"shapeId":"reference_shape_1","baseShapeId":"$reference","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"reference_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"user_shape"}},"consumingParameterId":"$referenceInner"}}}},
+      {"ShapeAdded":{"shapeId":"nullable_shape_2","baseShapeId":"$nullable","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"nullable_shape_2","providerDescriptor":{"ShapeProvider":{"shapeId":"reference_shape_1"}},"consumingParameterId":"$nullableInner"}}}},
+      {"ShapeAdded":{"shapeId":"list_shape_1","baseShapeId":"$list","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"list_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"reference_shape_1"}},"consumingParameterId":"$listItem"}}}},
+      {"FieldAdded":{"fieldId":"field_1","shapeId":"user_shape","name":"name","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_1","shapeId":"string_shape_1"}}}},
+      {"FieldAdded":{"fieldId":"field_2","shapeId":"user_shape","name":"nickname","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_2","shapeId":"optional_shape_1"}}}},
+      {"FieldAdded":{"fieldId":"field_3","shapeId":"user_shape","name":"age","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_3","shapeId":"one_of_shape_1"}}}},
+      {"FieldAdded":{"fieldId":"field_4","shapeId":"user_shape","name":"friends","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_4","shapeId":"list_shape_1"}}}},
+      {"FieldAdded":{"fieldId":"field_5","shapeId":"user_shape","name":"manager","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_5","shapeId":"nullable_shape_2"}}}},
+    ]))
+    .expect("should be able to deserialize test events");
+    let shape_projection = ShapeProjection::from(events);
+
+    let user_schema = export_json_schema(&shape_projection, &String::from("user_shape"))
+      .expect("should be able to export an existing shape");
+    assert_json_snapshot!("can_export_shapes_as_json_schema__user_schema", user_schema);
+
+    let list_schema = export_json_schema(&shape_projection, &String::from("list_shape_1"))
+      .expect("should be able to export an existing shape");
+    assert_json_snapshot!("can_export_shapes_as_json_schema__list_schema", list_schema);
+
+    assert!(export_json_schema(&shape_projection, &String::from("not_a_shape")).is_none());
+  }
+}
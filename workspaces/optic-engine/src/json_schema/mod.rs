@@ -0,0 +1,5 @@
+mod export;
+mod schema;
+
+pub use export::{export_json_schema, JSON_SCHEMA_DIALECT};
+pub(crate) use schema::{SchemaBuilder, SchemaDialect};
@@ -0,0 +1,242 @@
+use crate::queries::ShapeQueries;
+use crate::state::shape::{ShapeId, ShapeKind};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The ways documents describing shapes as schemas differ from each other
+pub trait SchemaDialect {
+  /// The schema pointing at a shape described once, under the given name
+  fn reference(&self, name: &str) -> JsonValue;
+
+  /// The schema of a value described by a schema, or null
+  fn nullable(&self, schema: JsonValue) -> JsonValue;
+
+  /// The schema pointing at a shape containing itself, when it needn't be described separately
+  fn self_reference(&self, _shape_id: &ShapeId) -> Option<JsonValue> {
+    None
+  }
+}
+
+/// Describes shapes as schemas. Referenced shapes, and shapes containing themselves, are
+/// described once as components named after them, every other shape is described inline.
+pub struct SchemaBuilder<'a, D> {
+  shape_queries: ShapeQueries<'a>,
+  dialect: D,
+  components: BTreeMap<String, JsonValue>,
+  component_names: HashMap<ShapeId, String>,
+  /// Shapes to describe as components rather than inline
+  component_shape_ids: HashSet<ShapeId>,
+  /// Shapes being described, to tell when a shape contains itself
+  described_shape_ids: HashSet<ShapeId>,
+}
+
+impl<'a, D> SchemaBuilder<'a, D>
+where
+  D: SchemaDialect,
+{
+  pub fn new(shape_queries: ShapeQueries<'a>, dialect: D) -> Self {
+    Self {
+      shape_queries,
+      dialect,
+      components: BTreeMap::new(),
+      component_names: HashMap::new(),
+      component_shape_ids: HashSet::new(),
+      described_shape_ids: HashSet::new(),
+    }
+  }
+
+  /// The schemas of the shapes described once, by name
+  pub fn components(&self) -> &BTreeMap<String, JsonValue> {
+    &self.components
+  }
+
+  pub fn schema(&mut self, shape_id: &ShapeId) -> JsonValue {
+    let shape_id = &self.resolve_own_shape_id(shape_id);
+    if let ShapeKind::ReferenceKind = self.shape_queries.resolve_to_own_core_shape(shape_id) {
+      // references point at components already, cycles are told by the shapes referenced
+      return self.own_schema(shape_id);
+    }
+    let is_described_component = self
+      .component_names
+      .get(shape_id)
+      .is_some_and(|component_name| self.components.contains_key(component_name));
+    if is_described_component {
+      return self.component_ref(shape_id);
+    }
+    if self.described_shape_ids.contains(shape_id) {
+      if let Some(self_reference) = self.dialect.self_reference(shape_id) {
+        return self_reference;
+      }
+      self.component_shape_ids.insert(shape_id.clone());
+      return self.component_ref(shape_id);
+    }
+
+    self.described_shape_ids.insert(shape_id.clone());
+    let schema = self.own_schema(shape_id);
+    self.described_shape_ids.remove(shape_id);
+
+    if self.component_shape_ids.contains(shape_id) {
+      let component_name = self.component_name(shape_id);
+      self.components.insert(component_name, schema);
+      self.component_ref(shape_id)
+    } else {
+      schema
+    }
+  }
+
+  /// The schema of a value that might be left out, and whether it's required
+  pub fn value_schema(&mut self, shape_id: &ShapeId) -> (JsonValue, bool) {
+    let shape_id = &self.resolve_own_shape_id(shape_id);
+    let core_shape_kind = self
+      .shape_queries
+      .resolve_to_own_core_shape(shape_id)
+      .clone();
+    match core_shape_kind {
+      ShapeKind::OptionalKind => {
+        let inner_shape_id = self.inner_shape_id(shape_id, &core_shape_kind);
+        (self.schema(&inner_shape_id), false)
+      }
+      _ => (self.schema(shape_id), true),
+    }
+  }
+
+  /// The schemas of the fields of an object, by name, and whether they're required
+  pub fn object_fields(&mut self, shape_id: &ShapeId) -> Vec<(String, JsonValue, bool)> {
+    let fields = self
+      .shape_queries
+      .resolve_shape_field_id_and_names(shape_id)
+      .map(|(field_id, name)| {
+        let field_shape_id = self
+          .shape_queries
+          .resolve_field_shape_node(field_id)
+          .expect("field node should have an edge to a shape node describing its value");
+        (name.clone(), field_shape_id)
+      })
+      .collect::<BTreeMap<_, _>>();
+
+    fields
+      .into_iter()
+      .map(|(name, field_shape_id)| {
+        let (schema, required) = self.value_schema(&field_shape_id);
+        (name, schema, required)
+      })
+      .collect()
+  }
+
+  fn own_schema(&mut self, shape_id: &ShapeId) -> JsonValue {
+    let core_shape_kind = self
+      .shape_queries
+      .resolve_to_own_core_shape(shape_id)
+      .clone();
+
+    match core_shape_kind {
+      ShapeKind::ObjectKind => {
+        let mut properties = JsonMap::new();
+        let mut required = vec![];
+        for (name, schema, is_required) in self.object_fields(shape_id) {
+          if is_required {
+            required.push(name.clone());
+          }
+          properties.insert(name, schema);
+        }
+
+        let mut schema = json!({ "type": "object", "properties": properties });
+        if !required.is_empty() {
+          schema["required"] = JsonValue::from(required);
+        }
+        schema
+      }
+      ShapeKind::ListKind => {
+        let item_shape_id = self.inner_shape_id(shape_id, &core_shape_kind);
+        json!({ "type": "array", "items": self.schema(&item_shape_id) })
+      }
+      ShapeKind::MapKind => {
+        let value_shape_id = self.inner_shape_id(shape_id, &core_shape_kind);
+        json!({ "type": "object", "additionalProperties": self.schema(&value_shape_id) })
+      }
+      ShapeKind::OneOfKind => {
+        let item_schemas = self
+          .shape_queries
+          .resolve_parameters_to_shapes(shape_id)
+          .into_iter()
+          .map(|(_, item_shape_id)| self.schema(&item_shape_id))
+          .collect::<Vec<_>>();
+        json!({ "oneOf": item_schemas })
+      }
+      ShapeKind::NullableKind => {
+        let inner_shape_id = self.inner_shape_id(shape_id, &core_shape_kind);
+        let inner_schema = self.schema(&inner_shape_id);
+        self.dialect.nullable(inner_schema)
+      }
+      ShapeKind::OptionalKind | ShapeKind::IdentifierKind => {
+        let inner_shape_id = self.inner_shape_id(shape_id, &core_shape_kind);
+        self.schema(&inner_shape_id)
+      }
+      ShapeKind::ReferenceKind => {
+        let referenced_shape_id =
+          self.resolve_own_shape_id(&self.inner_shape_id(shape_id, &core_shape_kind));
+        if self.dialect.self_reference(&referenced_shape_id).is_none() {
+          self.component_shape_ids.insert(referenced_shape_id.clone());
+        }
+        self.schema(&referenced_shape_id)
+      }
+      ShapeKind::StringKind => json!({ "type": "string" }),
+      ShapeKind::NumberKind => json!({ "type": "number" }),
+      ShapeKind::BooleanKind => json!({ "type": "boolean" }),
+      ShapeKind::AnyKind | ShapeKind::UnknownKind => json!({}),
+    }
+  }
+
+  /// Follows the shapes a shape descends from, up to the one descending from a core shape
+  fn resolve_own_shape_id(&self, shape_id: &ShapeId) -> ShapeId {
+    let mut shape_id = shape_id;
+    while let Some(base_shape_id) = self.shape_queries.resolve_base_shape_id(shape_id) {
+      shape_id = base_shape_id;
+    }
+    shape_id.clone()
+  }
+
+  fn component_ref(&mut self, shape_id: &ShapeId) -> JsonValue {
+    let component_name = self.component_name(shape_id);
+    self.dialect.reference(&component_name)
+  }
+
+  /// Components are named after their shape, falling back to its id for unnamed shapes and
+  /// names that are taken already
+  fn component_name(&mut self, shape_id: &ShapeId) -> String {
+    if let Some(component_name) = self.component_names.get(shape_id) {
+      return component_name.clone();
+    }
+
+    let name = match self.shape_queries.resolve_shape_name(shape_id) {
+      Some(name) => name
+        .chars()
+        .map(|c| match c {
+          'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+          _ => '_',
+        })
+        .collect(),
+      None => shape_id.clone(),
+    };
+    let component_name = if self.component_names.values().any(|taken| *taken == name) {
+      shape_id.clone()
+    } else {
+      name
+    };
+
+    self
+      .component_names
+      .insert(shape_id.clone(), component_name.clone());
+    component_name
+  }
+
+  fn inner_shape_id(&self, shape_id: &ShapeId, core_shape_kind: &ShapeKind) -> ShapeId {
+    let parameter_id = core_shape_kind
+      .get_parameter_descriptor()
+      .expect("shape kinds wrapping other shapes should have a parameter descriptor")
+      .shape_parameter_id;
+    self
+      .shape_queries
+      .resolve_parameter_to_shape(shape_id, &String::from(parameter_id))
+  }
+}
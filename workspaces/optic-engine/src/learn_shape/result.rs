@@ -1,6 +1,6 @@
 use crate::commands::shape as shape_commands;
 use crate::commands::{ShapeCommand, SpecCommand};
-use crate::shapes::JsonTrail;
+use crate::shapes::{JsonTrail, JsonTrailPathComponent, JsonTrailPattern};
 use crate::state::shape::{FieldId, ShapeId, ShapeKind, ShapeKindDescriptor};
 use crate::state::SpecIdGenerator;
 use crate::BodyDescriptor;
@@ -60,11 +60,95 @@ impl TrailObservationsResult {
     self.values_by_trail.remove(trail)
   }
 
+  /// Leave out the observations of trails matching any of the ignored trails, as if the values
+  /// there were never observed. Objects lose the ignored keys, lists with ignored items are
+  /// learned as lists of unknown items.
+  pub fn without_trails(mut self, ignored_trails: &[JsonTrailPattern]) -> Self {
+    if ignored_trails.is_empty() {
+      return self;
+    }
+
+    let (ignored_values_by_trail, values_by_trail): (HashMap<_, _>, HashMap<_, _>) = self
+      .values_by_trail
+      .into_iter()
+      .partition(|(trail, _)| ignored_trails.iter().any(|pattern| pattern.matches(trail)));
+    self.values_by_trail = values_by_trail;
+
+    for mut parent_trail in ignored_values_by_trail.into_keys() {
+      let ignored_component = parent_trail.pop();
+      let parent_values = match self.values_by_trail.get_mut(&parent_trail) {
+        Some(parent_values) => parent_values,
+        None => continue,
+      };
+      match ignored_component {
+        Some(JsonTrailPathComponent::JsonObjectKey { key }) => {
+          let field_sets = std::mem::take(&mut parent_values.field_sets);
+          for mut field_set in field_sets {
+            field_set.remove(&key);
+            parent_values.insert_field_set(field_set);
+          }
+        }
+        Some(JsonTrailPathComponent::JsonArrayItem { .. }) => parent_values.was_empty_array = true,
+        _ => {}
+      }
+    }
+
+    self
+  }
+
+  /// Merge the observations of all values of objects that look like maps, so a single shape
+  /// can be learned for them. Maps are collapsed parents first, as merging values can reveal
+  /// nested maps.
+  fn collapse_maps(&mut self) {
+    let mut collapsed_map_trails = HashSet::new();
+
+    loop {
+      let next_map_trail = self
+        .values_by_trail
+        .values()
+        .filter(|trail_values| !collapsed_map_trails.contains(&trail_values.trail))
+        .filter(|trail_values| trail_values.is_map_like())
+        .map(|trail_values| trail_values.trail.clone())
+        .min();
+
+      let map_trail = match next_map_trail {
+        Some(map_trail) => map_trail,
+        None => break,
+      };
+
+      let value_trails = self
+        .values_by_trail
+        .keys()
+        .filter_map(|trail| {
+          trail
+            .as_map_value_of(&map_trail)
+            .map(|value_trail| (trail.clone(), value_trail))
+        })
+        .collect::<Vec<_>>();
+
+      for (trail, value_trail) in value_trails {
+        let mut trail_values = self.values_by_trail.remove(&trail).unwrap();
+        trail_values.trail = value_trail.clone();
+
+        let existing_trail_values = self
+          .values_by_trail
+          .entry(value_trail)
+          .or_insert_with_key(TrailValues::new);
+
+        existing_trail_values.union(trail_values);
+      }
+
+      collapsed_map_trails.insert(map_trail);
+    }
+  }
+
   pub fn into_commands(
     mut self,
     id_generator: &mut impl SpecIdGenerator,
     root_trail: &JsonTrail,
   ) -> (Option<String>, impl Iterator<Item = SpecCommand>) {
+    self.collapse_maps();
+
     let sorted_trails = {
       let mut trails = self
         .values_by_trail
@@ -136,6 +220,15 @@ fn shape_prototypes_trails<'a>(
 
           trails
         }
+        ShapePrototypeDescriptor::MapOfShape { .. } => {
+          let value_prototype = prototypes_by_trail.get(&shape_prototype.trail.with_map_value());
+          let nested_trails = shape_prototypes_trails(value_prototype, prototypes_by_trail);
+
+          let mut trails = vec![shape_prototype.trail.clone()];
+          trails.extend(nested_trails);
+
+          trails
+        }
         ShapePrototypeDescriptor::ObjectWithFields { ref fields } => {
           let nested_trails = fields.iter().flat_map(|field_prototype_descriptor| {
             let field_prototype = prototypes_by_trail.get(
@@ -266,6 +359,27 @@ fn shape_prototypes_to_commands(
 
           Some(commands)
         }
+        ShapePrototypeDescriptor::MapOfShape { value_shape_id } => {
+          let commands = vec![
+            ShapeCommand::add_shape(
+              shape_prototype.id.clone(),
+              ShapeKind::MapKind,
+              String::from(""),
+            ),
+            ShapeCommand::set_parameter_shape(
+              shape_prototype.id,
+              String::from(
+                ShapeKind::MapKind
+                  .get_parameter_descriptor()
+                  .unwrap()
+                  .shape_parameter_id,
+              ),
+              value_shape_id,
+            ),
+          ];
+
+          Some(commands)
+        }
         ShapePrototypeDescriptor::ObjectWithFields { fields } => {
           let mut commands = vec![];
           commands.push(ShapeCommand::add_shape(
@@ -323,12 +437,24 @@ impl From<HashMap<JsonTrail, TrailValues>> for TrailObservationsResult {
 
 pub type FieldSet = HashSet<String>;
 
+const MAP_MIN_DYNAMIC_KEYS: usize = 2;
+const MAP_CHURN_MIN_FIELD_SETS: usize = 3;
+const MAP_CHURN_MIN_KEYS: usize = 8;
+
+fn is_dynamic_key(key: &str) -> bool {
+  let is_numeric = !key.is_empty() && key.chars().all(|c| c.is_ascii_digit());
+  is_numeric || uuid::Uuid::parse_str(key).is_ok()
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TrailValues {
   pub trail: JsonTrail,
   pub was_string: bool,
   pub was_number: bool,
+  /// Text that could stand for a number, as found in query strings
+  #[serde(default)]
+  pub was_numeric_string: bool,
   pub was_boolean: bool,
   pub was_null: bool,
   pub was_array: bool,
@@ -351,6 +477,7 @@ impl TrailValues {
       trail: json_trail.clone(),
       was_string: false,
       was_number: false,
+      was_numeric_string: false,
       was_boolean: false,
       was_null: false,
       was_array: false,
@@ -363,6 +490,7 @@ impl TrailValues {
   pub fn union(&mut self, new_values: TrailValues) {
     self.was_string = self.was_string || new_values.was_string;
     self.was_number = self.was_number || new_values.was_number;
+    self.was_numeric_string = self.was_numeric_string || new_values.was_numeric_string;
     self.was_boolean = self.was_boolean || new_values.was_boolean;
     self.was_null = self.was_null || new_values.was_null;
     self.was_array = self.was_array || new_values.was_array;
@@ -381,6 +509,7 @@ impl TrailValues {
   pub fn was_unknown(&self) -> bool {
     !self.was_string
       && !self.was_number
+      && !self.was_numeric_string
       && !self.was_boolean
       && !self.was_null
       && !self.was_array
@@ -402,6 +531,62 @@ impl TrailValues {
     }
   }
 
+  /// Whether the observed objects look like maps rather than records with fixed fields: either
+  /// several keys were observed which all look like identifiers, or keys keep changing between
+  /// observations.
+  pub fn is_map_like(&self) -> bool {
+    if !self.was_object {
+      return false;
+    }
+
+    let (keys, _) = self.field_keys();
+    if keys.is_empty() {
+      return false;
+    }
+
+    if keys.len() >= MAP_MIN_DYNAMIC_KEYS && keys.iter().all(|key| is_dynamic_key(key)) {
+      return true;
+    }
+
+    // high churn: plenty of distinct keys, none of which shows up in most of the objects
+    self.field_sets.len() >= MAP_CHURN_MIN_FIELD_SETS
+      && keys.len() >= MAP_CHURN_MIN_KEYS
+      && keys.iter().all(|key| {
+        let occurrences = self
+          .field_sets
+          .iter()
+          .filter(|field_set| field_set.contains(key))
+          .count();
+        occurrences * 2 <= self.field_sets.len()
+      })
+  }
+
+  /// All keys observed across the field sets, sorted, and those keys that were missing from at
+  /// least one of them.
+  pub fn field_keys(&self) -> (Vec<String>, Vec<String>) {
+    let all_keys_set = self
+      .field_sets
+      .iter()
+      .fold(HashSet::new(), |all_keys: HashSet<String>, field_set| {
+        all_keys.union(&field_set).cloned().collect()
+      });
+
+    let optional_keys_set =
+      self
+        .field_sets
+        .iter()
+        .fold(HashSet::new(), |optional_keys, field_set| {
+          let missing_keys = all_keys_set.difference(&field_set).cloned().collect();
+          optional_keys.union(&missing_keys).cloned().collect()
+        });
+
+    let mut all_keys = all_keys_set.into_iter().collect::<Vec<_>>();
+    all_keys.sort();
+
+    let optional_keys = optional_keys_set.into_iter().collect::<Vec<_>>();
+    (all_keys, optional_keys)
+  }
+
   fn into_shape_prototype(
     self,
     id_generator: &mut impl SpecIdGenerator,
@@ -415,7 +600,8 @@ impl TrailValues {
       } else {
         None
       },
-      if self.was_number {
+      // numeric strings are numbers, unless other strings were observed as well
+      if self.was_number || (self.was_numeric_string && !self.was_string) {
         Some(ShapePrototypeDescriptor::PrimitiveKind {
           base_shape_kind: ShapeKind::NumberKind,
         })
@@ -449,54 +635,40 @@ impl TrailValues {
         None
       },
       if self.was_object {
-        let (field_keys, optional_keys) = {
-          let all_keys_set = self
-            .field_sets
-            .iter()
-            .fold(HashSet::new(), |all_keys: HashSet<String>, field_set| {
-              all_keys.union(&field_set).cloned().collect()
-            });
-
-          let optional_keys_set =
-            self
-              .field_sets
-              .iter()
-              .fold(HashSet::new(), |optional_keys, field_set| {
-                let missing_keys = all_keys_set.difference(&field_set).cloned().collect();
-                optional_keys.union(&missing_keys).cloned().collect()
-              });
-
-          let mut all_keys = all_keys_set.into_iter().collect::<Vec<_>>();
-          all_keys.sort();
-
-          let optional_keys = optional_keys_set.into_iter().collect::<Vec<_>>();
-          (all_keys, optional_keys)
-        };
-
-        let field_descriptors = field_keys
-          .into_iter()
-          .map(|key| {
-            let field_trail = self.trail.with_object_key(key.clone());
-            let field_shape_prototype = existing_prototypes.get(&field_trail).expect(
-              "object field shape prototype should have been generated before its parent object",
-            );
-            let is_optional = optional_keys.contains(&key);
-
-            FieldPrototypeDescriptor {
-              field_id: id_generator.field(),
-              key,
-              optional_shape_id: match is_optional {
-                true => Some(id_generator.shape()),
-                false => None,
-              },
-              value_shape_id: field_shape_prototype.id.clone(),
-            }
+        // maps were collapsed into a single value trail before generating prototypes
+        let map_value_prototype = existing_prototypes.get(&self.trail.with_map_value());
+        if let Some(map_value_prototype) = map_value_prototype {
+          Some(ShapePrototypeDescriptor::MapOfShape {
+            value_shape_id: map_value_prototype.id.clone(),
           })
-          .collect::<Vec<_>>();
+        } else {
+          let (field_keys, optional_keys) = self.field_keys();
 
-        Some(ShapePrototypeDescriptor::ObjectWithFields {
-          fields: field_descriptors,
-        })
+          let field_descriptors = field_keys
+            .into_iter()
+            .map(|key| {
+              let field_trail = self.trail.with_object_key(key.clone());
+              let field_shape_prototype = existing_prototypes.get(&field_trail).expect(
+                "object field shape prototype should have been generated before its parent object",
+              );
+              let is_optional = optional_keys.contains(&key);
+
+              FieldPrototypeDescriptor {
+                field_id: id_generator.field(),
+                key,
+                optional_shape_id: match is_optional {
+                  true => Some(id_generator.shape()),
+                  false => None,
+                },
+                value_shape_id: field_shape_prototype.id.clone(),
+              }
+            })
+            .collect::<Vec<_>>();
+
+          Some(ShapePrototypeDescriptor::ObjectWithFields {
+            fields: field_descriptors,
+          })
+        }
       } else {
         None
       },
@@ -570,6 +742,9 @@ enum ShapePrototypeDescriptor {
   ObjectWithFields {
     fields: Vec<FieldPrototypeDescriptor>,
   },
+  MapOfShape {
+    value_shape_id: ShapeId,
+  },
   ListOfShape {
     item_shape_id: ShapeId,
     item_is_unknown: bool,
@@ -592,12 +767,13 @@ struct FieldPrototypeDescriptor {
 mod test {
   use super::*;
   use crate::projections::SpecProjection;
-  use crate::shapes::diff as diff_shapes;
+  use crate::shapes::{diff as diff_shapes, diff_ignoring_trails as diff_shapes_ignoring_trails};
   use crate::state::body::BodyDescriptor;
   use crate::{learn_shape::observe_body_trails, Body};
   use cqrs_core::Aggregate;
   use insta::assert_debug_snapshot;
   use serde_json::json;
+  use std::str::FromStr;
 
   #[test]
   fn trail_observations_can_generate_commands_for_primitive_bodies() {
@@ -807,6 +983,118 @@ mod test {
     );
   }
 
+  #[test]
+  fn trail_observations_can_leave_out_ignored_trails() {
+    let body = BodyDescriptor::from(json!({
+      "id": 1,
+      "debug": { "sql": "SELECT 1" },
+      "traces": [{ "span": "a" }]
+    }));
+    let ignored_trails = vec![
+      JsonTrailPattern::from_str("$.debug").unwrap(),
+      JsonTrailPattern::from_str("$.traces[*]").unwrap(),
+    ];
+
+    let observations = observe_body_trails(body.clone()).without_trails(&ignored_trails);
+    assert!(observations
+      .trails()
+      .all(|trail| !ignored_trails.iter().any(|pattern| pattern.matches(trail))));
+
+    let mut test_id_generator = TestIdGenerator::default();
+    let (root_shape_id, commands) =
+      collect_commands(observations.into_commands(&mut test_id_generator, &JsonTrail::empty()));
+    let field_names = commands
+      .iter()
+      .filter_map(|command| match command {
+        SpecCommand::ShapeCommand(ShapeCommand::AddField(add_field)) => {
+          Some(add_field.name.as_str())
+        }
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(field_names, vec!["id", "traces"]);
+
+    let spec_projection = assert_valid_commands(commands.clone());
+    let results = diff_shapes_ignoring_trails(
+      spec_projection.shape(),
+      Some(body),
+      root_shape_id.as_ref().unwrap(),
+      &ignored_trails,
+    );
+    assert!(results.is_empty(), "ignored trails should not be diffed");
+  }
+
+  #[test]
+  fn trail_observations_can_generate_commands_for_map_bodies() {
+    let id_keyed_bodies = vec![
+      BodyDescriptor::from(json!({
+        "3f2b8c1e-6a4d-4b8e-9c1a-2d5e7f9a0b3c": { "name": "first", "count": 1 },
+        "a1b2c3d4-e5f6-4a7b-8c9d-0e1f2a3b4c5d": { "name": "second" }
+      })),
+      BodyDescriptor::from(json!({
+        "9e8d7c6b-5a4f-4e3d-8c2b-1a0f9e8d7c6b": { "name": "third", "count": 3 }
+      })),
+    ];
+    let churning_bodies = (0..4)
+      .map(|n| {
+        let mut fields = serde_json::Map::new();
+        fields.insert(format!("user-{}", n * 2), json!(true));
+        fields.insert(format!("user-{}", n * 2 + 1), json!(false));
+        BodyDescriptor::from(serde_json::Value::Object(fields))
+      })
+      .collect::<Vec<_>>();
+
+    let mut test_id_generator = TestIdGenerator::default();
+
+    let mut id_keyed_observations = TrailObservationsResult::default();
+    for body in id_keyed_bodies.iter() {
+      id_keyed_observations.union(observe_body_trails(body.clone()).normalized());
+    }
+    let id_keyed_results = collect_commands(
+      id_keyed_observations.into_commands(&mut test_id_generator, &JsonTrail::empty()),
+    );
+    assert!(id_keyed_results.0.is_some());
+    let spec_projection = assert_valid_commands(id_keyed_results.1.clone());
+    assert_no_shape_diffs(
+      &spec_projection,
+      id_keyed_results.0.as_ref().unwrap(),
+      id_keyed_bodies,
+    );
+    assert_debug_snapshot!(
+      "trail_observations_can_generate_commands_for_map_bodies__id_keyed_results",
+      &id_keyed_results
+    );
+
+    let mut churning_observations = TrailObservationsResult::default();
+    for body in churning_bodies.iter() {
+      churning_observations.union(observe_body_trails(body.clone()).normalized());
+    }
+    let churning_results = collect_commands(
+      churning_observations.into_commands(&mut test_id_generator, &JsonTrail::empty()),
+    );
+    assert!(churning_results.0.is_some());
+    let spec_projection = assert_valid_commands(churning_results.1.clone());
+    assert_no_shape_diffs(
+      &spec_projection,
+      churning_results.0.as_ref().unwrap(),
+      churning_bodies,
+    );
+    assert_debug_snapshot!(
+      "trail_observations_can_generate_commands_for_map_bodies__churning_results",
+      &churning_results
+    );
+
+    let single_key_observations =
+      observe_body_trails(BodyDescriptor::from(json!({ "1": { "name": "first" } }))).normalized();
+    let single_key_values = single_key_observations
+      .get(&JsonTrail::empty())
+      .expect("root trail should have been observed");
+    assert!(
+      !single_key_values.is_map_like(),
+      "a single identifier-like key isn't enough to take an object for a map"
+    );
+  }
+
   #[test]
   fn trail_observations_can_generate_commands_for_object_with_optional_fields() {
     let primitive_object_bodies = vec![
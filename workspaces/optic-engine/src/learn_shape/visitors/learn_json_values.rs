@@ -113,7 +113,9 @@ impl BodyPrimitiveVisitor<TrailValues> for LearnPrimitiveVisitor {
     match body {
       BodyDescriptor::Boolean => trail_values.was_boolean = true,
       BodyDescriptor::Number => trail_values.was_number = true,
-      BodyDescriptor::String => trail_values.was_string = true,
+      BodyDescriptor::NumericString => trail_values.was_numeric_string = true,
+      // binary content is documented as a string, for lack of a core shape of its own
+      BodyDescriptor::String | BodyDescriptor::Binary => trail_values.was_string = true,
       BodyDescriptor::Null => trail_values.was_null = true,
       _ => unreachable!("should not call primitive visitor without a primitive value"),
     }
@@ -1,9 +1,12 @@
 #![allow(dead_code, unused_imports, unused_variables)]
 
+mod changelog;
 mod commands;
 mod events;
 mod interactions;
+mod json_schema;
 mod learn_shape;
+mod openapi;
 mod projections;
 mod protos;
 mod queries;
@@ -14,36 +17,57 @@ mod state;
 #[cfg(feature = "streams")]
 pub mod streams;
 
-pub use commands::{CommandContext, EndpointCommand, RfcCommand, SpecCommand, SpecCommandHandler};
+pub use changelog::{BodyChange, ChangeKind, Changelog, EndpointChange, FieldChange, FieldSummary};
+pub use commands::{
+  CommandContext, EndpointCommand, RfcCommand, ShapeCommand, SpecCommand, SpecCommandHandler,
+};
 pub use cqrs_core::Aggregate;
 pub use events::{
   http_interaction::{
     ArbitraryData, Body, HttpInteraction, QueryParametersData, Request, Response,
   },
-  RfcEvent, SpecChunkEvent, SpecEvent,
+  RfcEvent, SpecChunkEvent, SpecEvent, ROOT_BATCH_ID,
 };
 pub use interactions::result::{BodyAnalysisLocation, BodyAnalysisResult, InteractionDiffResult};
 pub use interactions::{
-  analyze_documented_bodies, analyze_undocumented_bodies, AnalyzeUndocumentedBodiesConfig,
+  analyze_documented_bodies, analyze_undocumented_bodies, AnalyzeDocumentedBodiesConfig,
+  AnalyzeUndocumentedBodiesConfig,
 };
 pub use interactions::{diff as diff_interaction, DiffConfig as DiffInteractionConfig};
+pub use interactions::{Glob, IgnoreRules, StatusCodePattern};
+pub use json_schema::export_json_schema;
 pub use learn_shape::{TrailObservationsResult, TrailValues};
+pub use openapi::{export_openapi, import_openapi, OpenApiExportConfig};
 pub use projections::{
-  EndpointProjection, LearnedShapeDiffAffordancesProjection, LearnedUndocumentedBodiesProjection,
-  ResponseBodyDescriptor, ShapeProjection, SpecAssemblerProjection, SpecProjection,
+  EndpointProjection, LearnedPaths, LearnedShapeDiffAffordancesProjection,
+  LearnedUndocumentedBodiesProjection, LearnedUndocumentedPathsProjection, ResponseBodyDescriptor,
+  ShapeProjection, SpecAssemblerProjection, SpecChunkConflict, SpecChunkConflictKind,
+  SpecProjection,
 };
 pub use protos::shapehash;
 pub use queries::endpoint::EndpointQueries;
 pub use queries::shape::ShapeQueries;
 pub use queries::spectacle::spec_choices::{JsonType, ShapeChoiceQueries};
-pub use shapes::{diff as diff_shape, JsonTrail};
+pub use shapes::{diff as diff_shape, JsonTrail, JsonTrailPattern};
 pub use spec::append_batch as append_batch_to_spec;
+pub use spec::compact_events as compact_spec_events;
+pub use spec::revert_batch_commands;
 pub use state::endpoint::ResponseId;
 pub use state::{body::BodyDescriptor, SpecIdGenerator, TaggedInput, Tags};
 
 pub mod errors {
-  pub use super::events::EventLoadingError;
+  pub use super::changelog::ChangelogError;
+  pub use super::events::{EventLoadingError, UnknownBatchCommitError};
+  pub use super::interactions::StatusCodePatternError;
+  pub use super::openapi::OpenApiImportError;
+  pub use super::shapes::JsonTrailPatternError;
+  pub use super::spec::RevertBatchError;
+
+  #[cfg(feature = "avro")]
+  pub use super::events::http_interaction::HttpInteractionAvroError;
 
+  #[cfg(feature = "streams")]
+  pub use super::streams::http_interaction::HarReaderError;
   #[cfg(feature = "streams")]
   pub use super::streams::spec_chunks::{SpecChunkLoaderError, SpecChunkWriterError};
 }
@@ -0,0 +1,335 @@
+use crate::json_schema::{SchemaBuilder, SchemaDialect};
+use crate::projections::endpoint::QueryParametersDescriptor;
+use crate::projections::SpecProjection;
+use crate::queries::{EndpointQueries, ShapeQueries};
+use crate::state::endpoint::{
+  HttpMethod, PathComponentId, RequestId, ResponseId, ShapedRequestParameterShapeDescriptor,
+};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::collections::BTreeMap;
+
+pub const OPENAPI_VERSION: &str = "3.0.3";
+
+type JsonObject = JsonMap<String, JsonValue>;
+
+#[derive(Debug, Clone)]
+pub struct OpenApiExportConfig {
+  pub title: String,
+  pub version: String,
+}
+
+impl Default for OpenApiExportConfig {
+  fn default() -> Self {
+    Self {
+      title: String::from("Optic API"),
+      version: String::from("0.0.0"),
+    }
+  }
+}
+
+/// Describe every endpoint of a spec as an OpenAPI 3.0 document. Referenced shapes, and shapes
+/// containing themselves, are described once as component schemas named after them, every other
+/// shape is described inline.
+pub fn export_openapi(spec_projection: &SpecProjection, config: &OpenApiExportConfig) -> JsonValue {
+  let endpoint_queries = EndpointQueries::new(spec_projection.endpoint());
+  let mut schemas = SchemaBuilder::new(ShapeQueries::new(spec_projection.shape()), OpenApiDialect);
+
+  let mut paths = JsonMap::new();
+  for (path_id, path_pattern) in endpoint_queries.resolve_path_patterns() {
+    let mut path_item = JsonMap::new();
+    for method in endpoint_queries.resolve_path_methods(path_id) {
+      if let Some(operation) = operation_object(&endpoint_queries, &mut schemas, path_id, method) {
+        path_item.insert(method.to_lowercase(), operation);
+      }
+    }
+    if path_item.is_empty() {
+      continue;
+    }
+
+    // the segments of a pattern stand in for the values of its parameters
+    let path_parameters = endpoint_queries
+      .resolve_path_parameter_values(path_id, &path_pattern)
+      .into_iter()
+      .map(|path_parameter| {
+        let schema = match path_parameter.shape {
+          Some(shape) if !shape.is_removed => {
+            with_string_constraints(schemas.schema(&shape.shape_id), shape)
+          }
+          _ => json!({ "type": "string" }),
+        };
+        json!({
+          "name": path_parameter.name,
+          "in": "path",
+          "required": true,
+          "schema": schema,
+        })
+      })
+      .collect::<Vec<_>>();
+    if !path_parameters.is_empty() {
+      path_item.insert(String::from("parameters"), JsonValue::from(path_parameters));
+    }
+
+    paths.insert(path_pattern, JsonValue::Object(path_item));
+  }
+
+  let mut document = json!({
+    "openapi": OPENAPI_VERSION,
+    "info": {
+      "title": config.title,
+      "version": config.version,
+    },
+    "paths": paths,
+  });
+  if !schemas.components().is_empty() {
+    document["components"] = json!({ "schemas": schemas.components() });
+  }
+
+  document
+}
+
+fn operation_object(
+  endpoint_queries: &EndpointQueries,
+  schemas: &mut SchemaBuilder<OpenApiDialect>,
+  path_id: &PathComponentId,
+  method: &HttpMethod,
+) -> Option<JsonValue> {
+  let requests = endpoint_queries
+    .resolve_requests(path_id, method)
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+  let responses = endpoint_queries
+    .resolve_endpoint_responses(path_id, method)
+    .collect::<Vec<_>>();
+  if requests.is_empty() && responses.is_empty() {
+    return None;
+  }
+
+  let mut parameters = vec![];
+  if let Some((_, QueryParametersDescriptor { shape: Some(shape) })) =
+    endpoint_queries.resolve_endpoint_query_params(path_id, method)
+  {
+    if !shape.is_removed {
+      for (name, schema, required) in schemas.object_fields(&shape.shape_id) {
+        parameters.push(json!({
+          "name": name,
+          "in": "query",
+          "required": required,
+          "schema": schema,
+        }));
+      }
+    }
+  }
+
+  let mut request_headers = BTreeMap::new();
+  let mut request_content = JsonMap::new();
+  for (request_id, request_descriptor) in &requests {
+    request_headers.append(&mut header_schemas(endpoint_queries, schemas, request_id));
+    if let Some(body) = &request_descriptor.body {
+      request_content.insert(
+        body.http_content_type.clone(),
+        json!({ "schema": schemas.schema(&body.root_shape_id) }),
+      );
+    }
+  }
+  for (name, (schema, required)) in request_headers {
+    parameters.push(json!({
+      "name": name,
+      "in": "header",
+      "required": required,
+      "schema": schema,
+    }));
+  }
+
+  // content and headers of responses, by status code
+  let mut response_objects: BTreeMap<String, (JsonObject, JsonObject)> = BTreeMap::new();
+  for (status_code, response_id, response_descriptor) in responses {
+    let (content, headers) = response_objects.entry(status_code.to_string()).or_default();
+    for (name, (schema, required)) in header_schemas(endpoint_queries, schemas, response_id) {
+      headers.insert(name, json!({ "required": required, "schema": schema }));
+    }
+    if let Some(body) = &response_descriptor.body {
+      content.insert(
+        body.http_content_type.clone(),
+        json!({ "schema": schemas.schema(&body.root_shape_id) }),
+      );
+    }
+  }
+
+  let mut responses = JsonMap::new();
+  for (status_code, (content, headers)) in response_objects {
+    let mut response = json!({ "description": "" });
+    if !headers.is_empty() {
+      response["headers"] = JsonValue::Object(headers);
+    }
+    if !content.is_empty() {
+      response["content"] = JsonValue::Object(content);
+    }
+    responses.insert(status_code, response);
+  }
+  if responses.is_empty() {
+    // OpenAPI requires every operation to describe at least one response
+    responses.insert(
+      String::from("default"),
+      json!({ "description": "No documented responses" }),
+    );
+  }
+
+  let mut operation = json!({ "responses": responses });
+  if !parameters.is_empty() {
+    operation["parameters"] = JsonValue::from(parameters);
+  }
+  if !request_content.is_empty() {
+    operation["requestBody"] = json!({
+      "required": requests.iter().all(|(_, request)| request.body.is_some()),
+      "content": request_content,
+    });
+  }
+
+  Some(operation)
+}
+
+fn header_schemas(
+  endpoint_queries: &EndpointQueries,
+  schemas: &mut SchemaBuilder<OpenApiDialect>,
+  parent_id: &str,
+) -> BTreeMap<String, (JsonValue, bool)> {
+  endpoint_queries
+    .resolve_header_parameters(parent_id)
+    .filter_map(|(_, header_descriptor)| {
+      let shape = header_descriptor.shape.as_ref()?;
+      if shape.is_removed {
+        return None;
+      }
+      let (schema, required) = schemas.value_schema(&shape.shape_id);
+      let schema = with_string_constraints(schema, shape);
+      Some((header_descriptor.name.clone(), (schema, required)))
+    })
+    .collect()
+}
+
+/// The schema of a parameter, along with the format and values it's limited to
+fn with_string_constraints(
+  schema: JsonValue,
+  shape: &ShapedRequestParameterShapeDescriptor,
+) -> JsonValue {
+  if shape.format.is_none() && shape.enum_values.is_none() {
+    return schema;
+  }
+
+  // siblings of a $ref are ignored
+  let mut schema = if schema.get("$ref").is_some() {
+    json!({ "allOf": [schema] })
+  } else {
+    schema
+  };
+  if let Some(format) = &shape.format {
+    schema["format"] = serde_json::to_value(format).expect("string formats should serialize");
+  }
+  if let Some(enum_values) = &shape.enum_values {
+    schema["enum"] = JsonValue::from(enum_values.clone());
+  }
+  schema
+}
+
+struct OpenApiDialect;
+
+impl SchemaDialect for OpenApiDialect {
+  fn reference(&self, name: &str) -> JsonValue {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+  }
+
+  fn nullable(&self, mut schema: JsonValue) -> JsonValue {
+    if schema.get("$ref").is_some() {
+      // siblings of $ref are ignored in OpenAPI 3.0
+      json!({ "allOf": [schema], "nullable": true })
+    } else {
+      schema["nullable"] = JsonValue::from(true);
+      schema
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::events::SpecEvent;
+  use insta::assert_json_snapshot;
+  use serde_json::json;
+
+  #[test]
+  pub fn can_export_endpoints_as_openapi() {
+    let events: Vec<SpecEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"users"}},
+      {"PathParameterAdded":{"pathId":"path_2","parentPathId":"path_1","name":"userId"}},
+      {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"number_shape_1","baseShapeId":"$number","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"PathParameterShapeSet":{"pathId":"path_2","shapeDescriptor":{"shapeId":"number_shape_1","isRemoved":false}}},
+      {"ShapeAdded":{"shapeId":"boolean_shape_1","baseShapeId":"$boolean","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"nullable_shape_1","baseShapeId":"$nullable","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"nullable_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"string_shape_1"}},"consumingParameterId":"$nullableInner"}}}},
+      {"ShapeAdded":{"shapeId":"optional_shape_1","baseShapeId":"$optional","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"optional_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"nullable_shape_1"}},"consumingParameterId":"$optionalInner"}}}},
+      {"ShapeAdded":{"shapeId":"one_of_shape_1","baseShapeId":"$oneOf","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterAdded":{"shapeParameterId":"one_of_parameter_1","shapeId":"one_of_shape_1","name":"","shapeDescriptor":{"ProviderInShape":{"shapeId":"one_of_shape_1","providerDescriptor":{"NoProvider":{}},"consumingParameterId":"one_of_parameter_1"}}}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"one_of_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"number_shape_1"}},"consumingParameterId":"one_of_parameter_1"}}}},
+      {"ShapeParameterAdded":{"shapeParameterId":"one_of_parameter_2","shapeId":"one_of_shape_1","name":"","shapeDescriptor":{"ProviderInShape":{"shapeId":"one_of_shape_1","providerDescriptor":{"NoProvider":{}},"consumingParameterId":"one_of_parameter_2"}}}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"one_of_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"string_shape_1"}},"consumingParameterId":"one_of_parameter_2"}}}},
+      {"ShapeAdded":{"shapeId":"user_shape","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":"User"}},
+      {"FieldAdded":{"fieldId":"field_1","shapeId":"user_shape","name":"name","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_1","shapeId":"string_shape_1"}}}},
+      {"FieldAdded":{"fieldId":"field_2","shapeId":"user_shape","name":"nickname","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_2","shapeId":"optional_shape_1"}}}},
+      {"FieldAdded":{"fieldId":"field_3","shapeId":"user_shape","name":"age","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_3","shapeId":"one_of_shape_1"}}}},
+      {"ShapeAdded":{"shapeId":"reference_shape_1","baseShapeId":"$reference","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"reference_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"user_shape"}},"consumingParameterId":"$referenceInner"}}}},
+      {"ShapeAdded":{"shapeId":"list_shape_1","baseShapeId":"$list","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"list_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"reference_shape_1"}},"consumingParameterId":"$listItem"}}}},
+      {"ShapeAdded":{"shapeId":"query_shape_1","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"optional_shape_2","baseShapeId":"$optional","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"optional_shape_2","providerDescriptor":{"ShapeProvider":{"shapeId":"boolean_shape_1"}},"consumingParameterId":"$optionalInner"}}}},
+      {"FieldAdded":{"fieldId":"field_4","shapeId":"query_shape_1","name":"verbose","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_4","shapeId":"optional_shape_2"}}}},
+      {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"GET"}},
+      {"QueryParametersAdded":{"queryParametersId":"query_1","pathId":"path_1","httpMethod":"GET"}},
+      {"QueryParametersShapeSet":{"queryParametersId":"query_1","shapeDescriptor":{"shapeId":"query_shape_1","isRemoved":false}}},
+      {"ResponseAddedByPathAndMethod":{"responseId":"response_1","pathId":"path_1","httpMethod":"GET","httpStatusCode":200}},
+      {"ResponseBodySet":{"responseId":"response_1","bodyDescriptor":{"httpContentType":"application/json","shapeId":"list_shape_1","isRemoved":false}}},
+      {"ResponseHeaderParameterAdded":{"parameterId":"header_1","responseId":"response_1","name":"X-Rate-Limit"}},
+      {"HeaderParameterShapeSet":{"parameterId":"header_1","parameterDescriptor":{"shapeId":"number_shape_1","isRemoved":false}}},
+      {"RequestAdded":{"requestId":"request_2","pathId":"path_2","httpMethod":"PATCH"}},
+      {"RequestBodySet":{"requestId":"request_2","bodyDescriptor":{"httpContentType":"application/json","shapeId":"reference_shape_1","isRemoved":false}}},
+      {"HeaderParameterAdded":{"parameterId":"header_2","requestId":"request_2","name":"X-Request-Id"}},
+      {"HeaderParameterShapeSet":{"parameterId":"header_2","parameterDescriptor":{"shapeId":"string_shape_1","isRemoved":false}}},
+      {"ResponseAddedByPathAndMethod":{"responseId":"response_2","pathId":"path_2","httpMethod":"PATCH","httpStatusCode":204}},
+    ]))
+    .expect("should be able to deserialize test events");
+
+    let spec_projection = SpecProjection::from(events);
+    let document = export_openapi(&spec_projection, &OpenApiExportConfig::default());
+
+    assert_json_snapshot!("can_export_endpoints_as_openapi__document", document);
+  }
+
+  #[test]
+  pub fn can_export_shapes_containing_themselves_as_openapi() {
+    let events: Vec<SpecEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"categories"}},
+      {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"category_shape","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":"Category"}},
+      {"ShapeAdded":{"shapeId":"list_shape_1","baseShapeId":"$list","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"list_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"category_shape"}},"consumingParameterId":"$listItem"}}}},
+      {"FieldAdded":{"fieldId":"field_1","shapeId":"category_shape","name":"name","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_1","shapeId":"string_shape_1"}}}},
+      {"FieldAdded":{"fieldId":"field_2","shapeId":"category_shape","name":"children","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_2","shapeId":"list_shape_1"}}}},
+      {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"GET"}},
+      {"ResponseAddedByPathAndMethod":{"responseId":"response_1","pathId":"path_1","httpMethod":"GET","httpStatusCode":200}},
+      {"ResponseBodySet":{"responseId":"response_1","bodyDescriptor":{"httpContentType":"application/json","shapeId":"category_shape","isRemoved":false}}},
+    ]))
+    .expect("should be able to deserialize test events");
+
+    let spec_projection = SpecProjection::from(events);
+    let document = export_openapi(&spec_projection, &OpenApiExportConfig::default());
+
+    assert_json_snapshot!(
+      "can_export_shapes_containing_themselves_as_openapi__document",
+      document
+    );
+  }
+}
@@ -0,0 +1,953 @@
+use crate::commands::{EndpointCommand, ShapeCommand, SpecCommand};
+use crate::projections::endpoint::{Node, ROOT_PATH_ID};
+use crate::projections::SpecProjection;
+use crate::queries::EndpointQueries;
+use crate::state::endpoint::{PathComponentId, RequestId, ResponseId, StringFormat};
+use crate::state::shape::{ShapeId, ShapeKind};
+use crate::state::SpecIdGenerator;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, BTreeSet};
+use thiserror::Error;
+
+const HTTP_METHODS: [&str; 8] = [
+  "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+// references between reusable objects can't be cyclic in a valid document
+const MAX_REFERENCE_DEPTH: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum OpenApiImportError {
+  #[error("unsupported OpenAPI version: {}", .0)]
+  UnsupportedVersion(String),
+  #[error("invalid OpenAPI document: {}", .0)]
+  InvalidDocument(&'static str),
+}
+
+/// Generate the commands describing every operation of an OpenAPI 3.0 document. Path
+/// components, requests, responses and headers already known to the spec are reused, while
+/// schemas referenced through `$ref` become named shapes that are pointed at by reference shapes.
+pub fn import_openapi(
+  spec_projection: &SpecProjection,
+  document: &JsonValue,
+  id_generator: &mut impl SpecIdGenerator,
+) -> Result<Vec<SpecCommand>, OpenApiImportError> {
+  let version = document.get("openapi").and_then(JsonValue::as_str).ok_or(
+    OpenApiImportError::InvalidDocument("missing openapi version"),
+  )?;
+  if !version.starts_with("3.") {
+    return Err(OpenApiImportError::UnsupportedVersion(String::from(
+      version,
+    )));
+  }
+  let paths = document
+    .get("paths")
+    .and_then(JsonValue::as_object)
+    .ok_or(OpenApiImportError::InvalidDocument("missing paths object"))?;
+
+  let mut importer = Importer::new(spec_projection, document, id_generator);
+  for (path_pattern, path_item) in paths {
+    let path_item = importer.resolve(path_item);
+    let (path_id, path_parameter_ids) = importer.path_id(path_pattern);
+    importer.set_path_parameter_shapes(&path_parameter_ids, path_item);
+    for method in HTTP_METHODS.iter() {
+      if let Some(operation) = path_item.get(method) {
+        importer.add_operation(&path_id, &method.to_uppercase(), path_item, operation);
+      }
+    }
+  }
+
+  Ok(importer.commands)
+}
+
+struct Importer<'a, I> {
+  spec_projection: &'a SpecProjection,
+  document: &'a JsonValue,
+  id_generator: &'a mut I,
+  // path components added by the import, keyed by parent and name (None for parameters)
+  path_ids: BTreeMap<(PathComponentId, Option<String>), PathComponentId>,
+  component_shape_ids: BTreeMap<String, ShapeId>,
+  // path parameters the import set a shape for
+  shaped_path_parameter_ids: BTreeSet<PathComponentId>,
+  commands: Vec<SpecCommand>,
+}
+
+impl<'a, I> Importer<'a, I>
+where
+  I: SpecIdGenerator,
+{
+  fn new(
+    spec_projection: &'a SpecProjection,
+    document: &'a JsonValue,
+    id_generator: &'a mut I,
+  ) -> Self {
+    Self {
+      spec_projection,
+      document,
+      id_generator,
+      path_ids: BTreeMap::new(),
+      component_shape_ids: BTreeMap::new(),
+      shaped_path_parameter_ids: BTreeSet::new(),
+      commands: vec![],
+    }
+  }
+
+  /// Follow `$ref`s of reusable objects (path items, parameters, request bodies, responses
+  /// and headers) to the object they describe
+  fn resolve(&self, mut value: &'a JsonValue) -> &'a JsonValue {
+    for _ in 0..MAX_REFERENCE_DEPTH {
+      let referenced = value
+        .get("$ref")
+        .and_then(JsonValue::as_str)
+        .and_then(|reference| self.document.pointer(reference.strip_prefix('#')?));
+      match referenced {
+        Some(referenced) => value = referenced,
+        None => break,
+      }
+    }
+    value
+  }
+
+  /// The path component a path pattern leads to, along with its path parameters by name
+  fn path_id(
+    &mut self,
+    path_pattern: &str,
+  ) -> (PathComponentId, BTreeMap<String, PathComponentId>) {
+    let endpoint_projection = self.spec_projection.endpoint();
+    let mut path_id = PathComponentId::from(ROOT_PATH_ID);
+    let mut path_parameter_ids = BTreeMap::new();
+
+    for segment in path_pattern
+      .split('/')
+      .filter(|segment| !segment.is_empty())
+    {
+      let parameter_name = segment
+        .strip_prefix('{')
+        .and_then(|segment| segment.strip_suffix('}'));
+
+      // any parameter matches the same urls, so existing parameters are reused regardless of name
+      let existing_path_id = endpoint_projection
+        .get_child_path_component_nodes(&path_id)
+        .into_iter()
+        .flatten()
+        .find_map(|node| match node {
+          Node::PathComponent(child_id, descriptor)
+            if descriptor.is_parameter == parameter_name.is_some()
+              && (descriptor.is_parameter || descriptor.name == segment) =>
+          {
+            Some(child_id.clone())
+          }
+          _ => None,
+        });
+
+      let key = (
+        path_id.clone(),
+        parameter_name.map_or_else(|| Some(String::from(segment)), |_| None),
+      );
+      path_id = match existing_path_id.or_else(|| self.path_ids.get(&key).cloned()) {
+        Some(existing_path_id) => existing_path_id,
+        None => {
+          let child_path_id = self.id_generator.path();
+          let command = match parameter_name {
+            Some(name) => EndpointCommand::add_path_parameter(
+              child_path_id.clone(),
+              path_id,
+              String::from(name),
+            ),
+            None => EndpointCommand::add_path_component(
+              child_path_id.clone(),
+              path_id,
+              String::from(segment),
+            ),
+          };
+          self.commands.push(SpecCommand::from(command));
+          self.path_ids.insert(key, child_path_id.clone());
+          child_path_id
+        }
+      };
+      if let Some(name) = parameter_name {
+        path_parameter_ids.insert(String::from(name), path_id.clone());
+      }
+    }
+
+    (path_id, path_parameter_ids)
+  }
+
+  /// Path parameters are shared by all operations on a path, so the first declaration of each
+  /// describes it, unless the spec does already
+  fn set_path_parameter_shapes(
+    &mut self,
+    path_parameter_ids: &BTreeMap<String, PathComponentId>,
+    path_item: &'a JsonValue,
+  ) {
+    let declared_parameters = std::iter::once(path_item)
+      .chain(
+        HTTP_METHODS
+          .iter()
+          .filter_map(|method| path_item.get(method)),
+      )
+      .filter_map(|object| object.get("parameters"))
+      .filter_map(JsonValue::as_array)
+      .flatten()
+      .map(|parameter| self.resolve(parameter))
+      .collect::<Vec<_>>();
+    for parameter in declared_parameters {
+      if parameter.get("in").and_then(JsonValue::as_str) != Some("path") {
+        continue;
+      }
+      let path_id = match parameter
+        .get("name")
+        .and_then(JsonValue::as_str)
+        .and_then(|name| path_parameter_ids.get(name))
+      {
+        Some(path_id) => path_id.clone(),
+        None => continue,
+      };
+      let has_shape = self
+        .spec_projection
+        .endpoint()
+        .path_parameter_shapes
+        .contains_key(&path_id);
+      if has_shape || !self.shaped_path_parameter_ids.insert(path_id.clone()) {
+        continue;
+      }
+
+      let schema = parameter.get("schema");
+      let (format, enum_values) = string_constraints(schema);
+      let shape_id = self.schema_shape(schema);
+      self.commands.push(SpecCommand::from(
+        EndpointCommand::set_path_parameter_shape(path_id, shape_id, format, enum_values),
+      ));
+    }
+  }
+
+  fn add_operation(
+    &mut self,
+    path_id: &PathComponentId,
+    method: &str,
+    path_item: &'a JsonValue,
+    operation: &'a JsonValue,
+  ) {
+    // parameters of the operation override those of the path item by location and name
+    let mut parameters = BTreeMap::new();
+    let declared_parameters = path_item
+      .get("parameters")
+      .into_iter()
+      .chain(operation.get("parameters"))
+      .filter_map(JsonValue::as_array)
+      .flatten();
+    for parameter in declared_parameters {
+      let parameter = self.resolve(parameter);
+      let location = parameter.get("in").and_then(JsonValue::as_str);
+      let name = parameter.get("name").and_then(JsonValue::as_str);
+      if let (Some(location), Some(name)) = (location, name) {
+        parameters.insert((location, name), parameter);
+      }
+    }
+    let parameters_in = |parameter_location: &'static str| {
+      parameters
+        .iter()
+        .filter(move |((location, _), _)| *location == parameter_location)
+        .map(|((_, name), parameter)| (*name, *parameter))
+        .collect::<Vec<_>>()
+    };
+
+    // query parameters
+    let query_parameters = parameters_in("query");
+    let has_query_parameters = self
+      .spec_projection
+      .endpoint()
+      .get_endpoint_query_parameter_node(path_id, &String::from(method))
+      .is_some();
+    if !query_parameters.is_empty() && !has_query_parameters {
+      let query_shape_id = self.id_generator.shape();
+      self.add_shape(
+        query_shape_id.clone(),
+        ShapeKind::ObjectKind,
+        String::from(""),
+      );
+      for (name, parameter) in query_parameters {
+        let field_shape_id = self.parameter_shape(parameter);
+        self.add_field(&query_shape_id, name, field_shape_id);
+      }
+
+      let query_parameters_id = self.id_generator.query_params();
+      self.commands.extend(vec![
+        SpecCommand::from(EndpointCommand::add_query_parameters(
+          query_parameters_id.clone(),
+          path_id.clone(),
+          String::from(method),
+        )),
+        SpecCommand::from(EndpointCommand::set_query_parameters_shape(
+          query_parameters_id,
+          query_shape_id,
+          false,
+        )),
+      ]);
+    }
+
+    // requests, one per content type
+    let request_body = operation
+      .get("requestBody")
+      .map(|request_body| self.resolve(request_body));
+    let request_content = request_body
+      .and_then(|request_body| request_body.get("content"))
+      .and_then(JsonValue::as_object)
+      .into_iter()
+      .flatten()
+      .collect::<Vec<_>>();
+    let body_required = request_body
+      .and_then(|request_body| request_body.get("required"))
+      .and_then(JsonValue::as_bool)
+      .unwrap_or(false);
+
+    let mut request_ids = vec![];
+    for (content_type, media_type) in &request_content {
+      if let Some(request_id) = self.existing_request_id(path_id, method, Some(content_type)) {
+        request_ids.push(request_id);
+        continue;
+      }
+      let request_id = self.add_request(path_id, method);
+      let body_shape_id = self.schema_shape(media_type.get("schema"));
+      self
+        .commands
+        .push(SpecCommand::from(EndpointCommand::set_request_body_shape(
+          request_id.clone(),
+          body_shape_id,
+          String::from(*content_type),
+          false,
+        )));
+      request_ids.push(request_id);
+    }
+    if request_content.is_empty() || !body_required {
+      let request_id = self
+        .existing_request_id(path_id, method, None)
+        .unwrap_or_else(|| self.add_request(path_id, method));
+      request_ids.push(request_id);
+    }
+
+    let header_parameters = parameters_in("header");
+    for request_id in &request_ids {
+      for (name, parameter) in &header_parameters {
+        if self.has_header_parameter(request_id, name) {
+          continue;
+        }
+        let parameter_id = self.id_generator.request_parameter();
+        self
+          .commands
+          .push(SpecCommand::from(EndpointCommand::add_header_parameter(
+            parameter_id.clone(),
+            request_id.clone(),
+            String::from(*name),
+          )));
+        self.set_header_parameter_shape(parameter_id, parameter);
+      }
+    }
+
+    // responses, one per status code and content type
+    let responses = operation
+      .get("responses")
+      .and_then(JsonValue::as_object)
+      .into_iter()
+      .flatten();
+    for (status_code, response) in responses {
+      // ranges and the default response can't be described by a single status code
+      let status_code = match status_code.parse::<u16>() {
+        Ok(status_code) => status_code,
+        Err(_) => continue,
+      };
+      let response = self.resolve(response);
+
+      let response_content = response
+        .get("content")
+        .and_then(JsonValue::as_object)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+      let mut response_ids = vec![];
+      for (content_type, media_type) in &response_content {
+        if let Some(response_id) =
+          self.existing_response_id(path_id, method, status_code, Some(content_type))
+        {
+          response_ids.push(response_id);
+          continue;
+        }
+        let response_id = self.add_response(path_id, method, status_code);
+        let body_shape_id = self.schema_shape(media_type.get("schema"));
+        self
+          .commands
+          .push(SpecCommand::from(EndpointCommand::set_response_body_shape(
+            response_id.clone(),
+            body_shape_id,
+            String::from(*content_type),
+            false,
+          )));
+        response_ids.push(response_id);
+      }
+      if response_content.is_empty() {
+        let response_id = self
+          .existing_response_id(path_id, method, status_code, None)
+          .unwrap_or_else(|| self.add_response(path_id, method, status_code));
+        response_ids.push(response_id);
+      }
+
+      let headers = response
+        .get("headers")
+        .and_then(JsonValue::as_object)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+      for response_id in &response_ids {
+        for (name, header) in &headers {
+          if self.has_header_parameter(response_id, name) {
+            continue;
+          }
+          let header = self.resolve(header);
+          let parameter_id = self.id_generator.request_parameter();
+          self.commands.push(SpecCommand::from(
+            EndpointCommand::add_response_header_parameter(
+              parameter_id.clone(),
+              response_id.clone(),
+              String::from(*name),
+            ),
+          ));
+          self.set_header_parameter_shape(parameter_id, header);
+        }
+      }
+    }
+  }
+
+  /// The request of the spec with the same method and content type, so re-importing a document
+  /// doesn't describe it twice
+  fn existing_request_id(
+    &self,
+    path_id: &PathComponentId,
+    method: &str,
+    content_type: Option<&String>,
+  ) -> Option<RequestId> {
+    let endpoint_projection = self.spec_projection.endpoint();
+    endpoint_projection.get_path_component_node_index(path_id)?;
+    let method = String::from(method);
+    let endpoint_queries = EndpointQueries::new(endpoint_projection);
+    let (request_id, _) = endpoint_queries.resolve_request_by_method_and_content_type(
+      path_id,
+      &method,
+      content_type,
+    )?;
+    Some(request_id.clone())
+  }
+
+  /// The response of the spec with the same method, status code and content type
+  fn existing_response_id(
+    &self,
+    path_id: &PathComponentId,
+    method: &str,
+    status_code: u16,
+    content_type: Option<&String>,
+  ) -> Option<ResponseId> {
+    let endpoint_projection = self.spec_projection.endpoint();
+    endpoint_projection.get_path_component_node_index(path_id)?;
+    let endpoint_queries = EndpointQueries::new(endpoint_projection);
+    let (response_id, _) = endpoint_queries
+      .resolve_response_by_method_status_code_and_content_type(
+        path_id,
+        method,
+        status_code,
+        content_type,
+      )?;
+    Some(response_id.clone())
+  }
+
+  fn has_header_parameter(&self, parent_id: &str, name: &str) -> bool {
+    EndpointQueries::new(self.spec_projection.endpoint())
+      .resolve_header_parameters(parent_id)
+      .any(|(_, descriptor)| descriptor.name.eq_ignore_ascii_case(name))
+  }
+
+  fn add_request(&mut self, path_id: &PathComponentId, method: &str) -> RequestId {
+    let request_id = self.id_generator.request();
+    self
+      .commands
+      .push(SpecCommand::from(EndpointCommand::add_request(
+        request_id.clone(),
+        path_id.clone(),
+        String::from(method),
+      )));
+    request_id
+  }
+
+  fn add_response(
+    &mut self,
+    path_id: &PathComponentId,
+    method: &str,
+    status_code: u16,
+  ) -> ResponseId {
+    let response_id = self.id_generator.response();
+    self.commands.push(SpecCommand::from(
+      EndpointCommand::add_response_by_path_and_method(
+        response_id.clone(),
+        path_id.clone(),
+        String::from(method),
+        status_code,
+      ),
+    ));
+    response_id
+  }
+
+  fn set_header_parameter_shape(&mut self, parameter_id: String, parameter: &'a JsonValue) {
+    let shape_id = self.parameter_shape(parameter);
+    let (format, enum_values) = string_constraints(parameter.get("schema"));
+    self.commands.push(SpecCommand::from(
+      EndpointCommand::set_header_parameter_shape(
+        parameter_id,
+        shape_id,
+        false,
+        format,
+        enum_values,
+      ),
+    ));
+  }
+
+  /// The shape of a parameter or header, optional unless it's required
+  fn parameter_shape(&mut self, parameter: &'a JsonValue) -> ShapeId {
+    let required = parameter
+      .get("required")
+      .and_then(JsonValue::as_bool)
+      .unwrap_or(false);
+    self.value_shape(parameter.get("schema"), required)
+  }
+
+  fn value_shape(&mut self, schema: Option<&'a JsonValue>, required: bool) -> ShapeId {
+    let value_shape_id = self.schema_shape(schema);
+    if required {
+      return value_shape_id;
+    }
+
+    let optional_shape_id = self.id_generator.shape();
+    self.add_shape(
+      optional_shape_id.clone(),
+      ShapeKind::OptionalKind,
+      String::from(""),
+    );
+    self.set_inner_shape(&optional_shape_id, &ShapeKind::OptionalKind, value_shape_id);
+    optional_shape_id
+  }
+
+  fn schema_shape(&mut self, schema: Option<&'a JsonValue>) -> ShapeId {
+    let shape_id = self.id_generator.shape();
+    self.add_schema_shape(shape_id.clone(), schema, String::from(""));
+    shape_id
+  }
+
+  /// The shape of a schema referenced by `$ref`, generated only once for all references to it
+  fn component_shape_id(&mut self, reference: &str) -> ShapeId {
+    if let Some(shape_id) = self.component_shape_ids.get(reference) {
+      return shape_id.clone();
+    }
+
+    // registered before its commands are generated, so references back to it don't recurse
+    let shape_id = self.id_generator.shape();
+    self
+      .component_shape_ids
+      .insert(String::from(reference), shape_id.clone());
+
+    let schema = reference
+      .strip_prefix('#')
+      .and_then(|pointer| self.document.pointer(pointer));
+    let name = reference.rsplit('/').next().unwrap_or_default();
+    self.add_schema_shape(shape_id.clone(), schema, String::from(name));
+    shape_id
+  }
+
+  // Every shape is added before the shapes it's composed of, so that schemas referring back to
+  // a schema that's still being generated can point to its shape.
+  fn add_schema_shape(&mut self, shape_id: ShapeId, schema: Option<&'a JsonValue>, name: String) {
+    let schema = match schema {
+      Some(schema) => schema,
+      None => return self.add_shape(shape_id, ShapeKind::UnknownKind, name),
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(JsonValue::as_str) {
+      self.add_shape(shape_id.clone(), ShapeKind::ReferenceKind, name);
+      let referenced_shape_id = self.component_shape_id(reference);
+      self.set_inner_shape(&shape_id, &ShapeKind::ReferenceKind, referenced_shape_id);
+    } else if schema.get("nullable").and_then(JsonValue::as_bool) == Some(true) {
+      self.add_shape(shape_id.clone(), ShapeKind::NullableKind, name);
+      let inner_shape_id = self.id_generator.shape();
+      self.add_non_nullable_schema_shape(inner_shape_id.clone(), schema, String::from(""));
+      self.set_inner_shape(&shape_id, &ShapeKind::NullableKind, inner_shape_id);
+    } else {
+      self.add_non_nullable_schema_shape(shape_id, schema, name);
+    }
+  }
+
+  fn add_non_nullable_schema_shape(
+    &mut self,
+    shape_id: ShapeId,
+    schema: &'a JsonValue,
+    name: String,
+  ) {
+    let branches = schema
+      .get("oneOf")
+      .or_else(|| schema.get("anyOf"))
+      .and_then(JsonValue::as_array);
+    if let Some(branches) = branches {
+      self.add_shape(shape_id.clone(), ShapeKind::OneOfKind, name);
+      for branch in branches {
+        let branch_shape_id = self.schema_shape(Some(branch));
+        let parameter_id = self.id_generator.shape_param();
+        self.commands.extend(vec![
+          SpecCommand::from(ShapeCommand::add_shape_parameter(
+            parameter_id.clone(),
+            shape_id.clone(),
+            String::from(""),
+          )),
+          SpecCommand::from(ShapeCommand::set_parameter_shape(
+            shape_id.clone(),
+            parameter_id,
+            branch_shape_id,
+          )),
+        ]);
+      }
+      return;
+    }
+
+    let mut properties = BTreeMap::new();
+    let mut required = BTreeSet::new();
+    self.collect_object_properties(schema, &mut properties, &mut required);
+    let additional_properties = schema.get("additionalProperties");
+
+    match schema.get("type").and_then(JsonValue::as_str) {
+      Some("string") => self.add_shape(shape_id, ShapeKind::StringKind, name),
+      Some("number") | Some("integer") => self.add_shape(shape_id, ShapeKind::NumberKind, name),
+      Some("boolean") => self.add_shape(shape_id, ShapeKind::BooleanKind, name),
+      Some("array") => {
+        self.add_shape(shape_id.clone(), ShapeKind::ListKind, name);
+        let item_shape_id = self.schema_shape(schema.get("items"));
+        self.set_inner_shape(&shape_id, &ShapeKind::ListKind, item_shape_id);
+      }
+      Some("object") | None
+        if properties.is_empty()
+          && !matches!(additional_properties, None | Some(JsonValue::Bool(false))) =>
+      {
+        self.add_shape(shape_id.clone(), ShapeKind::MapKind, name);
+        let value_schema = additional_properties.filter(|additional| additional.is_object());
+        let value_shape_id = self.schema_shape(value_schema);
+        self.set_inner_shape(&shape_id, &ShapeKind::MapKind, value_shape_id);
+      }
+      Some("object") => self.add_object_shape(shape_id, properties, required, name),
+      None if !properties.is_empty() || schema.get("allOf").is_some() => {
+        self.add_object_shape(shape_id, properties, required, name)
+      }
+      _ => self.add_shape(shape_id, ShapeKind::UnknownKind, name),
+    }
+  }
+
+  /// Properties of an object schema, including those of the schemas it's composed of
+  fn collect_object_properties(
+    &self,
+    schema: &'a JsonValue,
+    properties: &mut BTreeMap<&'a str, &'a JsonValue>,
+    required: &mut BTreeSet<&'a str>,
+  ) {
+    let all_of = schema
+      .get("allOf")
+      .and_then(JsonValue::as_array)
+      .into_iter()
+      .flatten();
+    for member in all_of {
+      self.collect_object_properties(self.resolve(member), properties, required);
+    }
+
+    let own_properties = schema
+      .get("properties")
+      .and_then(JsonValue::as_object)
+      .into_iter()
+      .flatten();
+    for (name, property_schema) in own_properties {
+      properties.insert(name, property_schema);
+    }
+
+    let own_required = schema
+      .get("required")
+      .and_then(JsonValue::as_array)
+      .into_iter()
+      .flatten()
+      .filter_map(JsonValue::as_str);
+    required.extend(own_required);
+  }
+
+  fn add_object_shape(
+    &mut self,
+    shape_id: ShapeId,
+    properties: BTreeMap<&'a str, &'a JsonValue>,
+    required: BTreeSet<&'a str>,
+    name: String,
+  ) {
+    self.add_shape(shape_id.clone(), ShapeKind::ObjectKind, name);
+    for (field_name, field_schema) in properties {
+      let field_shape_id = self.value_shape(Some(field_schema), required.contains(field_name));
+      self.add_field(&shape_id, field_name, field_shape_id);
+    }
+  }
+
+  fn add_field(&mut self, object_shape_id: &ShapeId, name: &str, field_shape_id: ShapeId) {
+    let field_id = self.id_generator.field();
+    self
+      .commands
+      .push(SpecCommand::from(ShapeCommand::add_field(
+        String::from(name),
+        field_id,
+        object_shape_id.clone(),
+        field_shape_id,
+      )));
+  }
+
+  fn add_shape(&mut self, shape_id: ShapeId, shape_kind: ShapeKind, name: String) {
+    self
+      .commands
+      .push(SpecCommand::from(ShapeCommand::add_shape(
+        shape_id, shape_kind, name,
+      )));
+  }
+
+  fn set_inner_shape(
+    &mut self,
+    shape_id: &ShapeId,
+    shape_kind: &ShapeKind,
+    inner_shape_id: ShapeId,
+  ) {
+    let parameter_id = shape_kind
+      .get_parameter_descriptor()
+      .expect("shape kinds wrapping other shapes should have a parameter descriptor")
+      .shape_parameter_id;
+    self
+      .commands
+      .push(SpecCommand::from(ShapeCommand::set_parameter_shape(
+        shape_id.clone(),
+        String::from(parameter_id),
+        inner_shape_id,
+      )));
+  }
+}
+
+/// The format and allowed values of a parameter's schema, which parameters check on top of their
+/// shape
+fn string_constraints(schema: Option<&JsonValue>) -> (Option<StringFormat>, Option<Vec<String>>) {
+  let format = match schema
+    .and_then(|schema| schema.get("format"))
+    .and_then(JsonValue::as_str)
+  {
+    Some("uuid") => Some(StringFormat::Uuid),
+    _ => None,
+  };
+  let enum_values = schema
+    .and_then(|schema| schema.get("enum"))
+    .and_then(JsonValue::as_array)
+    .map(|values| {
+      values
+        .iter()
+        .map(|value| match value {
+          JsonValue::String(value) => value.clone(),
+          value => value.to_string(),
+        })
+        .collect()
+    });
+  (format, enum_values)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::events::SpecEvent;
+  use crate::openapi::{export_openapi, OpenApiExportConfig};
+  use cqrs_core::Aggregate;
+  use insta::assert_json_snapshot;
+  use serde_json::json;
+
+  #[test]
+  pub fn can_import_openapi_documents_as_commands() {
+    let events: Vec<SpecEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"users"}},
+      {"PathParameterAdded":{"pathId":"path_2","parentPathId":"path_1","name":"id"}},
+    ]))
+    .expect("should be able to deserialize test events");
+    let mut spec_projection = SpecProjection::from(events);
+
+    let document = json!({
+      "openapi": "3.0.3",
+      "info": { "title": "Users", "version": "1.0.0" },
+      "paths": {
+        "/users": {
+          "get": {
+            "parameters": [
+              { "name": "limit", "in": "query", "schema": { "type": "integer" } },
+              { "$ref": "#/components/parameters/RequestId" },
+              { "name": "X-Client", "in": "header", "schema": { "type": "string", "enum": ["web", "mobile"] } }
+            ],
+            "responses": {
+              "200": {
+                "description": "",
+                "headers": {
+                  "X-Rate-Limit": { "required": true, "schema": { "type": "integer" } }
+                },
+                "content": {
+                  "application/json": {
+                    "schema": { "type": "array", "items": { "$ref": "#/components/schemas/User" } }
+                  }
+                }
+              },
+              "default": { "description": "" }
+            }
+          }
+        },
+        "/users/{userId}": {
+          "parameters": [
+            { "name": "userId", "in": "path", "required": true, "schema": { "type": "string", "format": "uuid" } }
+          ],
+          "patch": {
+            "requestBody": {
+              "required": true,
+              "content": {
+                "application/json": {
+                  "schema": {
+                    "allOf": [
+                      { "$ref": "#/components/schemas/Named" },
+                      { "properties": { "tags": { "type": "object", "additionalProperties": { "type": "string" } } } }
+                    ]
+                  }
+                }
+              }
+            },
+            "responses": { "204": { "description": "" } }
+          }
+        },
+        "/users/{userId}/avatar": {
+          "put": {
+            "requestBody": {
+              "content": { "image/png": {} }
+            },
+            "responses": { "201": { "$ref": "#/components/responses/Created" } }
+          }
+        }
+      },
+      "components": {
+        "schemas": {
+          "Named": {
+            "type": "object",
+            "required": ["name"],
+            "properties": { "name": { "type": "string" } }
+          },
+          "User": {
+            "type": "object",
+            "required": ["name", "age"],
+            "properties": {
+              "name": { "type": "string" },
+              "nickname": { "type": "string", "nullable": true },
+              "age": { "oneOf": [{ "type": "number" }, { "type": "string" }] },
+              "friends": { "type": "array", "items": { "$ref": "#/components/schemas/User" } }
+            }
+          }
+        },
+        "parameters": {
+          "RequestId": { "name": "X-Request-Id", "in": "header", "schema": { "type": "string" } }
+        },
+        "responses": {
+          "Created": {
+            "description": "",
+            "content": { "application/json": { "schema": { "type": "boolean" } } }
+          }
+        }
+      }
+    });
+
+    let commands = import_openapi(&spec_projection, &document, &mut TestIdGenerator::default())
+      .expect("should be able to import a valid document");
+
+    let added_paths = commands
+      .iter()
+      .filter_map(|command| match command {
+        SpecCommand::EndpointCommand(EndpointCommand::AddPathComponent(command)) => {
+          Some((command.parent_path_id.as_str(), command.name.as_str()))
+        }
+        SpecCommand::EndpointCommand(EndpointCommand::AddPathParameter(command)) => {
+          Some((command.parent_path_id.as_str(), command.name.as_str()))
+        }
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      added_paths,
+      vec![("path_2", "avatar")],
+      "existing path components should be reused"
+    );
+
+    for command in commands {
+      let events = spec_projection
+        .execute(command)
+        .expect("generated commands must be valid");
+      for event in events {
+        spec_projection.apply(event);
+      }
+    }
+
+    let exported = export_openapi(&spec_projection, &OpenApiExportConfig::default());
+    assert_json_snapshot!(
+      "can_import_openapi_documents_as_commands__exported",
+      exported
+    );
+
+    let reimported_commands =
+      import_openapi(&spec_projection, &document, &mut TestIdGenerator::default())
+        .expect("should be able to import a valid document again");
+    assert!(
+      reimported_commands.is_empty(),
+      "operations already in the spec should be reused: {:?}",
+      reimported_commands
+    );
+  }
+
+  #[test]
+  pub fn rejects_documents_that_are_not_openapi_3() {
+    let document = json!({
+      "swagger": "2.0",
+      "paths": {}
+    });
+    let result = import_openapi(
+      &SpecProjection::default(),
+      &document,
+      &mut TestIdGenerator::default(),
+    );
+    assert!(matches!(
+      result,
+      Err(OpenApiImportError::InvalidDocument(_))
+    ));
+
+    let document = json!({
+      "openapi": "2.0",
+      "paths": {}
+    });
+    let result = import_openapi(
+      &SpecProjection::default(),
+      &document,
+      &mut TestIdGenerator::default(),
+    );
+    assert!(matches!(
+      result,
+      Err(OpenApiImportError::UnsupportedVersion(_))
+    ));
+  }
+
+  #[derive(Debug, Default)]
+  struct TestIdGenerator {
+    counter: usize,
+  }
+
+  impl SpecIdGenerator for TestIdGenerator {
+    fn generate_id(&mut self, prefix: &str) -> String {
+      let id = format!("test-id-{}-{}", prefix, self.counter);
+      self.counter += 1;
+      id
+    }
+  }
+}
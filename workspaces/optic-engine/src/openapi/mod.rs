@@ -0,0 +1,5 @@
+mod export;
+mod import;
+
+pub use export::{export_openapi, OpenApiExportConfig, OPENAPI_VERSION};
+pub use import::{import_openapi, OpenApiImportError};
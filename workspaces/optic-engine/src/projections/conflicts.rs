@@ -3,11 +3,12 @@ use crate::projections::endpoint::ROOT_PATH_ID;
 use crate::state::endpoint::{
   HttpContentType, HttpMethod, HttpStatusCode, PathComponentId, RequestId, ResponseId,
 };
+use crate::state::shape::{FieldId, FieldShapeDescriptor, ShapeId};
 use cqrs_core::{Aggregate, AggregateEvent};
 use petgraph::csr::NodeIndex;
-use petgraph::Direction::Incoming;
+use petgraph::Direction::{Incoming, Outgoing};
 use petgraph::Graph;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub struct ConflictsProjection {
@@ -18,6 +19,10 @@ pub struct ConflictsProjection {
   // without rebuilding these maps.
   pub node_id_to_index: HashMap<String, petgraph::graph::NodeIndex>,
   pub domain_id_to_index: HashMap<String, petgraph::graph::NodeIndex>,
+
+  // ids of the domain entities that were changed after they were added, so changes made to the
+  // same entity on either side of a merge can be detected
+  pub edited_ids: HashSet<String>,
 }
 
 impl Default for ConflictsProjection {
@@ -25,11 +30,13 @@ impl Default for ConflictsProjection {
     let graph: Graph<Node, Edge> = Graph::new();
     let node_id_to_index = HashMap::new();
     let domain_id_to_index = HashMap::new();
+    let edited_ids = HashSet::new();
 
     let mut projection = ConflictsProjection {
       graph,
       node_id_to_index,
       domain_id_to_index,
+      edited_ids,
     };
 
     projection.with_path_component_node(
@@ -50,7 +57,23 @@ impl Aggregate for ConflictsProjection {
 }
 
 impl AggregateEvent<ConflictsProjection> for ShapeEvent {
-  fn apply_to(self, projection: &mut ConflictsProjection) {}
+  fn apply_to(self, projection: &mut ConflictsProjection) {
+    match self {
+      ShapeEvent::FieldAdded(e) => {
+        projection.with_field(e.shape_id, e.field_id, e.name);
+      }
+      ShapeEvent::FieldShapeSet(e) => {
+        let field_id = match e.shape_descriptor {
+          FieldShapeDescriptor::FieldShapeFromShape(descriptor) => descriptor.field_id,
+          FieldShapeDescriptor::FieldShapeFromParameter(descriptor) => descriptor.field_id,
+        };
+        projection.with_edit(field_id);
+      }
+      ShapeEvent::FieldRenamed(e) => projection.with_edit(e.field_id),
+      ShapeEvent::FieldRemoved(e) => projection.with_edit(e.field_id),
+      _ => {}
+    }
+  }
 }
 
 impl AggregateEvent<ConflictsProjection> for EndpointEvent {
@@ -70,10 +93,21 @@ impl AggregateEvent<ConflictsProjection> for EndpointEvent {
       }
       EndpointEvent::RequestBodySet(e) => {
         //@TODO: projection.with_request_body_content_type(...)
+        projection.with_edit(e.request_id);
       }
       EndpointEvent::ResponseBodySet(e) => {
         //@TODO: projection.with_response_body_content_type(...)
+        projection.with_edit(e.response_id);
       }
+      EndpointEvent::PathComponentRenamed(e) => projection.with_edit(e.path_id),
+      EndpointEvent::PathComponentRemoved(e) => projection.with_edit(e.path_id),
+      EndpointEvent::PathParameterRenamed(e) => projection.with_edit(e.path_id),
+      EndpointEvent::PathParameterRemoved(e) => projection.with_edit(e.path_id),
+      EndpointEvent::RequestBodyUnset(e) => projection.with_edit(e.request_id),
+      EndpointEvent::RequestRemoved(e) => projection.with_edit(e.request_id),
+      EndpointEvent::ResponseStatusCodeSet(e) => projection.with_edit(e.response_id),
+      EndpointEvent::ResponseBodyUnset(e) => projection.with_edit(e.response_id),
+      EndpointEvent::ResponseRemoved(e) => projection.with_edit(e.response_id),
       _ => eprintln!(
         "Ignoring applying event of type '{}' for ConflictsProjection",
         self.event_type()
@@ -83,7 +117,13 @@ impl AggregateEvent<ConflictsProjection> for EndpointEvent {
 }
 
 impl AggregateEvent<ConflictsProjection> for SpecEvent {
-  fn apply_to(self, projection: &mut ConflictsProjection) {}
+  fn apply_to(self, projection: &mut ConflictsProjection) {
+    match self {
+      SpecEvent::EndpointEvent(event) => projection.apply(event),
+      SpecEvent::ShapeEvent(event) => projection.apply(event),
+      SpecEvent::RfcEvent(_) => {}
+    }
+  }
 }
 
 impl<I> From<I> for ConflictsProjection
@@ -322,6 +362,48 @@ impl ConflictsProjection {
       .domain_id_to_index
       .insert(response_id, status_code_index);
   }
+  ////////////////////////////////////////////////////////////////////////////////////////////////////
+  pub fn with_field(&mut self, shape_id: ShapeId, field_id: FieldId, field_name: String) {
+    // fields are identified by their name within the object shape they're a part of
+    let node_id = format!("{}.{}", shape_id, field_name);
+    if let Some(node_index) = self.node_id_to_index.get(&node_id) {
+      let node_index = *node_index;
+      if let Some(Node::Field(_, _, ids)) = self.graph.node_weight_mut(node_index) {
+        ids.push(field_id.clone());
+      }
+      self.domain_id_to_index.insert(field_id, node_index);
+    } else {
+      let node_index =
+        self
+          .graph
+          .add_node(Node::Field(shape_id, field_name, vec![field_id.clone()]));
+      self.node_id_to_index.insert(node_id, node_index);
+      self.domain_id_to_index.insert(field_id, node_index);
+    }
+  }
+
+  pub fn with_edit(&mut self, domain_id: String) {
+    self.edited_ids.insert(domain_id);
+  }
+  ////////////////////////////////////////////////////////////////////////////////////////////////////
+
+  /// Nodes that more than one domain entity resolves to, like two paths with the same pattern
+  pub fn conflicting_nodes(&self) -> impl Iterator<Item = (petgraph::graph::NodeIndex, &Node)> {
+    self
+      .graph
+      .node_indices()
+      .map(move |node_index| (node_index, &self.graph[node_index]))
+      .filter(|(_, node)| node.conflicting_ids().len() > 1)
+  }
+
+  /// The node a node is a child of, like the path of an http method
+  pub fn parent_node(
+    &self,
+    node_index: petgraph::graph::NodeIndex,
+  ) -> Option<(petgraph::graph::NodeIndex, &Node)> {
+    let parent_index = self.graph.neighbors_directed(node_index, Outgoing).next()?;
+    Some((parent_index, self.graph.node_weight(parent_index)?))
+  }
 }
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -334,6 +416,21 @@ pub enum Node {
   HttpMethod(HttpMethod, ConflictingIds),
   HttpStatusCode(HttpStatusCode, ConflictingIds),
   HttpContentType(HttpContentType, ConflictingIds),
+  Field(ShapeId, FieldName, ConflictingIds),
+}
+
+pub type FieldName = String;
+
+impl Node {
+  pub fn conflicting_ids(&self) -> &ConflictingIds {
+    match self {
+      Node::Path(_, ids) => ids,
+      Node::HttpMethod(_, ids) => ids,
+      Node::HttpStatusCode(_, ids) => ids,
+      Node::HttpContentType(_, ids) => ids,
+      Node::Field(_, _, ids) => ids,
+    }
+  }
 }
 
 #[derive(Debug, Clone)]
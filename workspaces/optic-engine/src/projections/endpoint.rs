@@ -30,6 +30,12 @@ pub struct QueryParametersDescriptor {
   pub shape: Option<QueryParametersShapeDescriptor>,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct HeaderParameterDescriptor {
+  pub name: String,
+  pub shape: Option<ShapedRequestParameterShapeDescriptor>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct RequestDescriptor {
   pub body: Option<BodyDescriptor>,
@@ -46,6 +52,7 @@ pub enum Node {
   HttpStatusCode(HttpStatusCode),
   PathComponent(PathComponentId, PathComponentDescriptor),
   QueryParameters(QueryParametersId, QueryParametersDescriptor),
+  HeaderParameter(RequestParameterId, HeaderParameterDescriptor),
   Request(RequestId, RequestDescriptor),
   Response(ResponseId, ResponseBodyDescriptor),
 }
@@ -62,6 +69,7 @@ pub struct EndpointProjection {
   // which no longer exist or point to a different node. Compiler can't track these nodes for us. Do not delete nodes
   // without rebuilding this map.
   pub node_id_to_index: HashMap<String, petgraph::graph::NodeIndex>,
+  pub path_parameter_shapes: HashMap<PathComponentId, ShapedRequestParameterShapeDescriptor>,
 }
 
 impl EndpointProjection {
@@ -99,9 +107,18 @@ impl EndpointProjection {
   }
 
   pub fn without_path_parameter(&mut self, path_id: PathComponentId) {
+    self.path_parameter_shapes.remove(&path_id);
     self.without_path_component(path_id);
   }
 
+  pub fn with_path_parameter_shape(
+    &mut self,
+    path_id: PathComponentId,
+    shape_descriptor: ShapedRequestParameterShapeDescriptor,
+  ) {
+    self.path_parameter_shapes.insert(path_id, shape_descriptor);
+  }
+
   fn with_path_component_node(
     &mut self,
     parent_path_id: PathComponentId,
@@ -275,6 +292,84 @@ impl EndpointProjection {
     // eventual garbage collection.
   }
 
+  pub fn with_header_parameter(
+    &mut self,
+    parent_id: String,
+    parameter_id: RequestParameterId,
+    name: String,
+  ) {
+    let parent_node_index = *self
+      .node_id_to_index
+      .get(&parent_id)
+      .expect("expected parent_id to have a corresponding node");
+    let header_node = Node::HeaderParameter(
+      parameter_id.clone(),
+      HeaderParameterDescriptor { name, shape: None },
+    );
+    let header_node_index = self.graph.add_node(header_node);
+    self
+      .graph
+      .add_edge(header_node_index, parent_node_index, Edge::IsChildOf);
+    self
+      .node_id_to_index
+      .insert(parameter_id, header_node_index);
+  }
+
+  pub fn with_header_parameter_shape(
+    &mut self,
+    parameter_id: RequestParameterId,
+    shape_descriptor: ShapedRequestParameterShapeDescriptor,
+  ) {
+    let header_descriptor = self.get_header_parameter_descriptor_mut(&parameter_id);
+    header_descriptor.shape = Some(shape_descriptor);
+  }
+
+  pub fn with_header_parameter_name(&mut self, parameter_id: RequestParameterId, name: String) {
+    let header_descriptor = self.get_header_parameter_descriptor_mut(&parameter_id);
+    header_descriptor.name = name;
+  }
+
+  pub fn without_header_parameter_shape(&mut self, parameter_id: RequestParameterId) {
+    let header_descriptor = self.get_header_parameter_descriptor_mut(&parameter_id);
+    header_descriptor.shape = None;
+  }
+
+  pub fn without_header_parameter(&mut self, parameter_id: RequestParameterId) {
+    let header_node_index = *self
+      .node_id_to_index
+      .get(&parameter_id)
+      .expect("expected parameter_id to have a corresponding node");
+
+    let parent_edge_index = self
+      .graph
+      .edges_directed(header_node_index, petgraph::Direction::Outgoing)
+      .next()
+      .map(|parent_edge| parent_edge.id());
+
+    if let Some(parent_edge_index) = parent_edge_index {
+      self.graph.remove_edge(parent_edge_index); // prevents header to be resolved from request or response node
+    }
+    self.node_id_to_index.remove(&parameter_id); // prevents header node to be looked up by parameter id
+
+    // GOTCHA: we're not deleting the header node itself, as that would invalidate self.node_id_to_index
+    // as the graph indexes shift.
+  }
+
+  fn get_header_parameter_descriptor_mut(
+    &mut self,
+    parameter_id: &RequestParameterId,
+  ) -> &mut HeaderParameterDescriptor {
+    let header_node_index = self
+      .node_id_to_index
+      .get(parameter_id)
+      .expect("expected parameter_id to have a corresponding node");
+    let header_node = self.graph.node_weight_mut(*header_node_index).unwrap();
+    match header_node {
+      Node::HeaderParameter(_, descriptor) => descriptor,
+      _ => unreachable!("header parameter ids should point to header parameter nodes"),
+    }
+  }
+
   fn ensure_method_node(
     &mut self,
     path_node_index: petgraph::graph::NodeIndex,
@@ -504,6 +599,57 @@ impl EndpointProjection {
     }
   }
 
+  pub fn get_header_parameter_node_index(
+    &self,
+    parameter_id: &RequestParameterId,
+  ) -> Option<&NodeIndex> {
+    let node_index = self.node_id_to_index.get(parameter_id)?;
+    let node = self.graph.node_weight(*node_index)?;
+    if let &Node::HeaderParameter(_, _) = node {
+      Some(node_index)
+    } else {
+      None
+    }
+  }
+
+  pub fn get_header_parameter_parent_id(
+    &self,
+    parameter_id: &RequestParameterId,
+  ) -> Option<&String> {
+    let header_node_index = self.get_header_parameter_node_index(parameter_id)?;
+
+    self
+      .graph
+      .neighbors_directed(*header_node_index, petgraph::Direction::Outgoing)
+      .find_map(
+        |parent_node_index| match self.graph.node_weight(parent_node_index)? {
+          Node::Request(request_id, _) => Some(request_id),
+          Node::Response(response_id, _) => Some(response_id),
+          _ => None,
+        },
+      )
+  }
+
+  pub fn get_header_parameter_nodes<'a>(
+    &'a self,
+    parent_id: &str,
+  ) -> Option<impl Iterator<Item = (&'a RequestParameterId, &'a HeaderParameterDescriptor)> + 'a>
+  {
+    let parent_node_index = self.node_id_to_index.get(parent_id)?;
+
+    let header_nodes = self
+      .graph
+      .neighbors_directed(*parent_node_index, petgraph::Direction::Incoming)
+      .filter_map(
+        move |node_index| match self.graph.node_weight(node_index)? {
+          Node::HeaderParameter(parameter_id, descriptor) => Some((parameter_id, descriptor)),
+          _ => None,
+        },
+      );
+
+    Some(header_nodes)
+  }
+
   pub fn get_child_path_component_nodes<'a>(
     &'a self,
     path_id: &'a PathComponentId,
@@ -585,16 +731,15 @@ impl EndpointProjection {
           .graph
           .neighbors_directed(http_method_node_index, petgraph::Direction::Incoming);
 
-        status_code_nodes.flat_map(move |status_code_node_index| {
-          let status_code = self
-            .graph
-            .node_weight(status_code_node_index)
-            .map(|node| match node {
-              Node::HttpStatusCode(status_code) => Some(status_code),
-              _ => None,
-            })
-            .flatten()
-            .unwrap();
+        // methods also parent requests and query parameters, which aren't grouped by status code
+        let status_code_nodes = status_code_nodes.filter_map(move |node_index| {
+          match self.graph.node_weight(node_index)? {
+            Node::HttpStatusCode(status_code) => Some((node_index, status_code)),
+            _ => None,
+          }
+        });
+
+        status_code_nodes.flat_map(move |(status_code_node_index, status_code)| {
           let response_nodes = self
             .graph
             .neighbors_directed(status_code_node_index, petgraph::Direction::Incoming);
@@ -705,6 +850,7 @@ impl Default for EndpointProjection {
     EndpointProjection {
       graph,
       node_id_to_index,
+      path_parameter_shapes: HashMap::new(),
     }
   }
 }
@@ -747,6 +893,9 @@ impl AggregateEvent<EndpointProjection> for EndpointEvent {
       EndpointEvent::PathParameterRemoved(e) => {
         aggregate.without_path_parameter(e.path_id);
       }
+      EndpointEvent::PathParameterShapeSet(e) => {
+        aggregate.with_path_parameter_shape(e.path_id, e.shape_descriptor);
+      }
       EndpointEvent::QueryParametersAdded(e) => {
         aggregate.with_query_parameters(e.path_id, e.http_method, e.query_parameters_id);
       }
@@ -782,6 +931,24 @@ impl AggregateEvent<EndpointProjection> for EndpointEvent {
           e.body_descriptor.shape_id,
         );
       }
+      EndpointEvent::HeaderParameterAdded(e) => {
+        aggregate.with_header_parameter(e.request_id, e.parameter_id, e.name);
+      }
+      EndpointEvent::ResponseHeaderParameterAdded(e) => {
+        aggregate.with_header_parameter(e.response_id, e.parameter_id, e.name);
+      }
+      EndpointEvent::HeaderParameterShapeSet(e) => {
+        aggregate.with_header_parameter_shape(e.parameter_id, e.parameter_descriptor);
+      }
+      EndpointEvent::HeaderParameterRenamed(e) => {
+        aggregate.with_header_parameter_name(e.parameter_id, e.name);
+      }
+      EndpointEvent::HeaderParameterShapeUnset(e) => {
+        aggregate.without_header_parameter_shape(e.parameter_id);
+      }
+      EndpointEvent::HeaderParameterRemoved(e) => {
+        aggregate.without_header_parameter(e.parameter_id);
+      }
       _ => eprintln!(
         "Ignoring applying event of type '{}' for EndpointProjection",
         self.event_type()
@@ -185,29 +185,14 @@ impl HistoryProjection {
   //
 
   pub fn find_last_batch_commit_index(&self) -> Option<NodeIndex> {
-    let root_node_index = *self.get_batch_commit_node_index(&CommitId::from(ROOT_COMMIT_ID))?;
-    let commit_graph = visit::NodeFiltered::from_fn(&self.graph, |node_index| {
+    // the batch commit started last, which for chunks assembled from several branches is the
+    // last one of the linearized history, rather than the tip of whichever branch
+    self.graph.node_indices().rev().find(|node_index| {
       matches!(
-        self.graph.node_weight(node_index),
+        self.graph.node_weight(*node_index),
         Some(Node::BatchCommit(_))
       )
-    });
-
-    // @GOTCHA: this assumes there's the commit graph is a commit log, with no branches
-    let mut leaf_node_index = Some(root_node_index);
-    visit::depth_first_search(&self.graph, Some(root_node_index), |event| {
-      if let visit::DfsEvent::TreeEdge(parent_node_index, child_node_index) = event {
-        leaf_node_index.replace(child_node_index);
-      }
-
-      if let visit::DfsEvent::BackEdge(_, _) = event {
-        visit::Control::Break(())
-      } else {
-        visit::Control::Continue
-      }
-    });
-
-    leaf_node_index
+    })
   }
 }
 
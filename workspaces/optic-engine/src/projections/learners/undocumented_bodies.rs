@@ -3,9 +3,11 @@ use log;
 use serde::Serialize;
 use std::collections::HashMap;
 
-use crate::commands::{EndpointCommand, SpecCommand};
+use crate::commands::{EndpointCommand, ShapeCommand, SpecCommand};
 use crate::interactions::{BodyAnalysisLocation, BodyAnalysisResult};
 use crate::learn_shape::TrailObservationsResult;
+use crate::state::endpoint::RequestParameterId;
+use crate::state::shape::ShapeKind;
 use crate::state::SpecIdGenerator;
 use crate::JsonTrail;
 
@@ -29,12 +31,26 @@ impl LearnedUndocumentedBodiesProjection {
     id_generator: &mut impl SpecIdGenerator,
   ) -> impl Iterator<Item = EndpointBodies> {
     let mut endpoints_by_endpoint = HashMap::new();
-    for (body_location, observations) in self.observations_by_location {
+    let (mut headers_by_location, bodies_by_location): (HashMap<_, _>, HashMap<_, _>) = self
+      .observations_by_location
+      .into_iter()
+      .partition(|(location, _)| {
+        matches!(
+          location,
+          BodyAnalysisLocation::UnmatchedRequestHeaders { .. }
+            | BodyAnalysisLocation::UnmatchedResponseHeaders { .. }
+        )
+      });
+
+    for (body_location, observations) in bodies_by_location {
       let (root_shape_id, body_commands) =
         observations.into_commands(id_generator, &JsonTrail::empty());
       let mut endpoint_body = EndpointBody::new(&body_location, root_shape_id, body_commands);
 
-      endpoint_body.append_endpoint_commands(id_generator);
+      let headers_observations = body_location
+        .headers_location()
+        .and_then(|headers_location| headers_by_location.remove(&headers_location));
+      endpoint_body.append_endpoint_commands(id_generator, headers_observations);
 
       let (path_id, method) = match body_location {
         BodyAnalysisLocation::UnmatchedRequest {
@@ -257,7 +273,11 @@ impl EndpointBody {
     }
   }
 
-  fn append_endpoint_commands(&mut self, ids: &mut impl SpecIdGenerator) {
+  fn append_endpoint_commands(
+    &mut self,
+    ids: &mut impl SpecIdGenerator,
+    headers_observations: Option<TrailObservationsResult>,
+  ) {
     match self {
       EndpointBody::QueryParameters(query_parameters) => {
         let query_params_id = ids.query_params();
@@ -300,6 +320,16 @@ impl EndpointBody {
               false,
             )));
         }
+
+        if let Some(headers_observations) = headers_observations {
+          request_body.commands.extend(headers_into_commands(
+            headers_observations,
+            ids,
+            |parameter_id, name| {
+              EndpointCommand::add_header_parameter(parameter_id, request_id.clone(), name)
+            },
+          ));
+        }
       }
       EndpointBody::Response(response_body) => {
         let response_id = ids.response();
@@ -316,17 +346,125 @@ impl EndpointBody {
           response_body
             .commands
             .push(SpecCommand::from(EndpointCommand::set_response_body_shape(
-              response_id,
+              response_id.clone(),
               body_descriptor.root_shape_id.clone(),
               body_descriptor.content_type.clone(),
               false,
             )));
         }
+
+        if let Some(headers_observations) = headers_observations {
+          response_body.commands.extend(headers_into_commands(
+            headers_observations,
+            ids,
+            |parameter_id, name| {
+              EndpointCommand::add_response_header_parameter(
+                parameter_id,
+                response_id.clone(),
+                name,
+              )
+            },
+          ));
+        }
       }
     };
   }
 }
 
+/// Headers set by clients, servers and proxies for every request or response, or described by
+/// the request or response itself, rather than the API.
+const STANDARD_HEADER_NAMES: &[&str] = &[
+  "accept",
+  "accept-charset",
+  "accept-encoding",
+  "accept-language",
+  "connection",
+  "content-encoding",
+  "content-length",
+  "content-type",
+  "cookie",
+  "date",
+  "expect",
+  "host",
+  "keep-alive",
+  "proxy-connection",
+  "server",
+  "te",
+  "trailer",
+  "transfer-encoding",
+  "upgrade",
+  "user-agent",
+  "via",
+];
+
+/// Generate commands for header parameters from the observations of headers, taken as objects
+/// keyed by header name. Headers missing from some of the observed interactions become optional.
+/// Standard headers, and pseudo headers like `:authority`, aren't learned.
+fn headers_into_commands(
+  headers_observations: TrailObservationsResult,
+  ids: &mut impl SpecIdGenerator,
+  add_header_parameter: impl Fn(RequestParameterId, String) -> EndpointCommand,
+) -> Vec<SpecCommand> {
+  let headers_trail = JsonTrail::empty();
+  let (header_names, optional_header_names) = match headers_observations.get(&headers_trail) {
+    Some(headers_values) => headers_values.field_keys(),
+    None => return vec![],
+  };
+
+  let mut commands = vec![];
+  let header_names = header_names.into_iter().filter(|header_name| {
+    !header_name.starts_with(':') && !STANDARD_HEADER_NAMES.contains(&header_name.as_str())
+  });
+  for header_name in header_names {
+    let header_trail = headers_trail.with_object_key(header_name.clone());
+    let header_observations = TrailObservationsResult {
+      values_by_trail: headers_observations
+        .values()
+        .filter(|values| {
+          values.trail == header_trail || values.trail.is_descendant_of(&header_trail)
+        })
+        .map(|values| (values.trail.clone(), values.clone()))
+        .collect(),
+    };
+
+    let (value_shape_id, shape_commands) = header_observations.into_commands(ids, &header_trail);
+    let value_shape_id = value_shape_id.expect("observed header should have a shape");
+    commands.extend(shape_commands);
+
+    let header_shape_id = if optional_header_names.contains(&header_name) {
+      let optional_shape_id = ids.shape();
+      commands.push(SpecCommand::from(ShapeCommand::add_shape(
+        optional_shape_id.clone(),
+        ShapeKind::OptionalKind,
+        String::from(""),
+      )));
+      let parameter_id = ShapeKind::OptionalKind
+        .get_parameter_descriptor()
+        .unwrap()
+        .shape_parameter_id;
+      commands.push(SpecCommand::from(ShapeCommand::set_parameter_shape(
+        optional_shape_id.clone(),
+        String::from(parameter_id),
+        value_shape_id,
+      )));
+      optional_shape_id
+    } else {
+      value_shape_id
+    };
+
+    let parameter_id = ids.request_parameter();
+    commands.push(SpecCommand::from(add_header_parameter(
+      parameter_id.clone(),
+      header_name,
+    )));
+    commands.push(SpecCommand::from(
+      EndpointCommand::set_header_parameter_shape(parameter_id, header_shape_id, false, None, None),
+    ));
+  }
+
+  commands
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -495,6 +633,147 @@ mod test {
     );
   }
 
+  #[test]
+  fn undocumented_bodies_generates_commands_for_request_headers() {
+    let request_location = BodyAnalysisLocation::UnmatchedRequest {
+      path_id: String::from("root"),
+      method: String::from("POST"),
+      content_type: Some(String::from("application/json")),
+    };
+    let headers_location = request_location.headers_location().unwrap();
+
+    let analysis_results = vec![
+      BodyAnalysisResult {
+        body_location: request_location.clone(),
+        trail_observations: observe_body_trails(BodyDescriptor::from(json!({ "id": 1 }))),
+      },
+      BodyAnalysisResult {
+        body_location: headers_location.clone(),
+        trail_observations: observe_body_trails(BodyDescriptor::from(json!({
+          "x-request-id": "a5d3b4c2",
+          "x-tenant-id": "acme"
+        }))),
+      },
+      BodyAnalysisResult {
+        body_location: headers_location,
+        trail_observations: observe_body_trails(BodyDescriptor::from(json!({
+          "x-request-id": "e3c1f2a9"
+        }))),
+      },
+    ];
+
+    let mut test_id_generator = TestIdGenerator::default();
+    let mut projection = LearnedUndocumentedBodiesProjection::default();
+
+    for result in analysis_results {
+      projection.apply(result);
+    }
+
+    let mut endpoint_bodies = projection
+      .into_endpoint_bodies(&mut test_id_generator)
+      .collect::<Vec<_>>();
+    assert_eq!(endpoint_bodies.len(), 1);
+
+    let commands = endpoint_bodies
+      .remove(0)
+      .into_commands()
+      .collect::<Vec<_>>();
+    assert_eq!(
+      commands
+        .iter()
+        .filter(|command| matches!(
+          command,
+          SpecCommand::EndpointCommand(EndpointCommand::AddHeaderParameter(_))
+        ))
+        .count(),
+      2
+    );
+
+    assert_debug_snapshot!(
+      "undocumented_bodies_generates_commands_for_request_headers__commands",
+      &commands
+    );
+
+    let base_spec = SpecProjection::default();
+    assert_valid_commands(base_spec, commands);
+  }
+
+  #[test]
+  fn undocumented_bodies_skips_standard_headers() {
+    let response_location = BodyAnalysisLocation::UnmatchedResponse {
+      path_id: String::from("root"),
+      method: String::from("GET"),
+      content_type: Some(String::from("application/json")),
+      status_code: 200,
+    };
+    let request_location = BodyAnalysisLocation::UnmatchedRequest {
+      path_id: String::from("root"),
+      method: String::from("GET"),
+      content_type: None,
+    };
+
+    let analysis_results = vec![
+      BodyAnalysisResult {
+        body_location: request_location.clone(),
+        trail_observations: observe_body_trails(None),
+      },
+      BodyAnalysisResult {
+        body_location: request_location.headers_location().unwrap(),
+        trail_observations: observe_body_trails(BodyDescriptor::from(json!({
+          "host": "localhost:3000",
+          "user-agent": "curl/7.64.1",
+          "accept": "*/*",
+          "cookie": "session=abc",
+          "connection": "keep-alive",
+          ":authority": "localhost:3000",
+          "x-api-key": "secret"
+        }))),
+      },
+      BodyAnalysisResult {
+        body_location: response_location.clone(),
+        trail_observations: observe_body_trails(BodyDescriptor::from(json!({ "id": 1 }))),
+      },
+      BodyAnalysisResult {
+        body_location: response_location.headers_location().unwrap(),
+        trail_observations: observe_body_trails(BodyDescriptor::from(json!({
+          "content-type": "application/json",
+          "content-length": "9",
+          "date": "Mon, 19 Jul 2021 10:00:00 GMT",
+          "x-rate-limit": "100"
+        }))),
+      },
+    ];
+
+    let mut test_id_generator = TestIdGenerator::default();
+    let mut projection = LearnedUndocumentedBodiesProjection::default();
+
+    for result in analysis_results {
+      projection.apply(result);
+    }
+
+    let commands = projection
+      .into_endpoint_bodies(&mut test_id_generator)
+      .flat_map(|endpoint_body| endpoint_body.into_commands())
+      .collect::<Vec<_>>();
+
+    let learned_header_names = commands
+      .iter()
+      .filter_map(|command| match command {
+        SpecCommand::EndpointCommand(EndpointCommand::AddHeaderParameter(command)) => {
+          Some(command.name.as_str())
+        }
+        SpecCommand::EndpointCommand(EndpointCommand::AddResponseHeaderParameter(command)) => {
+          Some(command.name.as_str())
+        }
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(learned_header_names, vec!["x-api-key", "x-rate-limit"]);
+
+    let base_spec = SpecProjection::default();
+    assert_valid_commands(base_spec, commands);
+  }
+
   #[derive(Debug, Default)]
   struct TestIdGenerator {
     counter: usize,
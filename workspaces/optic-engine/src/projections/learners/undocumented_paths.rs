@@ -0,0 +1,408 @@
+use cqrs_core::{Aggregate, AggregateEvent, Event};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::commands::{EndpointCommand, SpecCommand};
+use crate::interactions::result::UnmatchedRequestUrl;
+use crate::projections::endpoint::{EndpointProjection, Node, ROOT_PATH_ID};
+use crate::state::endpoint::PathComponentId;
+use crate::state::SpecIdGenerator;
+
+/// Distinct slug-like segments at the same position before they're considered a path parameter,
+/// rather than a handful of literal paths that happen to contain dashes or digits.
+const MIN_SLUG_CARDINALITY: usize = 5;
+
+#[derive(Default, Debug)]
+pub struct LearnedUndocumentedPathsProjection {
+  observed_paths: PathTrie,
+}
+
+impl LearnedUndocumentedPathsProjection {
+  fn with_unmatched_request_url(&mut self, diff: UnmatchedRequestUrl) {
+    if let Some(url_path) = diff.interaction_trail.get_url_path() {
+      let segments: Vec<&str> = url_path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+      self.observed_paths.insert(&segments);
+    }
+  }
+
+  /// Infers path templates from the unmatched urls, collapsing segments that look like
+  /// identifiers into path parameters. Existing path components of the spec are reused, so the
+  /// commands only add what's missing.
+  pub fn into_learned_paths(
+    self,
+    endpoint_projection: &EndpointProjection,
+    id_generator: &mut impl SpecIdGenerator,
+  ) -> LearnedPaths {
+    let mut learner = PathLearner {
+      endpoint_projection,
+      id_generator,
+      learned_paths: LearnedPaths::default(),
+    };
+    learner.learn(
+      self.observed_paths,
+      &PathComponentId::from(ROOT_PATH_ID),
+      true,
+      PathContext::default(),
+    );
+    learner.learned_paths
+  }
+}
+
+impl Aggregate for LearnedUndocumentedPathsProjection {
+  fn aggregate_type() -> &'static str {
+    "learned_undocumented_paths"
+  }
+}
+
+impl Event for UnmatchedRequestUrl {
+  fn event_type(&self) -> &'static str {
+    "unmatched_request_url"
+  }
+}
+
+impl AggregateEvent<LearnedUndocumentedPathsProjection> for UnmatchedRequestUrl {
+  fn apply_to(self, aggregate: &mut LearnedUndocumentedPathsProjection) {
+    aggregate.with_unmatched_request_url(self)
+  }
+}
+
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LearnedPaths {
+  /// Path patterns of the observed urls, with path parameters written as `{name}`
+  pub path_patterns: Vec<String>,
+  pub commands: Vec<SpecCommand>,
+}
+
+#[derive(Default, Debug)]
+struct PathTrie {
+  is_observed: bool,
+  literals: BTreeMap<String, PathTrie>,
+  parameter: Option<Box<PathTrie>>,
+}
+
+impl PathTrie {
+  fn insert(&mut self, segments: &[&str]) {
+    match segments {
+      [] => self.is_observed = true,
+      [segment, rest @ ..] => self
+        .literals
+        .entry(String::from(*segment))
+        .or_default()
+        .insert(rest),
+    }
+  }
+
+  fn merge(&mut self, other: PathTrie) {
+    self.is_observed |= other.is_observed;
+    for (segment, other_child) in other.literals {
+      self.literals.entry(segment).or_default().merge(other_child);
+    }
+    if let Some(other_parameter) = other.parameter {
+      self
+        .parameter
+        .get_or_insert_with(Default::default)
+        .merge(*other_parameter);
+    }
+  }
+}
+
+#[derive(Clone, Default)]
+struct PathContext {
+  pattern: String,
+  parameter_names: Vec<String>,
+  /// Literal segment of the path component being learned, used to name parameters below it
+  segment: Option<String>,
+}
+
+struct PathLearner<'a, G> {
+  endpoint_projection: &'a EndpointProjection,
+  id_generator: &'a mut G,
+  learned_paths: LearnedPaths,
+}
+
+impl<'a, G: SpecIdGenerator> PathLearner<'a, G> {
+  fn learn(
+    &mut self,
+    mut observed: PathTrie,
+    path_id: &PathComponentId,
+    is_existing: bool,
+    context: PathContext,
+  ) {
+    if observed.is_observed {
+      self
+        .learned_paths
+        .path_patterns
+        .push(if context.pattern.is_empty() {
+          String::from("/")
+        } else {
+          context.pattern.clone()
+        });
+    }
+
+    let mut existing_literals = HashMap::new();
+    let mut existing_parameter = None;
+    if is_existing {
+      let existing_children = self
+        .endpoint_projection
+        .get_child_path_component_nodes(path_id)
+        .into_iter()
+        .flatten();
+      for child_node in existing_children {
+        if let Node::PathComponent(child_id, descriptor) = child_node {
+          if descriptor.is_parameter {
+            existing_parameter = Some((child_id.clone(), descriptor.name.clone()));
+          } else {
+            existing_literals.insert(descriptor.name.clone(), child_id.clone());
+          }
+        }
+      }
+    }
+
+    // any segment would match an existing parameter, so only unknown literals need it
+    let slug_count = observed
+      .literals
+      .keys()
+      .filter(|segment| !existing_literals.contains_key(*segment))
+      .filter(|segment| is_slug_like(segment))
+      .count();
+    let parameter_segments: Vec<String> = observed
+      .literals
+      .keys()
+      .filter(|segment| !existing_literals.contains_key(*segment))
+      .filter(|segment| {
+        existing_parameter.is_some()
+          || is_id_like(segment)
+          || (is_slug_like(segment) && slug_count >= MIN_SLUG_CARDINALITY)
+      })
+      .cloned()
+      .collect();
+    for segment in parameter_segments {
+      let parameter_child = observed.literals.remove(&segment).unwrap();
+      observed
+        .parameter
+        .get_or_insert_with(Default::default)
+        .merge(parameter_child);
+    }
+
+    for (segment, observed_child) in observed.literals {
+      let (child_id, is_child_existing) = match existing_literals.get(&segment) {
+        Some(existing_id) => (existing_id.clone(), true),
+        None => {
+          let child_id = self.id_generator.path();
+          self
+            .learned_paths
+            .commands
+            .push(SpecCommand::from(EndpointCommand::add_path_component(
+              child_id.clone(),
+              path_id.clone(),
+              segment.clone(),
+            )));
+          (child_id, false)
+        }
+      };
+
+      let child_context = PathContext {
+        pattern: format!("{}/{}", context.pattern, segment),
+        parameter_names: context.parameter_names.clone(),
+        segment: Some(segment),
+      };
+      self.learn(observed_child, &child_id, is_child_existing, child_context);
+    }
+
+    if let Some(observed_parameter) = observed.parameter {
+      let (parameter_id, parameter_name, is_parameter_existing) = match existing_parameter {
+        Some((existing_id, existing_name)) => (existing_id, existing_name, true),
+        None => {
+          let parameter_id = self.id_generator.path();
+          let parameter_name = parameter_name(&context);
+          self
+            .learned_paths
+            .commands
+            .push(SpecCommand::from(EndpointCommand::add_path_parameter(
+              parameter_id.clone(),
+              path_id.clone(),
+              parameter_name.clone(),
+            )));
+          (parameter_id, parameter_name, false)
+        }
+      };
+
+      let mut parameter_names = context.parameter_names.clone();
+      parameter_names.push(parameter_name.clone());
+      let parameter_context = PathContext {
+        pattern: format!("{}/{{{}}}", context.pattern, parameter_name),
+        parameter_names,
+        segment: None,
+      };
+      self.learn(
+        *observed_parameter,
+        &parameter_id,
+        is_parameter_existing,
+        parameter_context,
+      );
+    }
+  }
+}
+
+/// Names a parameter after the collection it identifies an item of, like `userId` for `/users`,
+/// unique among the parameters of the path
+fn parameter_name(context: &PathContext) -> String {
+  let base_name = match &context.segment {
+    Some(segment) => {
+      let singular = if let Some(stem) = segment.strip_suffix("ies") {
+        format!("{}y", stem)
+      } else {
+        String::from(segment.strip_suffix('s').unwrap_or(segment))
+      };
+      let camel_cased: String = singular
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(index, word)| {
+          let mut chars = word.chars();
+          match chars.next() {
+            Some(first) if index > 0 => first.to_ascii_uppercase().to_string() + chars.as_str(),
+            Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+            None => String::new(),
+          }
+        })
+        .collect();
+      format!("{}Id", camel_cased)
+    }
+    None => String::from("id"),
+  };
+
+  let mut name = base_name.clone();
+  let mut suffix = 2;
+  while context.parameter_names.contains(&name) {
+    name = format!("{}{}", base_name, suffix);
+    suffix += 1;
+  }
+  name
+}
+
+/// Numbers, UUIDs, hashes and random tokens
+fn is_id_like(segment: &str) -> bool {
+  let is_hex = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit());
+  let digit_count = segment.chars().filter(|c| c.is_ascii_digit()).count();
+  let has_letters = segment.chars().any(|c| c.is_ascii_alphabetic());
+
+  let is_number = digit_count == segment.len();
+  let is_uuid = {
+    let groups: Vec<&str> = segment.split('-').collect();
+    groups
+      .iter()
+      .map(|group| group.len())
+      .eq([8, 4, 4, 4, 12].iter().copied())
+      && groups.iter().all(|group| is_hex(group))
+  };
+  let is_hash = segment.len() >= 16 && is_hex(segment) && digit_count > 0;
+  let is_token = segment.len() >= 8 && digit_count >= 2 && has_letters;
+
+  !segment.is_empty() && (is_number || is_uuid || is_hash || is_token)
+}
+
+/// Segments like `hello-world` or `item_2`, which are only identifiers when there are many
+fn is_slug_like(segment: &str) -> bool {
+  segment
+    .chars()
+    .any(|c| c == '-' || c == '_' || c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::events::SpecEvent;
+  use crate::interactions::result::{InteractionTrail, RequestSpecTrail, SpecRoot};
+  use crate::projections::SpecProjection;
+  use insta::assert_debug_snapshot;
+  use serde_json::json;
+
+  #[derive(Debug, Default)]
+  struct TestIdGenerator {
+    counter: usize,
+  }
+
+  impl SpecIdGenerator for TestIdGenerator {
+    fn generate_id(&mut self, prefix: &str) -> String {
+      let id = format!("test-id-{}-{}", prefix, self.counter);
+      self.counter += 1;
+      id
+    }
+  }
+
+  fn unmatched_request_url(method: &str, path: &str) -> UnmatchedRequestUrl {
+    let mut interaction_trail = InteractionTrail::empty();
+    interaction_trail.with_url(String::from(path));
+    interaction_trail.with_method(String::from(method));
+    UnmatchedRequestUrl::new(interaction_trail, RequestSpecTrail::SpecRoot(SpecRoot {}))
+  }
+
+  #[test]
+  fn undocumented_paths_can_be_learned_from_unmatched_urls() {
+    let events: Vec<SpecEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded": { "pathId": "path_1", "parentPathId": "root", "name": "users" }},
+      {"PathComponentAdded": { "pathId": "path_2", "parentPathId": "root", "name": "teams" }},
+      {"PathParameterAdded": { "pathId": "path_3", "parentPathId": "path_2", "name": "team" }},
+    ]))
+    .expect("should be able to deserialize test events");
+    let mut spec_projection = SpecProjection::from(events);
+
+    let mut projection = LearnedUndocumentedPathsProjection::default();
+    let unmatched_urls = vec![
+      ("GET", "/users/12/likes"),
+      ("GET", "/users/7/likes/"),
+      ("GET", "/users/me"),
+      ("GET", "/teams/platform/members"),
+      ("DELETE", "/orders/550e8400-e29b-41d4-a716-446655440000"),
+      ("GET", "/files/5f0c2b8e9d1a4c3b2e1f0a9b"),
+      ("GET", "/about/our-team"),
+      ("GET", "/posts/hello-world"),
+      ("GET", "/posts/rust-in-production"),
+      ("GET", "/posts/learning-from-traffic"),
+      ("GET", "/posts/post-4"),
+      ("GET", "/posts/the-fifth-one/comments/3"),
+      ("GET", "/categories/9/categories/12"),
+    ];
+    for (method, path) in unmatched_urls {
+      projection.apply(unmatched_request_url(method, path));
+    }
+
+    let learned_paths =
+      projection.into_learned_paths(spec_projection.endpoint(), &mut TestIdGenerator::default());
+    assert_debug_snapshot!(
+      "undocumented_paths_can_be_learned_from_unmatched_urls__path_patterns",
+      &learned_paths.path_patterns
+    );
+    assert_debug_snapshot!(
+      "undocumented_paths_can_be_learned_from_unmatched_urls__commands",
+      &learned_paths.commands
+    );
+
+    for command in learned_paths.commands {
+      let events = spec_projection
+        .execute(command)
+        .expect("generated commands must be valid");
+      for event in events {
+        spec_projection.apply(event);
+      }
+    }
+  }
+
+  #[test]
+  fn segments_can_be_recognized_as_ids() {
+    assert!(is_id_like("12"));
+    assert!(is_id_like("550e8400-e29b-41d4-a716-446655440000"));
+    assert!(is_id_like("5f0c2b8e9d1a4c3b2e1f0a9b"));
+    assert!(is_id_like("V1StGXR8_Z5jdHi6B-myT"));
+    assert!(!is_id_like("users"));
+    assert!(!is_id_like("v2"));
+    assert!(!is_id_like("oauth2callback"));
+    assert!(!is_id_like("deadbeef"));
+    assert!(!is_id_like(""));
+  }
+}
@@ -14,12 +14,15 @@ pub use history::{CommitId, HistoryProjection};
 pub use learners::{
   shape_diff_affordances::LearnedShapeDiffAffordancesProjection,
   undocumented_bodies::LearnedUndocumentedBodiesProjection,
+  undocumented_paths::{LearnedPaths, LearnedUndocumentedPathsProjection},
 };
 pub use shape::ShapeProjection;
-pub use spec_events::{SpecAssemblerError, SpecAssemblerProjection};
+pub use spec_events::{
+  SpecAssemblerError, SpecAssemblerProjection, SpecChunkConflict, SpecChunkConflictKind,
+};
 pub use spectacle::endpoints::EndpointsProjection;
 
-use crate::events::{EndpointEvent, RfcEvent, ShapeEvent, SpecEvent};
+use crate::events::{EndpointEvent, RfcEvent, ShapeEvent, SpecEvent, UnknownBatchCommitError};
 use cqrs_core::{Aggregate, AggregateCommand, AggregateEvent, CommandError};
 use std::error::Error;
 
@@ -47,6 +50,15 @@ impl Default for SpecProjection {
 }
 
 impl SpecProjection {
+  /// Project the spec as it was right after the batch commit with the given id
+  pub fn at_batch_commit(
+    events: &[SpecEvent],
+    batch_id: &str,
+  ) -> Result<Self, UnknownBatchCommitError> {
+    let events = SpecEvent::until_batch_commit(events, batch_id)?;
+    Ok(Self::from(events.iter().cloned()))
+  }
+
   pub fn endpoint(&self) -> &EndpointProjection {
     &self.endpoint
   }
@@ -93,6 +93,8 @@ pub struct FieldNodeDescriptor {
 pub struct ShapeProjection {
   pub graph: Graph<Node, Edge>,
   pub node_id_to_index: HashMap<NodeId, petgraph::graph::NodeIndex>,
+  /// Names given to shapes, for those that have one
+  pub shape_names: HashMap<ShapeId, String>,
 }
 
 impl Default for ShapeProjection {
@@ -102,6 +104,7 @@ impl Default for ShapeProjection {
     let mut projection = ShapeProjection {
       graph,
       node_id_to_index,
+      shape_names: HashMap::new(),
     };
 
     add_core_shape_to_projection(&mut projection, ShapeKind::StringKind);
@@ -114,6 +117,9 @@ impl Default for ShapeProjection {
     add_core_shape_to_projection(&mut projection, ShapeKind::UnknownKind);
     add_core_shape_to_projection(&mut projection, ShapeKind::OptionalKind);
     add_core_shape_to_projection(&mut projection, ShapeKind::OneOfKind);
+    add_core_shape_to_projection(&mut projection, ShapeKind::MapKind);
+    add_core_shape_to_projection(&mut projection, ShapeKind::IdentifierKind);
+    add_core_shape_to_projection(&mut projection, ShapeKind::ReferenceKind);
     projection
   }
 }
@@ -219,6 +225,7 @@ impl ShapeProjection {
       shape_id: shape_id.clone(),
     });
     let shape_node_index = self.graph.add_node(shape_node);
+    self.with_shape_name(shape_id.clone(), name);
     self.node_id_to_index.insert(shape_id, shape_node_index);
 
     let base_shape_node_index = self
@@ -238,6 +245,14 @@ impl ShapeProjection {
     );
   }
 
+  pub fn with_shape_name(&mut self, shape_id: ShapeId, name: String) {
+    if name.is_empty() {
+      self.shape_names.remove(&shape_id);
+    } else {
+      self.shape_names.insert(shape_id, name);
+    }
+  }
+
   pub fn with_base_shape(&mut self, shape_id: ShapeId, base_shape_id: ShapeId) {
     let shape_node_index = *self.node_id_to_index.get(&shape_id).unwrap_or_else(|| {
       panic!(
@@ -661,6 +676,10 @@ impl AggregateEvent<ShapeProjection> for ShapeEvent {
         // TODO: track removal history
       }
 
+      ShapeEvent::ShapeRenamed(e) => {
+        projection.with_shape_name(e.shape_id, e.name);
+      }
+
       ShapeEvent::BaseShapeSet(e) => {
         projection.with_base_shape(e.shape_id.clone(), e.base_shape_id);
 
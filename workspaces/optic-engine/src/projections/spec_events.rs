@@ -1,11 +1,14 @@
+use super::conflicts::{ConflictsProjection, Node as ConflictNode};
 use crate::events::spec_chunk::{BatchChunkEvent, RootChunkEvent};
-use crate::events::SpecChunkEvent;
+use crate::events::{RfcEvent, SpecChunkEvent};
+use crate::state::endpoint::{HttpMethod, HttpStatusCode};
 use crate::SpecEvent;
+use serde::Serialize;
 use std::error::Error;
 use std::fmt;
 
 use cqrs_core::{Aggregate, AggregateEvent};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 #[derive(Debug)]
 pub struct SpecAssemblerProjection {
@@ -42,39 +45,208 @@ impl SpecAssemblerProjection {
 
   // TODO: implement returning of an Iterator instead of Vec
   pub fn into_events(self) -> Result<Vec<SpecEvent>, SpecAssemblerError> {
+    let (events, _conflicts) = self.into_events_with_conflicts()?;
+    Ok(events)
+  }
+
+  /// Assemble the events of all chunks, linearizing chunks that share the same parent, like
+  /// those committed on separate git branches before being merged. Siblings are ordered by
+  /// when they were committed (and their id, in case of a tie), and every sibling is followed
+  /// by its own descendants. Changes made on more than one side of such a fork that conflict
+  /// with each other are returned alongside the events.
+  pub fn into_events_with_conflicts(
+    self,
+  ) -> Result<(Vec<SpecEvent>, Vec<SpecChunkConflict>), SpecAssemblerError> {
     let root_chunk = self
       .root_chunk
       .ok_or_else(|| SpecAssemblerError::RootChunkRequired)?;
 
-    let mut current_chunk_id = root_chunk.last_batch_id().clone();
-    let mut chunks = vec![SpecChunkEvent::Root(root_chunk)];
+    let root_chunk_id = root_chunk.last_batch_id().clone();
     let mut chunks_by_parent_id = self.chunks_by_parent_id;
+    for children in chunks_by_parent_id.values_mut() {
+      children.sort_by(|a, b| {
+        batch_created_at(a)
+          .cmp(&batch_created_at(b))
+          .then_with(|| a.id.cmp(&b.id))
+      });
+    }
 
+    // linearize by visiting the tree of chunks depth first
+    let mut ordered_chunks: Vec<&BatchChunkEvent> = vec![];
+    let mut pending_chunks: Vec<&BatchChunkEvent> = vec![];
+    let mut forks: Vec<&str> = vec![];
+    let mut visited_ids: HashSet<&str> = HashSet::new();
+    let mut current_id = Some(root_chunk_id.as_str());
     loop {
-      let children = chunks_by_parent_id.remove(&current_chunk_id);
-      if let None = children {
-        break;
+      if let Some(children) = current_id.and_then(|id| chunks_by_parent_id.get(id)) {
+        if children.len() > 1 {
+          forks.push(current_id.unwrap());
+        }
+        pending_chunks.extend(children.iter().rev());
       }
-      let mut children = children.unwrap();
 
-      if children.len() != 1 {
-        // if there are multiple children to a parent
-        break; // TODO: decide whether for this projection we need to do anything more
+      match pending_chunks.pop() {
+        // guard against chunks claiming to be their own ancestor
+        Some(chunk) if visited_ids.insert(&chunk.id) => {
+          ordered_chunks.push(chunk);
+          current_id = Some(&chunk.id);
+        }
+        Some(_) => current_id = None,
+        None => break,
       }
-      let child_chunk = children.pop().unwrap();
-
-      current_chunk_id = child_chunk.id.clone();
-      chunks.push(SpecChunkEvent::Batch(child_chunk));
     }
 
-    let events = chunks
+    let conflicts = forks
       .into_iter()
+      .flat_map(|fork_id| {
+        fork_conflicts(&root_chunk, &ordered_chunks, &chunks_by_parent_id, fork_id)
+      })
+      .collect();
+
+    let ordered_chunk_ids: Vec<_> = ordered_chunks
+      .iter()
+      .map(|chunk| chunk.id.clone())
+      .collect();
+    let mut chunks_by_id: HashMap<_, _> = chunks_by_parent_id
+      .into_values()
+      .flatten()
+      .map(|chunk| (chunk.id.clone(), chunk))
+      .collect();
+
+    let events = std::iter::once(SpecChunkEvent::Root(root_chunk))
+      .chain(ordered_chunk_ids.into_iter().map(|chunk_id| {
+        SpecChunkEvent::Batch(
+          chunks_by_id
+            .remove(&chunk_id)
+            .expect("ordered chunks should have been assembled"),
+        )
+      }))
       .flat_map(|chunk| chunk.into_events_iter());
 
-    Ok(events.collect())
+    Ok((events.collect(), conflicts))
   }
 }
 
+fn batch_created_at(chunk: &BatchChunkEvent) -> Option<&str> {
+  chunk.events.iter().find_map(|event| match event {
+    SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(e)) => e
+      .event_context
+      .as_ref()
+      .map(|context| context.created_at.as_str()),
+    _ => None,
+  })
+}
+
+/// Conflicts between the sides of a fork, found by comparing what each side added to or changed
+/// in the conflicts projection of the spec they were based on
+fn fork_conflicts(
+  root_chunk: &RootChunkEvent,
+  ordered_chunks: &[&BatchChunkEvent],
+  chunks_by_parent_id: &HashMap<String, Vec<BatchChunkEvent>>,
+  fork_id: &str,
+) -> Vec<SpecChunkConflict> {
+  let parent_ids: HashMap<&str, &str> = ordered_chunks
+    .iter()
+    .map(|chunk| (chunk.id.as_str(), chunk.parent_id.as_str()))
+    .collect();
+  let is_descendant_of = |chunk_id: &str, ancestor_id: &str| {
+    let mut current_id = chunk_id;
+    // bounded, in case of chunks claiming to be their own ancestor
+    for _ in 0..=parent_ids.len() {
+      if current_id == ancestor_id {
+        return true;
+      }
+      match parent_ids.get(current_id) {
+        Some(parent_id) => current_id = parent_id,
+        None => return false,
+      }
+    }
+    false
+  };
+
+  let base_events = root_chunk.events.iter().chain(
+    ordered_chunks
+      .iter()
+      .filter(|chunk| is_descendant_of(fork_id, &chunk.id))
+      .flat_map(|chunk| chunk.events.iter()),
+  );
+  let mut base = ConflictsProjection::from(base_events.cloned());
+  base.edited_ids.clear();
+
+  let fork_chunks: Vec<_> = ordered_chunks
+    .iter()
+    .filter(|chunk| chunk.id != fork_id && is_descendant_of(&chunk.id, fork_id))
+    .collect();
+
+  let sides: Vec<(&str, ConflictsProjection)> = chunks_by_parent_id[fork_id]
+    .iter()
+    .map(|side_chunk| {
+      let mut side = base.clone();
+      for chunk in fork_chunks
+        .iter()
+        .filter(|chunk| is_descendant_of(&chunk.id, &side_chunk.id))
+      {
+        for event in &chunk.events {
+          side.apply(event.clone());
+        }
+      }
+      (side_chunk.id.as_str(), side)
+    })
+    .collect();
+
+  let mut merged = base.clone();
+  for chunk in &fork_chunks {
+    for event in &chunk.events {
+      merged.apply(event.clone());
+    }
+  }
+
+  let mut conflicts = vec![];
+  for (node_index, node) in merged.conflicting_nodes() {
+    // the sides that added any of the entities resolving to the same node
+    let batch_ids: Vec<String> = sides
+      .iter()
+      .filter(|(_, side)| {
+        node.conflicting_ids().iter().any(|domain_id| {
+          side.domain_id_to_index.contains_key(domain_id)
+            && !base.domain_id_to_index.contains_key(domain_id)
+        })
+      })
+      .map(|(side_id, _)| String::from(*side_id))
+      .collect();
+    if batch_ids.len() > 1 {
+      conflicts.push(SpecChunkConflict {
+        parent_id: fork_id.to_owned(),
+        batch_ids,
+        kind: SpecChunkConflictKind::from_node(&merged, node_index, node),
+      });
+    }
+  }
+
+  let mut edited_ids: BTreeSet<&String> = BTreeSet::new();
+  for (_, side) in &sides {
+    edited_ids.extend(side.edited_ids.iter());
+  }
+  for edited_id in edited_ids {
+    let batch_ids: Vec<String> = sides
+      .iter()
+      .filter(|(_, side)| side.edited_ids.contains(edited_id))
+      .map(|(side_id, _)| String::from(*side_id))
+      .collect();
+    if batch_ids.len() > 1 {
+      conflicts.push(SpecChunkConflict {
+        parent_id: fork_id.to_owned(),
+        batch_ids,
+        kind: SpecChunkConflictKind::ConcurrentEdit {
+          id: edited_id.clone(),
+        },
+      });
+    }
+  }
+
+  conflicts
+}
+
 impl Aggregate for SpecAssemblerProjection {
   fn aggregate_type() -> &'static str {
     "spec_assembler_projection"
@@ -111,6 +283,102 @@ where
   }
 }
 
+// SpecChunkConflict
+// -----------------
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecChunkConflict {
+  /// The batch the conflicting chunks were both based on
+  pub parent_id: String,
+  /// The first chunk of every side of the fork involved in the conflict
+  pub batch_ids: Vec<String>,
+  pub kind: SpecChunkConflictKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpecChunkConflictKind {
+  #[serde(rename_all = "camelCase")]
+  PathAdded {
+    path_pattern: String,
+    path_ids: Vec<String>,
+  },
+  #[serde(rename_all = "camelCase")]
+  RequestAdded {
+    path_pattern: Option<String>,
+    http_method: HttpMethod,
+    request_ids: Vec<String>,
+  },
+  #[serde(rename_all = "camelCase")]
+  ResponseAdded {
+    path_pattern: Option<String>,
+    http_method: Option<HttpMethod>,
+    http_status_code: HttpStatusCode,
+    response_ids: Vec<String>,
+  },
+  #[serde(rename_all = "camelCase")]
+  FieldAdded {
+    shape_id: String,
+    field_name: String,
+    field_ids: Vec<String>,
+  },
+  #[serde(rename_all = "camelCase")]
+  Other { ids: Vec<String> },
+  /// The same entity was changed on more than one side
+  #[serde(rename_all = "camelCase")]
+  ConcurrentEdit { id: String },
+}
+
+impl SpecChunkConflictKind {
+  fn from_node(
+    projection: &ConflictsProjection,
+    node_index: petgraph::graph::NodeIndex,
+    node: &ConflictNode,
+  ) -> Self {
+    match node {
+      ConflictNode::Path(path_pattern, ids) => Self::PathAdded {
+        path_pattern: path_pattern.clone(),
+        path_ids: ids.clone(),
+      },
+      ConflictNode::HttpMethod(http_method, ids) => Self::RequestAdded {
+        path_pattern: path_pattern_of(projection, node_index),
+        http_method: http_method.clone(),
+        request_ids: ids.clone(),
+      },
+      ConflictNode::HttpStatusCode(http_status_code, ids) => {
+        let method = projection.parent_node(node_index);
+        Self::ResponseAdded {
+          path_pattern: method
+            .and_then(|(method_index, _)| path_pattern_of(projection, method_index)),
+          http_method: match method {
+            Some((_, ConflictNode::HttpMethod(http_method, _))) => Some(http_method.clone()),
+            _ => None,
+          },
+          http_status_code: *http_status_code,
+          response_ids: ids.clone(),
+        }
+      }
+      ConflictNode::Field(shape_id, field_name, ids) => Self::FieldAdded {
+        shape_id: shape_id.clone(),
+        field_name: field_name.clone(),
+        field_ids: ids.clone(),
+      },
+      ConflictNode::HttpContentType(_, ids) => Self::Other { ids: ids.clone() },
+    }
+  }
+}
+
+fn path_pattern_of(
+  projection: &ConflictsProjection,
+  method_index: petgraph::graph::NodeIndex,
+) -> Option<String> {
+  match projection.parent_node(method_index) {
+    Some((_, ConflictNode::Path(path_pattern, _))) => Some(path_pattern.clone()),
+    _ => None,
+  }
+}
+
 // SpecAssemblerError
 // ------------------
 
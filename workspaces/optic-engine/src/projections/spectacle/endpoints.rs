@@ -2,7 +2,8 @@ use crate::events::{EndpointEvent, Event, ShapeEvent, SpecEvent};
 use crate::projections::endpoint::ROOT_PATH_ID;
 use crate::state::endpoint::{
   HttpContentType, HttpMethod, HttpStatusCode, PathComponentId, QueryParametersId,
-  QueryParametersShapeDescriptor, RequestId, ResponseId,
+  QueryParametersShapeDescriptor, RequestId, RequestParameterId, ResponseId,
+  ShapedRequestParameterShapeDescriptor,
 };
 use crate::state::shape::ShapeId;
 use crate::RfcEvent;
@@ -202,6 +203,44 @@ impl AggregateEvent<EndpointsProjection> for EndpointEvent {
           projection.with_creation_history(c.client_command_batch_id, e.body_descriptor.shape_id);
         }
       }
+      EndpointEvent::HeaderParameterAdded(e) => {
+        projection.with_header_parameter(e.parameter_id.clone(), e.request_id, e.name);
+
+        if let Some(c) = e.event_context {
+          projection.with_creation_history(c.client_command_batch_id, e.parameter_id);
+        }
+      }
+      EndpointEvent::ResponseHeaderParameterAdded(e) => {
+        projection.with_header_parameter(e.parameter_id.clone(), e.response_id, e.name);
+
+        if let Some(c) = e.event_context {
+          projection.with_creation_history(c.client_command_batch_id, e.parameter_id);
+        }
+      }
+      EndpointEvent::HeaderParameterShapeSet(e) => {
+        projection.with_header_parameter_shape(e.parameter_id.clone(), e.parameter_descriptor);
+        if let Some(c) = e.event_context {
+          projection.with_update_history(c.client_command_batch_id, e.parameter_id);
+        }
+      }
+      EndpointEvent::HeaderParameterRenamed(e) => {
+        projection.with_header_parameter_name(e.parameter_id.clone(), e.name);
+        if let Some(c) = e.event_context {
+          projection.with_update_history(c.client_command_batch_id, e.parameter_id);
+        }
+      }
+      EndpointEvent::HeaderParameterShapeUnset(e) => {
+        projection.without_header_parameter_shape(e.parameter_id.clone());
+        if let Some(c) = e.event_context {
+          projection.with_update_history(c.client_command_batch_id, e.parameter_id);
+        }
+      }
+      EndpointEvent::HeaderParameterRemoved(e) => {
+        projection.without_header_parameter(&e.parameter_id);
+        if let Some(c) = e.event_context {
+          projection.with_remove_history(c.client_command_batch_id, e.parameter_id);
+        }
+      }
       _ => eprintln!(
         "Ignoring applying event of type '{}' for '{}'",
         self.event_type(),
@@ -764,6 +803,80 @@ impl EndpointsProjection {
       .add_edge(node_index, response_index, Edge::IsChildOf);
   }
   ////////////////////////////////////////////////////////////////////////////////////////////////////
+  pub fn with_header_parameter(
+    &mut self,
+    parameter_id: RequestParameterId,
+    parent_id: String,
+    name: String,
+  ) {
+    let parent_index = *self
+      .domain_id_to_index
+      .get(&parent_id)
+      .expect("expected node with domain_id $parent_id to exist in the graph");
+
+    let node = Node::HeaderParameter(HeaderParameterNode {
+      parameter_id: parameter_id.clone(),
+      name,
+      root_shape_id: None,
+      is_removed: false,
+    });
+    let node_index = self.graph.add_node(node);
+    self
+      .graph
+      .add_edge(node_index, parent_index, Edge::IsChildOf);
+
+    self.domain_id_to_index.insert(parameter_id, node_index);
+  }
+  ////////////////////////////////////////////////////////////////////////////////////////////////////
+  pub fn with_header_parameter_shape(
+    &mut self,
+    parameter_id: RequestParameterId,
+    shape_descriptor: ShapedRequestParameterShapeDescriptor,
+  ) {
+    let header_index = *self
+      .domain_id_to_index
+      .get(&parameter_id)
+      .expect("expected node with domain_id $parameter_id to exist in the graph");
+
+    if let Some(Node::HeaderParameter(header_node)) = self.graph.node_weight_mut(header_index) {
+      header_node.root_shape_id = Some(shape_descriptor.shape_id);
+      header_node.is_removed = shape_descriptor.is_removed;
+    }
+  }
+  ////////////////////////////////////////////////////////////////////////////////////////////////////
+  pub fn with_header_parameter_name(&mut self, parameter_id: RequestParameterId, name: String) {
+    let header_index = *self
+      .domain_id_to_index
+      .get(&parameter_id)
+      .expect("expected node with domain_id $parameter_id to exist in the graph");
+
+    if let Some(Node::HeaderParameter(header_node)) = self.graph.node_weight_mut(header_index) {
+      header_node.name = name;
+    }
+  }
+  ////////////////////////////////////////////////////////////////////////////////////////////////////
+  pub fn without_header_parameter_shape(&mut self, parameter_id: RequestParameterId) {
+    let header_index = *self
+      .domain_id_to_index
+      .get(&parameter_id)
+      .expect("expected node with domain_id $parameter_id to exist in the graph");
+
+    if let Some(Node::HeaderParameter(header_node)) = self.graph.node_weight_mut(header_index) {
+      header_node.root_shape_id = None;
+    }
+  }
+  ////////////////////////////////////////////////////////////////////////////////////////////////////
+  pub fn without_header_parameter(&mut self, parameter_id: &RequestParameterId) {
+    let header_index = *self
+      .domain_id_to_index
+      .get(parameter_id)
+      .expect("expected node with domain_id $parameter_id to exist in the graph");
+
+    if let Some(Node::HeaderParameter(header_node)) = self.graph.node_weight_mut(header_index) {
+      header_node.is_removed = true;
+    }
+  }
+  ////////////////////////////////////////////////////////////////////////////////////////////////////
   pub fn with_batch_commit(
     &mut self,
     batch_id: String,
@@ -840,6 +953,7 @@ pub enum Node {
   Request(RequestNode),
   Response(ResponseNode),
   QueryParameters(QueryParametersNode),
+  HeaderParameter(HeaderParameterNode),
   Body(BodyNode),
   BatchCommit(BatchCommitNode),
 }
@@ -886,6 +1000,15 @@ pub struct QueryParametersNode {
   is_removed: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderParameterNode {
+  parameter_id: RequestParameterId,
+  name: String,
+  root_shape_id: Option<ShapeId>,
+  is_removed: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseNode {
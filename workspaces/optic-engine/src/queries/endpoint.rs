@@ -1,14 +1,16 @@
 use crate::commands::{EndpointCommand, SpecCommand};
 use crate::events::HttpInteraction;
-use crate::projections::endpoint::{Edge, EndpointProjection, Node, ROOT_PATH_ID};
 use crate::projections::endpoint::{
-  QueryParametersDescriptor, RequestDescriptor, ResponseBodyDescriptor,
+  Edge, EndpointProjection, Node, PathComponentDescriptor, ROOT_PATH_ID,
+};
+use crate::projections::endpoint::{
+  HeaderParameterDescriptor, QueryParametersDescriptor, RequestDescriptor, ResponseBodyDescriptor,
 };
 use crate::state::endpoint::{
   HttpMethod, HttpStatusCode, PathComponentId, PathComponentIdRef, QueryParametersId, RequestId,
-  ResponseId,
+  RequestParameterId, ResponseId, ShapedRequestParameterShapeDescriptor,
 };
-use petgraph::graph::Graph;
+use petgraph::graph::{Graph, NodeIndex};
 use petgraph::visit::{
   depth_first_search, Control, DfsEvent, EdgeFilteredNeighborsDirected, Reversed,
 };
@@ -44,67 +46,134 @@ impl<'a> EndpointQueries<'a> {
   }
 
   pub fn resolve_path(&self, path: &str) -> Option<PathComponentIdRef> {
+    match self.resolve_most_specific_paths(path).as_slice() {
+      [path_id] => Some(path_id),
+      _ => None,
+    }
+  }
+
+  /// The most specific path components matching a path, backtracking through all candidates.
+  /// Literal path components are more specific than path parameters, with earlier components of
+  /// the path weighing more than later ones. More than one result means the path is ambiguous.
+  pub fn resolve_most_specific_paths(&self, path: &str) -> Vec<PathComponentIdRef<'_>> {
     if path.eq("/") {
-      return Some(ROOT_PATH_ID);
+      return vec![ROOT_PATH_ID];
     }
 
     let path = Self::extract_normalized_path(path);
-    // eprintln!("{}", path);
-    let mut path_components = path.split('/');
     // skip leading empty
-    path_components.next();
-    let mut last_resolved_path_id = Some(ROOT_PATH_ID);
-    while let Some(s) = path_components.next() {
-      // eprintln!("trying to match segment {}", s);
-      let node_index = self
-        .graph_get_index(last_resolved_path_id.unwrap())
-        .expect("expected a node with node_id to exist");
-
-      last_resolved_path_id = None;
-
-      // first search for named path components
-      let children = self.graph_get_children(node_index);
-      for child in children {
-        let child_node = self.endpoint_projection.graph.node_weight(child).unwrap();
+    let segments: Vec<&str> = path.split('/').skip(1).collect();
 
-        match child_node {
-          Node::PathComponent(id, descriptor) => {
-            // eprintln!("1 - neighbor {}", descriptor.name);
-            if !descriptor.is_parameter {
-              if descriptor.name == s {
-                last_resolved_path_id = Some(id);
-                break;
-              }
-            }
+    let root_node_index = self
+      .graph_get_index(ROOT_PATH_ID)
+      .expect("a root path component node should exist");
+    let mut pending_candidates = vec![PathCandidate {
+      path_id: ROOT_PATH_ID,
+      components: vec![],
+    }];
+    let mut candidates = vec![];
+
+    while let Some(candidate) = pending_candidates.pop() {
+      let segment = match segments.get(candidate.components.len()) {
+        Some(segment) => segment,
+        None => {
+          candidates.push(candidate);
+          continue;
+        }
+      };
+
+      let node_index = match candidate.components.last() {
+        Some((node_index, _)) => node_index,
+        None => root_node_index,
+      };
+      for child in self.graph_get_children(node_index) {
+        let child_node = self.endpoint_projection.graph.node_weight(child).unwrap();
+        if let Node::PathComponent(child_id, descriptor) = child_node {
+          if descriptor.is_parameter || descriptor.name == *segment {
+            let mut components = candidate.components.clone();
+            components.push((child, descriptor));
+            pending_candidates.push(PathCandidate {
+              path_id: child_id,
+              components,
+            });
           }
-          _ => {}
         }
       }
-      if let Some(x) = last_resolved_path_id {
+    }
+
+    let most_specific = match candidates.iter().map(PathCandidate::specificity).max() {
+      Some(specificity) => specificity,
+      None => return vec![],
+    };
+    // candidates with the same pattern are duplicates, as left by merging branches of a spec,
+    // which resolve to the most recently added like other conflicts
+    let mut most_specific_by_pattern: HashMap<Vec<&str>, PathCandidate> = HashMap::new();
+    for candidate in candidates {
+      if candidate.specificity() != most_specific {
         continue;
       }
-      // try path parameters since we didn't find any matches in regular path components
-      let children = self.graph_get_children(node_index);
-      for child in children {
-        let child_node = self.endpoint_projection.graph.node_weight(child).unwrap();
-        match child_node {
-          Node::PathComponent(id, descriptor) => {
-            // eprintln!("2 - neighbor {}", descriptor.name);
-
-            if descriptor.is_parameter {
-              last_resolved_path_id = Some(id);
-              break;
-            }
-          }
-          _ => {}
+      let pattern = candidate.pattern();
+      match most_specific_by_pattern.get(&pattern) {
+        Some(duplicate) if duplicate.node_indices() > candidate.node_indices() => {}
+        _ => {
+          most_specific_by_pattern.insert(pattern, candidate);
         }
       }
-      if let Some(x) = last_resolved_path_id {
-        continue;
+    }
+
+    let mut most_specific_paths: Vec<_> = most_specific_by_pattern
+      .into_values()
+      .map(|candidate| candidate.path_id)
+      .collect();
+    most_specific_paths.sort_unstable();
+    most_specific_paths
+  }
+
+  /// The path parameters along the path to a path component, with the segments of a matching
+  /// path they take the values of, from the root onwards.
+  pub fn resolve_path_parameter_values<'p>(
+    &'p self,
+    path_id: PathComponentIdRef,
+    path: &'p str,
+  ) -> Vec<PathParameterValue<'p>> {
+    let path = Self::extract_normalized_path(path);
+    let mut segments = path.split('/').skip(1);
+
+    let mut components = vec![];
+    let mut node_index = *self
+      .graph_get_index(path_id)
+      .expect("expected a node with node_id to exist");
+    while let Node::PathComponent(component_id, descriptor) = self
+      .endpoint_projection
+      .graph
+      .node_weight(node_index)
+      .expect("expected node with node index to exist")
+    {
+      if component_id == ROOT_PATH_ID {
+        break;
       }
-      return None;
+      components.push((component_id, descriptor));
+      node_index = match self.graph_get_parent_path(&node_index) {
+        Some(parent_index) => parent_index,
+        None => break,
+      };
     }
-    last_resolved_path_id
+
+    components
+      .into_iter()
+      .rev()
+      .filter_map(|component| Some((component, segments.next()?)))
+      .filter(|((_, descriptor), _)| descriptor.is_parameter)
+      .map(|((component_id, descriptor), value)| PathParameterValue {
+        path_id: component_id,
+        name: &descriptor.name,
+        shape: self
+          .endpoint_projection
+          .path_parameter_shapes
+          .get(component_id),
+        value,
+      })
+      .collect()
   }
 
   pub fn resolve_unused_paths(&self) -> impl Iterator<Item = PathComponentId> + '_ {
@@ -206,6 +275,82 @@ impl<'a> EndpointQueries<'a> {
     unused_path_ids_sorted.into_iter()
   }
 
+  /// Lists every known path component with the path pattern it describes, with path
+  /// parameters written as `{name}`.
+  pub fn resolve_path_patterns(&self) -> Vec<(&PathComponentId, String)> {
+    let mut path_patterns = vec![];
+    let mut pending_paths = vec![(
+      self
+        .endpoint_projection
+        .node_id_to_index
+        .get_key_value(ROOT_PATH_ID)
+        .map(|(root_path_id, _)| root_path_id)
+        .expect("a root path component node should exist"),
+      String::from(""),
+    )];
+
+    while let Some((path_id, pattern)) = pending_paths.pop() {
+      let children = self
+        .endpoint_projection
+        .get_child_path_component_nodes(path_id)
+        .into_iter()
+        .flatten();
+      for child_node in children {
+        if let Node::PathComponent(child_id, descriptor) = child_node {
+          let child_pattern = if descriptor.is_parameter {
+            format!("{}/{{{}}}", pattern, descriptor.name)
+          } else {
+            format!("{}/{}", pattern, descriptor.name)
+          };
+          pending_paths.push((child_id, child_pattern));
+        }
+      }
+
+      let pattern = if pattern.is_empty() {
+        String::from("/")
+      } else {
+        pattern
+      };
+      path_patterns.push((path_id, pattern));
+    }
+
+    path_patterns
+  }
+
+  pub fn resolve_path_methods(
+    &self,
+    path_id: PathComponentIdRef,
+  ) -> impl Iterator<Item = &HttpMethod> {
+    let path_node_index = self
+      .graph_get_index(path_id)
+      .expect("expected a node with node_id to exist");
+    self
+      .graph_get_children(path_node_index)
+      .filter_map(
+        move |i| match self.endpoint_projection.graph.node_weight(i) {
+          Some(Node::HttpMethod(http_method)) => Some(http_method),
+          _ => None,
+        },
+      )
+  }
+
+  pub fn resolve_endpoint_responses(
+    &self,
+    path_id: &'a PathComponentId,
+    method: &'a str,
+  ) -> impl Iterator<Item = (&HttpStatusCode, &ResponseId, &ResponseBodyDescriptor)> {
+    self
+      .endpoint_projection
+      .get_response_nodes(path_id)
+      .into_iter()
+      .flatten()
+      .filter(move |(http_method, _, _)| *http_method == method)
+      .map(|(_, status_code, node)| match node {
+        Node::Response(response_id, body_descriptor) => (status_code, response_id, body_descriptor),
+        _ => unreachable!("get response nodes should only return response nodes"),
+      })
+  }
+
   pub fn resolve_operations_by_request_method(
     &self,
     method: &'a String,
@@ -234,6 +379,17 @@ impl<'a> EndpointQueries<'a> {
       .get_endpoint_query_parameter_node(&path_id.to_owned(), method)
   }
 
+  pub fn resolve_header_parameters(
+    &self,
+    parent_id: &str,
+  ) -> impl Iterator<Item = (&RequestParameterId, &HeaderParameterDescriptor)> {
+    self
+      .endpoint_projection
+      .get_header_parameter_nodes(parent_id)
+      .into_iter()
+      .flatten()
+  }
+
   pub fn resolve_requests(
     &self,
     path_id: PathComponentIdRef,
@@ -450,6 +606,19 @@ impl<'a> EndpointQueries<'a> {
       .expect("expected node with node_id to exist")
   }
 
+  fn graph_get_parent_path(&self, node_index: &NodeIndex) -> Option<NodeIndex> {
+    self
+      .endpoint_projection
+      .graph
+      .neighbors_directed(*node_index, petgraph::Direction::Outgoing)
+      .find(|parent_index| {
+        matches!(
+          self.endpoint_projection.graph.node_weight(*parent_index),
+          Some(Node::PathComponent(_, _))
+        )
+      })
+  }
+
   fn graph_get_children(
     &self,
     node_index: &petgraph::graph::NodeIndex,
@@ -462,6 +631,48 @@ impl<'a> EndpointQueries<'a> {
   }
 }
 
+/// A path parameter of a resolved path, with the segment of the path it was matched to
+#[derive(Debug)]
+pub struct PathParameterValue<'a> {
+  pub path_id: &'a PathComponentId,
+  pub name: &'a str,
+  pub shape: Option<&'a ShapedRequestParameterShapeDescriptor>,
+  pub value: &'a str,
+}
+
+/// A chain of path components matching the start of a path
+struct PathCandidate<'a> {
+  path_id: PathComponentIdRef<'a>,
+  components: Vec<(NodeIndex, &'a PathComponentDescriptor)>,
+}
+
+impl<'a> PathCandidate<'a> {
+  /// Whether each of the components matched literally
+  fn specificity(&self) -> Vec<bool> {
+    self
+      .components
+      .iter()
+      .map(|(_, descriptor)| !descriptor.is_parameter)
+      .collect()
+  }
+
+  fn pattern(&self) -> Vec<&'a str> {
+    self
+      .components
+      .iter()
+      .map(|(_, descriptor)| descriptor.name.as_str())
+      .collect()
+  }
+
+  fn node_indices(&self) -> Vec<NodeIndex> {
+    self
+      .components
+      .iter()
+      .map(|(node_index, _)| *node_index)
+      .collect()
+  }
+}
+
 #[derive(Debug, Serialize)]
 pub struct DeleteEndpointCommands {
   path_id: PathComponentId,
@@ -580,6 +791,55 @@ mod test {
     assert_eq!(endpoint_queries.resolve_path("/").unwrap(), "root");
   }
 
+  #[test]
+  pub fn can_resolve_paths_by_specificity() {
+    let events: Vec<SpecEvent> = serde_json::from_value(json!([
+      {"PathComponentAdded": { "pathId": "path_users", "parentPathId": "root", "name": "users" }},
+      {"PathComponentAdded": { "pathId": "path_me", "parentPathId": "path_users", "name": "me" }},
+      {"PathParameterAdded": { "pathId": "path_user_id", "parentPathId": "path_users", "name": "userId" }},
+      {"PathComponentAdded": { "pathId": "path_settings", "parentPathId": "path_user_id", "name": "settings" }},
+      {"PathComponentAdded": { "pathId": "path_posts", "parentPathId": "path_user_id", "name": "posts" }},
+      {"PathComponentAdded": { "pathId": "path_me_posts", "parentPathId": "path_me", "name": "posts" }},
+      {"PathParameterAdded": { "pathId": "path_post_id", "parentPathId": "path_posts", "name": "postId" }},
+      {"PathParameterAdded": { "pathId": "path_post_slug", "parentPathId": "path_posts", "name": "slug" }},
+      {"PathComponentAdded": { "pathId": "path_users_duplicate", "parentPathId": "root", "name": "users" }},
+    ]))
+    .expect("should be able to deserialize test events");
+
+    let spec_projection = SpecProjection::from(events);
+    let endpoint_queries = EndpointQueries::new(spec_projection.endpoint());
+
+    // duplicates, like after merging branches, resolve to the most recently added
+    assert_eq!(
+      endpoint_queries.resolve_path("/users"),
+      Some("path_users_duplicate")
+    );
+    assert_eq!(endpoint_queries.resolve_path("/users/me"), Some("path_me"));
+    assert_eq!(
+      endpoint_queries.resolve_path("/users/12"),
+      Some("path_user_id")
+    );
+    // no `settings` below `/users/me`, so backtracks to `/users/{userId}/settings`
+    assert_eq!(
+      endpoint_queries.resolve_path("/users/me/settings"),
+      Some("path_settings")
+    );
+    assert_eq!(
+      endpoint_queries.resolve_path("/users/me/posts"),
+      Some("path_me_posts")
+    );
+    assert_eq!(endpoint_queries.resolve_path("/users/me/likes"), None);
+
+    assert_eq!(endpoint_queries.resolve_path("/users/12/posts/3"), None);
+    assert_eq!(
+      endpoint_queries.resolve_most_specific_paths("/users/12/posts/3"),
+      vec!["path_post_id", "path_post_slug"]
+    );
+    assert!(endpoint_queries
+      .resolve_most_specific_paths("/users/12/likes")
+      .is_empty());
+  }
+
   #[test]
   pub fn can_find_unused_paths() {
     let events: Vec<SpecEvent> = serde_json::from_value(json!([
@@ -61,6 +61,15 @@ impl<'a> ShapeQueries<'a> {
           Some(Node::Shape(shape_node)) => shape_node.shape_id.clone(),
           _ => unreachable!("expected to be a core shape node"),
         };
+        // shapes wrapping themselves, without a value in between, don't describe any value
+        if shape_trail.follows_wrapping_shapes_from(&shape_id) {
+          return vec![ChoiceOutput {
+            parent_trail: shape_trail.clone(),
+            additional_components: vec![],
+            shape_id,
+            core_shape_kind: ShapeKind::UnknownKind,
+          }];
+        }
         let trails: Vec<ChoiceOutput> = match core_shape_node.descriptor.kind {
           ShapeKind::NullableKind => {
             let nullable_parameter_id = core_shape_node
@@ -119,6 +128,28 @@ impl<'a> ShapeQueries<'a> {
             output.append(&mut self.list_trail_choices(&trail));
             output
           }
+          ShapeKind::IdentifierKind | ShapeKind::ReferenceKind => {
+            let inner_parameter_id = core_shape_node
+              .descriptor
+              .kind
+              .get_parameter_descriptor()
+              .unwrap()
+              .shape_parameter_id;
+            let inner_shape_id =
+              self.resolve_parameter_to_shape(&shape_id, &String::from(inner_parameter_id));
+
+            let trail = shape_trail.with_component(match core_shape_node.descriptor.kind {
+              ShapeKind::IdentifierKind => ShapeTrailPathComponent::IdentifierTrail {
+                shape_id: shape_id.clone(),
+                inner_shape_id,
+              },
+              _ => ShapeTrailPathComponent::ReferenceTrail {
+                shape_id: shape_id.clone(),
+                referenced_shape_id: inner_shape_id,
+              },
+            });
+            self.list_trail_choices(&trail)
+          }
           ShapeKind::OneOfKind => self
             .resolve_parameters_to_shapes(&shape_id)
             .into_iter()
@@ -159,7 +190,57 @@ impl<'a> ShapeQueries<'a> {
       .collect()
   }
 
+  /// Resolves the core shape of a shape, following identifiers and references to the shapes
+  /// they point to. References that lead back to themselves resolve to UnknownKind.
   pub fn resolve_to_core_shape(&self, shape_id: &ShapeId) -> &ShapeKind {
+    let mut followed_shape_ids = BTreeSet::new();
+    let mut current_shape_id = shape_id.clone();
+
+    loop {
+      let core_shape_kind = self.resolve_to_own_core_shape(&current_shape_id);
+      match core_shape_kind {
+        ShapeKind::IdentifierKind | ShapeKind::ReferenceKind => {
+          if !followed_shape_ids.insert(current_shape_id.clone()) {
+            return &ShapeKind::UnknownKind;
+          }
+          let inner_parameter_id = core_shape_kind
+            .get_parameter_descriptor()
+            .unwrap()
+            .shape_parameter_id;
+          current_shape_id =
+            self.resolve_parameter_to_shape(&current_shape_id, &String::from(inner_parameter_id));
+        }
+        _ => return core_shape_kind,
+      }
+    }
+  }
+
+  /// The name given to a shape, if any
+  pub fn resolve_shape_name(&self, shape_id: &ShapeId) -> Option<&str> {
+    self
+      .shape_projection
+      .shape_names
+      .get(shape_id)
+      .map(|name| name.as_str())
+  }
+
+  /// Resolves the shape a shape descends from, when that isn't a core shape
+  pub fn resolve_base_shape_id(&self, shape_id: &ShapeId) -> Option<&ShapeId> {
+    let shape_node_index = self.shape_projection.get_shape_node_index(shape_id)?;
+    let base_node_index = self
+      .shape_projection
+      .get_ancestor_shape_node_index(shape_node_index)?;
+    match self.shape_projection.graph.node_weight(base_node_index) {
+      Some(Node::Shape(base_shape_node)) if base_node_index != *shape_node_index => {
+        Some(&base_shape_node.shape_id)
+      }
+      _ => None,
+    }
+  }
+
+  /// Resolves the core shape a shape directly descends from, without following identifiers
+  /// or references.
+  pub fn resolve_to_own_core_shape(&self, shape_id: &ShapeId) -> &ShapeKind {
     //@TODO: use petgraph::visit::EdgeFiltered, etc.
     //@GOTCHA: this does not support multiple levels of ancestors
     let shape_node_index = self
@@ -193,6 +274,22 @@ impl<'a> ShapeQueries<'a> {
     //   "resolve_trail_to_core_shape_helper {:?}",
     //   parent.core_shape_kind.get_descriptor().name
     // );
+    // identifiers and references are followed regardless of what they resolve to
+    if let ShapeTrailPathComponent::IdentifierTrail {
+      inner_shape_id: target_shape_id,
+      ..
+    }
+    | ShapeTrailPathComponent::ReferenceTrail {
+      referenced_shape_id: target_shape_id,
+      ..
+    } = path_component
+    {
+      return ResolvedTrail {
+        shape_id: target_shape_id.clone(),
+        core_shape_kind: self.resolve_to_core_shape(target_shape_id),
+      };
+    }
+
     match parent.core_shape_kind {
       ShapeKind::ListKind => match path_component {
         ShapeTrailPathComponent::ListItemTrail {
@@ -204,6 +301,17 @@ impl<'a> ShapeQueries<'a> {
         },
         _ => unreachable!("should only receive ListItemTrail relative to ListKind"),
       },
+      ShapeKind::MapKind => match path_component {
+        ShapeTrailPathComponent::MapTrail { shape_id } => ResolvedTrail {
+          shape_id: shape_id.clone(),
+          core_shape_kind: &ShapeKind::MapKind,
+        },
+        ShapeTrailPathComponent::MapValueTrail { value_shape_id, .. } => ResolvedTrail {
+          shape_id: value_shape_id.clone(),
+          core_shape_kind: self.resolve_to_core_shape(value_shape_id),
+        },
+        _ => unreachable!("should only receive MapTrail or MapValueTrail relative to MapKind"),
+      },
       ShapeKind::OneOfKind => match path_component {
         ShapeTrailPathComponent::OneOfTrail { shape_id } => ResolvedTrail {
           shape_id: parent.shape_id.clone(),
@@ -463,6 +571,10 @@ impl<'a> ShapeQueries<'a> {
               let shape_id = shape_node.shape_id.clone();
               trail_components.push(ShapeTrailPathComponent::ObjectTrail { shape_id });
             }
+            ShapeKind::MapKind => {
+              let shape_id = shape_node.shape_id.clone();
+              trail_components.push(ShapeTrailPathComponent::MapTrail { shape_id });
+            }
             ShapeKind::OptionalKind => {
               unimplemented!("resolving of shape trail by optional shape")
             }
@@ -480,12 +592,12 @@ impl<'a> ShapeQueries<'a> {
               root_shape_id = None;
             }
 
-            ShapeKind::AnyKind
-            | ShapeKind::MapKind
-            | ShapeKind::IdentifierKind
-            | ShapeKind::ReferenceKind => {
+            ShapeKind::AnyKind => {
               unimplemented!("resolving of shape trail by complex typed shapes")
             }
+            ShapeKind::IdentifierKind | ShapeKind::ReferenceKind => {
+              unreachable!("identifiers and references resolve to the shapes they point to")
+            }
           }
 
           root_shape_id.replace(shape_node.shape_id.clone());
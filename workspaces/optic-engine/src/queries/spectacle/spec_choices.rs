@@ -49,12 +49,21 @@ pub struct ArrayChoice {
   item_shape_id: ShapeId,
 }
 
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MapChoice {
+  json_type: JsonType,
+  shape_id: ShapeId,
+  value_shape_id: ShapeId,
+}
+
 #[derive(Debug, Serialize, Clone)]
 #[serde(tag = "type")]
 pub enum ShapeChoice {
   Primitive(PrimitiveChoice),
   Object(ObjectChoice),
   Array(ArrayChoice),
+  Map(MapChoice),
   Any,
   Unknown,
 }
@@ -65,6 +74,7 @@ impl ShapeChoice {
       ShapeChoice::Primitive(choice) => Some(&choice.json_type),
       ShapeChoice::Object(choice) => Some(&choice.json_type),
       ShapeChoice::Array(choice) => Some(&choice.json_type),
+      ShapeChoice::Map(choice) => Some(&choice.json_type),
       ShapeChoice::Any => None,
       ShapeChoice::Unknown => None,
     }
@@ -138,7 +148,23 @@ impl<'a> ShapeChoiceQueries<'a> {
           };
           ShapeChoice::Array(output)
         }
-        ShapeKind::MapKind => unimplemented!(),
+        ShapeKind::MapKind => {
+          let shape_parameter_id = &String::from(
+            choice
+              .core_shape_kind
+              .get_parameter_descriptor()
+              .expect("expected $map to have a parameter descriptor")
+              .shape_parameter_id,
+          );
+          let map_value_shape_id =
+            queries.resolve_parameter_to_shape(&choice.shape_id, shape_parameter_id);
+          let output = MapChoice {
+            shape_id: choice.shape_id.clone(),
+            json_type: JsonType::Object,
+            value_shape_id: map_value_shape_id,
+          };
+          ShapeChoice::Map(output)
+        }
         ShapeKind::OneOfKind => unreachable!(),
         ShapeKind::AnyKind => ShapeChoice::Any,
         ShapeKind::UnknownKind => ShapeChoice::Unknown,
@@ -158,8 +184,9 @@ impl<'a> ShapeChoiceQueries<'a> {
           shape_id: choice.shape_id.clone(),
           json_type: JsonType::Null,
         }),
-        ShapeKind::IdentifierKind => unimplemented!(),
-        ShapeKind::ReferenceKind => unimplemented!(),
+        ShapeKind::IdentifierKind | ShapeKind::ReferenceKind => {
+          unreachable!("identifiers and references resolve to the shapes they point to")
+        }
         ShapeKind::OptionalKind => ShapeChoice::Primitive(PrimitiveChoice {
           shape_id: choice.shape_id.clone(),
           json_type: JsonType::Undefined,
@@ -242,6 +269,27 @@ mod test {
     );
   }
 
+  #[test]
+  pub fn can_list_choices_through_references() {
+    let events: Vec<SpecEvent> = serde_json::from_value(json!([
+      { "ShapeAdded": { "shapeId": "string_shape_1", "baseShapeId": "$string", "name": "", "eventContext": null }},
+      { "ShapeAdded": { "shapeId": "user_shape", "baseShapeId": "$object", "name": "User", "eventContext": null }},
+      { "FieldAdded": { "fieldId": "field_1", "shapeId": "user_shape", "name": "lastName", "shapeDescriptor": { "FieldShapeFromShape": { "fieldId": "field_1", "shapeId": "string_shape_1"}}, "eventContext": null }},
+      { "ShapeAdded": { "shapeId": "reference_shape_1", "baseShapeId": "$reference", "name": "", "eventContext": null }},
+      { "ShapeParameterShapeSet": { "shapeDescriptor": { "ProviderInShape": { "shapeId": "reference_shape_1", "providerDescriptor": { "ShapeProvider": { "shapeId": "user_shape" }}, "consumingParameterId": "$referenceInner" }}, "eventContext": null }},
+    ]))
+    .expect("should be able to deserialize test events");
+
+    let spec_projection = SpecProjection::from(events);
+    let queries = ShapeChoiceQueries::from(spec_projection.shape());
+
+    let choices = queries
+      .trail_choices(&ShapeTrail::new("reference_shape_1".to_owned()))
+      .collect::<Vec<_>>();
+
+    assert_debug_snapshot!("can_list_choices_through_references__choices", choices);
+  }
+
   fn assert_valid_commands(
     mut spec_projection: SpecProjection,
     commands: impl IntoIterator<Item = SpecCommand>,
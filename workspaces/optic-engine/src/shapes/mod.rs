@@ -1,6 +1,7 @@
 use crate::state::body::BodyDescriptor;
 
 mod result;
+mod trail_pattern;
 pub mod traverser;
 pub mod visitors;
 
@@ -11,6 +12,7 @@ use crate::state::shape::ShapeId;
 use crate::InteractionDiffResult;
 pub use result::ShapeDiffResult;
 use std::collections::HashMap;
+pub use trail_pattern::{JsonTrailPattern, JsonTrailPatternError};
 pub use traverser::{JsonTrail, JsonTrailPathComponent, ShapeTrail, ShapeTrailPathComponent};
 use visitors::BodyVisitors;
 
@@ -19,10 +21,20 @@ pub fn diff(
   shapes_projection: &ShapeProjection,
   body: Option<BodyDescriptor>,
   shape_id: &ShapeId,
+) -> Vec<ShapeDiffResult> {
+  diff_ignoring_trails(shapes_projection, body, shape_id, &[])
+}
+
+/// Like `diff`, but leaving out results for json trails matching any of the ignored trails.
+pub fn diff_ignoring_trails(
+  shapes_projection: &ShapeProjection,
+  body: Option<BodyDescriptor>,
+  shape_id: &ShapeId,
+  ignored_trails: &[JsonTrailPattern],
 ) -> Vec<ShapeDiffResult> {
   let shapes_queries = ShapeQueries::new(shapes_projection);
   let shape_traverser = traverser::Traverser::new(&shapes_queries);
-  let mut diff_visitors = visitors::diff::DiffVisitors::new();
+  let mut diff_visitors = visitors::diff::DiffVisitors::new(ignored_trails);
 
   //dbg!(
   //   &shape_id, &body
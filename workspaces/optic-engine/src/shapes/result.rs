@@ -19,6 +19,13 @@ pub enum ShapeDiffResult {
 }
 
 impl ShapeDiffResult {
+  pub fn json_trail(&self) -> &JsonTrail {
+    match self {
+      ShapeDiffResult::UnspecifiedShape { json_trail, .. } => json_trail,
+      ShapeDiffResult::UnmatchedShape { json_trail, .. } => json_trail,
+    }
+  }
+
   pub fn normalized(&self) -> Self {
     match self {
       ShapeDiffResult::UnmatchedShape {
@@ -37,6 +44,24 @@ impl ShapeDiffResult {
       },
     }
   }
+  pub fn prefixed(&self, prefix: &JsonTrail) -> Self {
+    match self {
+      ShapeDiffResult::UnmatchedShape {
+        json_trail,
+        shape_trail,
+      } => ShapeDiffResult::UnmatchedShape {
+        json_trail: json_trail.prefixed(prefix),
+        shape_trail: shape_trail.clone(),
+      },
+      ShapeDiffResult::UnspecifiedShape {
+        json_trail,
+        shape_trail,
+      } => ShapeDiffResult::UnspecifiedShape {
+        json_trail: json_trail.prefixed(prefix),
+        shape_trail: shape_trail.clone(),
+      },
+    }
+  }
   pub fn fingerprint(&self) -> String {
     let normalized = self.normalized();
     let s = serde_json::to_vec(&normalized).expect("ShapeDiffResult should be json serializable");
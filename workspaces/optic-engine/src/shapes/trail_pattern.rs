@@ -0,0 +1,147 @@
+use super::traverser::{JsonTrail, JsonTrailPathComponent};
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// A pattern for json trails in bodies, like `$.debug` or `$.items[*].trace`. Keys are matched
+/// with `.key` or `.*`, array items with `[0]` or `[*]`. A pattern matches a trail when it matches
+/// the trail itself or any of its ancestors, so ignoring an object ignores all of its fields.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct JsonTrailPattern {
+  components: Vec<PatternComponent>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PatternComponent {
+  Key(String),
+  AnyKey,
+  Item(u32),
+  AnyItem,
+}
+
+#[derive(Debug, Error)]
+#[error("invalid json trail pattern '{pattern}': {reason}")]
+pub struct JsonTrailPatternError {
+  pattern: String,
+  reason: &'static str,
+}
+
+impl JsonTrailPattern {
+  pub fn matches(&self, trail: &JsonTrail) -> bool {
+    let mut trail_components = trail.components().iter().filter(|component| {
+      !matches!(
+        component,
+        JsonTrailPathComponent::JsonObject {} | JsonTrailPathComponent::JsonArray {}
+      )
+    });
+
+    self.components.iter().all(|pattern_component| {
+      match (pattern_component, trail_components.next()) {
+        (
+          PatternComponent::Key(key),
+          Some(JsonTrailPathComponent::JsonObjectKey { key: trail_key }),
+        ) => key == trail_key,
+        (
+          PatternComponent::AnyKey,
+          Some(JsonTrailPathComponent::JsonObjectKey { .. })
+          | Some(JsonTrailPathComponent::JsonMapValue {}),
+        ) => true,
+        (
+          PatternComponent::Item(index),
+          Some(JsonTrailPathComponent::JsonArrayItem { index: trail_index }),
+        ) => index == trail_index,
+        (PatternComponent::AnyItem, Some(JsonTrailPathComponent::JsonArrayItem { .. })) => true,
+        _ => false,
+      }
+    })
+  }
+}
+
+impl FromStr for JsonTrailPattern {
+  type Err = JsonTrailPatternError;
+
+  fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+    let error = |reason| JsonTrailPatternError {
+      pattern: String::from(pattern),
+      reason,
+    };
+
+    let mut rest = pattern
+      .strip_prefix('$')
+      .ok_or_else(|| error("patterns start at the root of the body, with '$'"))?;
+    let mut components = vec![];
+    while !rest.is_empty() {
+      if let Some(after_dot) = rest.strip_prefix('.') {
+        let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+        let key = &after_dot[..end];
+        components.push(match key {
+          "" => return Err(error("keys can't be empty")),
+          "*" => PatternComponent::AnyKey,
+          key => PatternComponent::Key(String::from(key)),
+        });
+        rest = &after_dot[end..];
+      } else if let Some(after_bracket) = rest.strip_prefix('[') {
+        let end = after_bracket
+          .find(']')
+          .ok_or_else(|| error("array items have to be closed with ']'"))?;
+        components.push(match &after_bracket[..end] {
+          "*" => PatternComponent::AnyItem,
+          index => PatternComponent::Item(
+            index
+              .parse()
+              .map_err(|_| error("array items are either an index or '*'"))?,
+          ),
+        });
+        rest = &after_bracket[end + 1..];
+      } else {
+        return Err(error("expected '.' or '['"));
+      }
+    }
+
+    Ok(Self { components })
+  }
+}
+
+impl TryFrom<String> for JsonTrailPattern {
+  type Error = JsonTrailPatternError;
+
+  fn try_from(pattern: String) -> Result<Self, Self::Error> {
+    pattern.parse()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn json_trail_patterns_match_trails_and_their_descendants() {
+    let pattern = |pattern: &str| JsonTrailPattern::from_str(pattern).unwrap();
+    let debug_trail = JsonTrail::empty()
+      .with_object()
+      .with_object_key(String::from("debug"));
+    let trace_trail = JsonTrail::empty()
+      .with_object()
+      .with_object_key(String::from("items"))
+      .with_array()
+      .with_array_item(3)
+      .with_object()
+      .with_object_key(String::from("trace"));
+
+    assert!(pattern("$.debug").matches(&debug_trail));
+    assert!(pattern("$.debug").matches(&debug_trail.with_object_key(String::from("sql"))));
+    assert!(pattern("$.*").matches(&debug_trail));
+    assert!(!pattern("$.debug").matches(&trace_trail));
+    assert!(!pattern("$.debug.sql").matches(&debug_trail));
+    assert!(pattern("$.items[*].trace").matches(&trace_trail));
+    assert!(pattern("$.items[3]").matches(&trace_trail));
+    assert!(!pattern("$.items[2]").matches(&trace_trail));
+    assert!(pattern("$").matches(&trace_trail));
+
+    assert!(JsonTrailPattern::from_str("debug").is_err());
+    assert!(JsonTrailPattern::from_str("$.items[first]").is_err());
+    assert!(JsonTrailPattern::from_str("$..debug").is_err());
+  }
+}
@@ -124,37 +124,35 @@ impl<'a> Traverser<'a> {
           _ => unreachable!("expect body to be an object"),
         };
 
+        // maps don't prescribe keys, so only objects take part in visiting keys
         let object_key_choices = matching_choices
           .iter()
+          .filter(|choice| matches!(choice.core_shape_kind, ShapeKind::ObjectKind))
           .map(|choice| {
-            if let ShapeKind::ObjectKind = &choice.core_shape_kind {
-              // - find field node by key in object's field node edges
-              let field_ids = self
-                .shape_queries
-                .resolve_shape_field_id_and_names(&choice.shape_id);
-
-              (
-                choice,
-                field_ids
-                  .map(|(field_id, field_name)| {
-                    let field_shape_id = self
-                      .shape_queries
-                      .resolve_field_shape_node(&field_id)
-                      .unwrap();
-                    let field_core_shape_kind =
-                      self.shape_queries.resolve_to_core_shape(&field_shape_id);
-                    (
-                      field_name.clone(),
-                      field_id.clone(),
-                      field_shape_id.clone(),
-                      field_core_shape_kind,
-                    )
-                  })
-                  .collect::<Vec<_>>(),
-              )
-            } else {
-              unreachable!("should only contain choices of object kind");
-            }
+            // - find field node by key in object's field node edges
+            let field_ids = self
+              .shape_queries
+              .resolve_shape_field_id_and_names(&choice.shape_id);
+
+            (
+              choice,
+              field_ids
+                .map(|(field_id, field_name)| {
+                  let field_shape_id = self
+                    .shape_queries
+                    .resolve_field_shape_node(&field_id)
+                    .unwrap();
+                  let field_core_shape_kind =
+                    self.shape_queries.resolve_to_core_shape(&field_shape_id);
+                  (
+                    field_name.clone(),
+                    field_id.clone(),
+                    field_shape_id.clone(),
+                    field_core_shape_kind,
+                  )
+                })
+                .collect::<Vec<_>>(),
+            )
           })
           .collect::<Vec<_>>();
 
@@ -196,8 +194,28 @@ impl<'a> Traverser<'a> {
                       parent_object_shape_id: choice.shape_id.clone(),
                     });
                 self.shape_queries.list_known_trail_choices(&field_trail)
+              } else if let ShapeKind::MapKind = &choice.core_shape_kind {
+                // every key of a map shares the same value shape
+                let value_shape_id = self.shape_queries.resolve_parameter_to_shape(
+                  &choice.shape_id,
+                  &String::from(
+                    choice
+                      .core_shape_kind
+                      .get_parameter_descriptor()
+                      .unwrap()
+                      .shape_parameter_id,
+                  ),
+                );
+                let value_trail =
+                  choice
+                    .shape_trail()
+                    .with_component(ShapeTrailPathComponent::MapValueTrail {
+                      map_shape_id: choice.shape_id.clone(),
+                      value_shape_id,
+                    });
+                self.shape_queries.list_known_trail_choices(&value_trail)
               } else {
-                unreachable!("should only contain choices of object kind");
+                unreachable!("should only contain choices of object or map kind");
               }
             })
             .collect::<Vec<ChoiceOutput>>();
@@ -245,6 +263,13 @@ pub enum ShapeTrailPathComponent {
     item_shape_id: ShapeId,
   },
   #[serde(rename_all = "camelCase")]
+  MapTrail { shape_id: ShapeId },
+  #[serde(rename_all = "camelCase")]
+  MapValueTrail {
+    map_shape_id: ShapeId,
+    value_shape_id: ShapeId,
+  },
+  #[serde(rename_all = "camelCase")]
   OneOfTrail { shape_id: ShapeId },
   #[serde(rename_all = "camelCase")]
   OneOfItemTrail {
@@ -267,6 +292,16 @@ pub enum ShapeTrailPathComponent {
     inner_shape_id: ShapeId,
   },
   #[serde(rename_all = "camelCase")]
+  IdentifierTrail {
+    shape_id: ShapeId,
+    inner_shape_id: ShapeId,
+  },
+  #[serde(rename_all = "camelCase")]
+  ReferenceTrail {
+    shape_id: ShapeId,
+    referenced_shape_id: ShapeId,
+  },
+  #[serde(rename_all = "camelCase")]
   UnknownTrail {},
 }
 
@@ -297,6 +332,31 @@ impl ShapeTrail {
     )
   }
 
+  /// Whether this trail passed through the given shape since it last reached a value of its
+  /// own, following identifiers, references, nullables, optionals or one-ofs.
+  pub fn follows_wrapping_shapes_from(&self, shape_id: &ShapeId) -> bool {
+    self
+      .path
+      .iter()
+      .rev()
+      .map_while(|component| match component {
+        ShapeTrailPathComponent::IdentifierTrail { shape_id, .. }
+        | ShapeTrailPathComponent::ReferenceTrail { shape_id, .. }
+        | ShapeTrailPathComponent::NullableItemTrail { shape_id, .. }
+        | ShapeTrailPathComponent::OptionalItemTrail { shape_id, .. }
+        | ShapeTrailPathComponent::OneOfItemTrail {
+          one_of_id: shape_id,
+          ..
+        } => Some(Some(shape_id)),
+        ShapeTrailPathComponent::NullableTrail { .. }
+        | ShapeTrailPathComponent::OptionalTrail { .. }
+        | ShapeTrailPathComponent::OneOfTrail { .. } => Some(None),
+        _ => None,
+      })
+      .flatten()
+      .any(|followed_shape_id| followed_shape_id == shape_id)
+  }
+
   pub fn last_field_id(&self) -> Option<&FieldId> {
     self
       .path
@@ -319,6 +379,9 @@ pub enum JsonTrailPathComponent {
   JsonObjectKey { key: String },
   #[serde(rename_all = "camelCase")]
   JsonArrayItem { index: u32 },
+  /// Stands in for every key of a map when learning, so all its values are observed as one
+  #[serde(rename_all = "camelCase")]
+  JsonMapValue {},
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -351,6 +414,28 @@ impl JsonTrail {
     self.with_component(JsonTrailPathComponent::JsonObjectKey { key })
   }
 
+  pub fn with_map_value(&self) -> Self {
+    self.with_component(JsonTrailPathComponent::JsonMapValue {})
+  }
+
+  /// For a trail descending from the given map trail, replace the key of the map it descends
+  /// through with the shared map value component.
+  pub fn as_map_value_of(&self, map_trail: &JsonTrail) -> Option<Self> {
+    if !self.is_descendant_of(map_trail) {
+      return None;
+    }
+
+    let key_index = map_trail.path.len();
+    match self.path[key_index] {
+      JsonTrailPathComponent::JsonObjectKey { .. } => {
+        let mut path = self.path.clone();
+        path[key_index] = JsonTrailPathComponent::JsonMapValue {};
+        Some(Self { path })
+      }
+      _ => None,
+    }
+  }
+
   pub fn normalized(&self) -> Self {
     Self {
       path: self
@@ -366,6 +451,17 @@ impl JsonTrail {
     }
   }
 
+  pub fn prefixed(&self, prefix: &JsonTrail) -> Self {
+    Self {
+      path: prefix
+        .path
+        .iter()
+        .chain(self.path.iter())
+        .cloned()
+        .collect(),
+    }
+  }
+
   pub fn pop(&mut self) -> Option<JsonTrailPathComponent> {
     self.path.pop()
   }
@@ -386,6 +482,10 @@ impl JsonTrail {
   pub fn last_component(&self) -> Option<&JsonTrailPathComponent> {
     self.path.last()
   }
+
+  pub fn components(&self) -> &[JsonTrailPathComponent] {
+    &self.path
+  }
 }
 
 impl PartialEq for JsonTrail {
@@ -424,6 +524,7 @@ impl fmt::Display for JsonTrail {
         JsonTrailPathComponent::JsonObjectKey { key } => Some(key.clone()),
         JsonTrailPathComponent::JsonArray {} => None,
         JsonTrailPathComponent::JsonObject {} => None,
+        JsonTrailPathComponent::JsonMapValue {} => Some(String::from("*")),
       })
       .collect::<Vec<_>>();
     write!(f, "{}", identifiers.join("."))
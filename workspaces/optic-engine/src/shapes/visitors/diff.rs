@@ -4,7 +4,9 @@ use super::{
 };
 use crate::queries::shape::ChoiceOutput;
 use crate::shapes::ShapeDiffResult;
-use crate::shapes::{JsonTrail, JsonTrailPathComponent, ShapeTrail, ShapeTrailPathComponent};
+use crate::shapes::{
+  JsonTrail, JsonTrailPathComponent, JsonTrailPattern, ShapeTrail, ShapeTrailPathComponent,
+};
 use crate::state::body::BodyDescriptor;
 use crate::state::shape::{FieldId, ShapeId, ShapeKind};
 use serde_json::Value as JsonValue;
@@ -14,15 +16,17 @@ pub struct DiffVisitors {
   object: DiffObjectVisitor,
   object_key: DiffObjectKeyVisitor,
   primitive: DiffPrimitiveVisitor,
+  ignored_trails: Vec<JsonTrailPattern>,
 }
 
 impl DiffVisitors {
-  pub fn new() -> Self {
+  pub fn new(ignored_trails: &[JsonTrailPattern]) -> Self {
     DiffVisitors {
       array: DiffArrayVisitor::new(),
       object: DiffObjectVisitor::new(),
       object_key: DiffObjectKeyVisitor::new(),
       primitive: DiffPrimitiveVisitor::new(),
+      ignored_trails: ignored_trails.to_vec(),
     }
   }
 }
@@ -50,6 +54,28 @@ impl BodyVisitors<ShapeDiffResult> for DiffVisitors {
   fn primitive(&mut self) -> &mut DiffPrimitiveVisitor {
     &mut self.primitive
   }
+
+  fn take_results(&mut self) -> Option<Vec<ShapeDiffResult>> {
+    let results = vec![
+      self.primitive.take_results(),
+      self.array.take_results(),
+      self.object.take_results(),
+      self.object_key.take_results(),
+    ];
+    let ignored_trails = &self.ignored_trails;
+    Some(
+      results
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|result| {
+          !ignored_trails
+            .iter()
+            .any(|pattern| pattern.matches(result.json_trail()))
+        })
+        .collect(),
+    )
+  }
 }
 
 // Primitive visitor
@@ -103,10 +129,16 @@ impl BodyPrimitiveVisitor<ShapeDiffResult> for DiffPrimitiveVisitor {
           ShapeKind::StringKind => true,
           _ => false,
         },
+        BodyDescriptor::NumericString => matches!(
+          choice.core_shape_kind,
+          ShapeKind::NumberKind | ShapeKind::StringKind
+        ),
         BodyDescriptor::Null => match choice.core_shape_kind {
           ShapeKind::NullableKind => true,
           _ => false,
         },
+        // learned as a string, see BodyDescriptor::Binary
+        BodyDescriptor::Binary => matches!(choice.core_shape_kind, ShapeKind::StringKind),
         _ => unreachable!("should not call primitive visitor without a primitive value"),
       });
     if matched.is_empty() {
@@ -222,7 +254,7 @@ impl BodyObjectVisitor<ShapeDiffResult> for DiffObjectVisitor {
     let (matched, unmatched): (Vec<&ChoiceOutput>, Vec<&ChoiceOutput>) =
       trail_choices.into_iter().partition(|choice| match body {
         BodyDescriptor::Object(_) => match choice.core_shape_kind {
-          ShapeKind::ObjectKind => true,
+          ShapeKind::ObjectKind | ShapeKind::MapKind => true,
           _ => false,
         },
         _ => unreachable!("should only call object visitor for object body types"),
@@ -0,0 +1,689 @@
+use super::event_ids::{
+  added_id, changed_id, field_shape_descriptor_field_id, referenced_ids, removed_id,
+};
+use crate::events::rfc::{BatchCommitEnded, BatchCommitStarted};
+use crate::events::{
+  EndpointEvent, RfcEvent, ShapeEvent, SpecEvent, UnknownBatchCommitError, ROOT_BATCH_ID,
+};
+use crate::projections::{endpoint, shape, SpecProjection};
+use crate::state::shape::ParameterShapeDescriptor;
+use crate::Aggregate;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet};
+
+/// Compact the events of a spec into a minimal log describing the same spec. The events are
+/// replayed up to every kept boundary and only what's part of the spec there is described:
+/// entities that were added and removed again are left out, as are shapes no endpoint or named
+/// shape uses anymore. Renames are folded into the events adding what they rename and only the
+/// last of repeated changes (like setting a body) is kept. Contributions to what's left are
+/// preserved, those to squashed batch commits move to the batch commit they're squashed into.
+///
+/// All batch commits are squashed into the last one, unless their ids are kept, in which case
+/// the spec as it was right after each of them is preserved as well.
+pub fn compact_events(
+  spec_events: &[SpecEvent],
+  kept_batch_ids: &[&str],
+) -> Result<Vec<SpecEvent>, UnknownBatchCommitError> {
+  let mut boundary_batch_ids = HashSet::new();
+  for batch_id in kept_batch_ids {
+    SpecEvent::until_batch_commit(spec_events, batch_id)?;
+    if *batch_id != ROOT_BATCH_ID {
+      boundary_batch_ids.insert(*batch_id);
+    }
+  }
+  // the last batch commit is always kept, for the contributions to those squashed into it
+  if let Some(last_batch_id) = spec_events.iter().rev().find_map(|event| match event {
+    SpecEvent::RfcEvent(RfcEvent::BatchCommitEnded(e)) => Some(e.batch_id.as_str()),
+    _ => None,
+  }) {
+    boundary_batch_ids.insert(last_batch_id);
+  }
+
+  let segments = Segments::from_events(spec_events, &boundary_batch_ids);
+  let specs = segments.replay(spec_events);
+  let events = &segments.events;
+
+  // entities added between the same boundaries that aren't part of the spec at the end of them,
+  // unless anything that's kept still refers to them. Not every removal is replayed (like that of
+  // shapes), so entities removed between the same boundaries count as gone either way.
+  let added_segments: HashMap<&str, usize> = events
+    .iter()
+    .filter_map(|(segment, event)| added_id(event).map(|id| (id, *segment)))
+    .collect();
+  let removed_segments: HashMap<&str, usize> = events
+    .iter()
+    .filter_map(|(segment, event)| removed_id(event).map(|id| (id, *segment)))
+    .collect();
+  let mut dead_ids: HashSet<&str> = events
+    .iter()
+    .filter_map(|(segment, event)| {
+      let id = added_id(event)?;
+      let is_removed = removed_segments.get(id) == Some(segment);
+      let is_replayed = !matches!(
+        event,
+        SpecEvent::EndpointEvent(EndpointEvent::RequestParameterAddedByPathAndMethod(_))
+      );
+      if is_removed || (is_replayed && !has_entity(&specs[*segment], id)) {
+        Some(id)
+      } else {
+        None
+      }
+    })
+    .collect();
+  loop {
+    let referenced_dead_ids: Vec<&str> = events
+      .iter()
+      .filter(|(_, event)| !matches!(changed_id(event), Some(id) if dead_ids.contains(id)))
+      .flat_map(|(_, event)| referenced_ids(event))
+      .filter(|id| dead_ids.contains(id))
+      .collect();
+    if referenced_dead_ids.is_empty() {
+      break;
+    }
+    for id in referenced_dead_ids {
+      dead_ids.remove(id);
+    }
+  }
+
+  // contributions to squashed batch commits move to the batch commit they're squashed into. Only
+  // batch commits that were never ended aren't squashed into one, their contributions are kept.
+  let contributed_events: Vec<(usize, SpecEvent)> = events
+    .iter()
+    .map(|(segment, event)| match event {
+      SpecEvent::RfcEvent(RfcEvent::ContributionAdded(e)) => match segments.squashed_into(&e.id) {
+        Some(batch_id) => {
+          let mut contribution = e.clone();
+          contribution.id = String::from(batch_id);
+          (
+            *segment,
+            SpecEvent::from(RfcEvent::ContributionAdded(contribution)),
+          )
+        }
+        None => (*segment, (*event).clone()),
+      },
+      event => (*segment, (*event).clone()),
+    })
+    .collect();
+
+  // only the last of repeated changes between the same boundaries is kept, with renames of
+  // entities added between them folded into the events adding them
+  let mut last_changes: HashMap<(usize, ChangeKey), usize> = HashMap::new();
+  for (index, (segment, event)) in contributed_events.iter().enumerate() {
+    if let Some(key) = change_key(event) {
+      last_changes.insert((*segment, key), index);
+    }
+  }
+  let mut folded_names: HashMap<&str, &str> = HashMap::new();
+  let mut kept_events: Vec<(usize, SpecEvent)> = vec![];
+  for (index, (segment, event)) in contributed_events.iter().enumerate() {
+    if matches!(changed_id(event), Some(id) if dead_ids.contains(id)) {
+      continue;
+    }
+    if let SpecEvent::RfcEvent(RfcEvent::ContributionAdded(e)) = event {
+      if dead_ids.contains(e.id.as_str()) {
+        continue;
+      }
+    }
+    if let Some(key) = change_key(event) {
+      if last_changes[&(*segment, key)] != index {
+        continue;
+      }
+    }
+    if let Some((id, name)) = renamed(event) {
+      if added_segments.get(id) == Some(segment) {
+        folded_names.insert(id, name);
+        continue;
+      }
+    }
+    kept_events.push((*segment, event.clone()));
+  }
+  for (_, event) in kept_events.iter_mut() {
+    let added_id = added_id(event).map(String::from);
+    if let Some(name) = added_id.and_then(|id| folded_names.get(id.as_str())) {
+      rename_added(event, name);
+    }
+  }
+
+  let garbage_shape_ids = garbage_shape_ids(&kept_events, &specs);
+  let mut compacted_events = vec![];
+  let mut previous_batch_id = String::from(ROOT_BATCH_ID);
+  for (segment_index, segment) in segments.segments.iter().enumerate() {
+    if let Some((started, _)) = segment {
+      let mut started = started.clone();
+      started.parent_id = Some(previous_batch_id);
+      previous_batch_id = started.batch_id.clone();
+      compacted_events.push(SpecEvent::from(RfcEvent::BatchCommitStarted(started)));
+    }
+    compacted_events.extend(
+      kept_events
+        .iter()
+        .filter(|(segment, event)| {
+          *segment == segment_index
+            && !matches!(
+              owner_shape_id(event, &segments.owners),
+              Some(shape_id) if garbage_shape_ids.contains(shape_id)
+            )
+        })
+        .map(|(_, event)| event.clone()),
+    );
+    if let Some((_, ended)) = segment {
+      compacted_events.push(SpecEvent::from(RfcEvent::BatchCommitEnded(ended.clone())));
+    }
+  }
+
+  Ok(compacted_events)
+}
+
+// Events split at the boundaries of kept batch commits
+struct Segments<'a> {
+  segments: Vec<Option<(BatchCommitStarted, BatchCommitEnded)>>,
+  /// How many of the spec events there are up to the end of each segment
+  ends: Vec<usize>,
+  events: Vec<(usize, &'a SpecEvent)>,
+  /// The segments the events of every batch commit are in
+  batch_segments: HashMap<&'a str, usize>,
+  owners: Owners<'a>,
+}
+
+impl<'a> Segments<'a> {
+  fn from_events(spec_events: &'a [SpecEvent], boundary_batch_ids: &HashSet<&str>) -> Self {
+    let mut segments = vec![];
+    let mut ends = vec![];
+    let mut events = vec![];
+    let mut batch_segments = HashMap::new();
+    let mut segment_has_events = false;
+    let mut has_batches = false;
+    let mut boundary_started = None;
+    for (index, event) in spec_events.iter().enumerate() {
+      match event {
+        SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(e)) => {
+          // events from before any batch commit stay there
+          if !has_batches && segment_has_events {
+            segments.push(None);
+            ends.push(index);
+            segment_has_events = false;
+          }
+          has_batches = true;
+          batch_segments.insert(e.batch_id.as_str(), segments.len());
+          if boundary_batch_ids.contains(e.batch_id.as_str()) {
+            boundary_started = Some(e.clone());
+          }
+        }
+        SpecEvent::RfcEvent(RfcEvent::BatchCommitEnded(e)) => {
+          if boundary_batch_ids.contains(e.batch_id.as_str()) {
+            let started = boundary_started
+              .take()
+              .expect("batch commits should be ended after having been started");
+            segments.push(Some((started, e.clone())));
+            ends.push(index + 1);
+            segment_has_events = false;
+          }
+        }
+        event => {
+          events.push((segments.len(), event));
+          segment_has_events = true;
+        }
+      }
+    }
+    if segment_has_events {
+      segments.push(None);
+      ends.push(spec_events.len());
+    }
+
+    Self {
+      segments,
+      ends,
+      owners: Owners::from_events(spec_events),
+      batch_segments,
+      events,
+    }
+  }
+
+  /// The spec at the end of every segment
+  fn replay(&self, spec_events: &[SpecEvent]) -> Vec<SpecProjection> {
+    let mut spec = SpecProjection::default();
+    let mut replayed = 0;
+    self
+      .ends
+      .iter()
+      .map(|end| {
+        for event in &spec_events[replayed..*end] {
+          spec.apply(event.clone());
+        }
+        replayed = *end;
+        spec.clone()
+      })
+      .collect()
+  }
+
+  /// The batch commit a squashed batch commit is squashed into. None for batch commits that
+  /// aren't squashed into one, or aren't batch commits at all.
+  fn squashed_into(&self, batch_id: &str) -> Option<&str> {
+    let segment = *self.batch_segments.get(batch_id)?;
+    match self.segments.get(segment) {
+      Some(Some((started, _))) if started.batch_id != batch_id => Some(&started.batch_id),
+      _ => None,
+    }
+  }
+}
+
+/// Whether the spec has an entity, as removed entities can't be looked up anymore
+fn has_entity(spec: &SpecProjection, id: &str) -> bool {
+  spec.endpoint().node_id_to_index.contains_key(id)
+    || spec.shape().node_id_to_index.contains_key(id)
+}
+
+// The shapes fields and shape parameters belong to
+struct Owners<'a> {
+  field_shapes: HashMap<&'a str, &'a str>,
+  parameter_shapes: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Owners<'a> {
+  fn from_events(spec_events: &'a [SpecEvent]) -> Self {
+    let mut field_shapes = HashMap::new();
+    let mut parameter_shapes = HashMap::new();
+    for event in spec_events {
+      match event {
+        SpecEvent::ShapeEvent(ShapeEvent::FieldAdded(e)) => {
+          field_shapes.insert(e.field_id.as_str(), e.shape_id.as_str());
+        }
+        SpecEvent::ShapeEvent(ShapeEvent::ShapeParameterAdded(e)) => {
+          parameter_shapes.insert(e.shape_parameter_id.as_str(), e.shape_id.as_str());
+        }
+        _ => {}
+      }
+    }
+
+    Self {
+      field_shapes,
+      parameter_shapes,
+    }
+  }
+}
+
+/// The shape an event changes, either directly or through one of its fields or parameters
+fn owner_shape_id<'a>(event: &'a SpecEvent, owners: &Owners<'a>) -> Option<&'a str> {
+  match event {
+    SpecEvent::ShapeEvent(event) => match event {
+      ShapeEvent::ShapeAdded(e) => Some(&e.shape_id),
+      ShapeEvent::BaseShapeSet(e) => Some(&e.shape_id),
+      ShapeEvent::ShapeRenamed(e) => Some(&e.shape_id),
+      ShapeEvent::ShapeRemoved(e) => Some(&e.shape_id),
+      ShapeEvent::ShapeParameterAdded(e) => Some(&e.shape_id),
+      ShapeEvent::ShapeParameterShapeSet(e) => match &e.shape_descriptor {
+        ParameterShapeDescriptor::ProviderInShape(provider) => Some(&provider.shape_id),
+        ParameterShapeDescriptor::ProviderInField(_) => None,
+      },
+      ShapeEvent::ShapeParameterRenamed(e) => owners
+        .parameter_shapes
+        .get(e.shape_parameter_id.as_str())
+        .copied(),
+      ShapeEvent::ShapeParameterRemoved(e) => owners
+        .parameter_shapes
+        .get(e.shape_parameter_id.as_str())
+        .copied(),
+      ShapeEvent::FieldAdded(e) => Some(&e.shape_id),
+      ShapeEvent::FieldShapeSet(e) => owners
+        .field_shapes
+        .get(field_shape_descriptor_field_id(&e.shape_descriptor))
+        .copied(),
+      ShapeEvent::FieldRenamed(e) => owners.field_shapes.get(e.field_id.as_str()).copied(),
+      ShapeEvent::FieldRemoved(e) => owners.field_shapes.get(e.field_id.as_str()).copied(),
+    },
+    SpecEvent::RfcEvent(RfcEvent::ContributionAdded(e)) => Some(&e.id),
+    _ => None,
+  }
+}
+
+/// Shapes that neither endpoints nor named shapes use, directly or through other shapes, in
+/// the spec at the end of any of the segments
+fn garbage_shape_ids<'a>(
+  kept_events: &'a [(usize, SpecEvent)],
+  specs: &[SpecProjection],
+) -> HashSet<&'a str> {
+  let used_shape_ids: HashSet<String> = specs.iter().flat_map(used_shape_ids).collect();
+
+  kept_events
+    .iter()
+    .filter_map(|(_, event)| match event {
+      SpecEvent::ShapeEvent(ShapeEvent::ShapeAdded(e)) => Some(e.shape_id.as_str()),
+      _ => None,
+    })
+    .filter(|shape_id| !used_shape_ids.contains(*shape_id))
+    .collect()
+}
+
+/// Shapes that endpoints or named shapes of a spec use, directly or through other shapes
+fn used_shape_ids(spec: &SpecProjection) -> HashSet<String> {
+  let endpoint_projection = spec.endpoint();
+  let shape_projection = spec.shape();
+
+  let mut root_shape_ids: Vec<&str> = shape_projection
+    .shape_names
+    .iter()
+    .filter(|(_, name)| !name.is_empty())
+    .map(|(shape_id, _)| shape_id.as_str())
+    .collect();
+  root_shape_ids.extend(
+    endpoint_projection
+      .path_parameter_shapes
+      .values()
+      .map(|shape| shape.shape_id.as_str()),
+  );
+  for node_index in endpoint_projection.node_id_to_index.values() {
+    let root_shape_id = match &endpoint_projection.graph[*node_index] {
+      endpoint::Node::Request(_, descriptor) => {
+        descriptor.body.as_ref().map(|body| &body.root_shape_id)
+      }
+      endpoint::Node::Response(_, descriptor) => {
+        descriptor.body.as_ref().map(|body| &body.root_shape_id)
+      }
+      endpoint::Node::QueryParameters(_, descriptor) => {
+        descriptor.shape.as_ref().map(|shape| &shape.shape_id)
+      }
+      endpoint::Node::HeaderParameter(_, descriptor) => {
+        descriptor.shape.as_ref().map(|shape| &shape.shape_id)
+      }
+      _ => None,
+    };
+    root_shape_ids.extend(root_shape_id.map(String::as_str));
+  }
+
+  let graph = &shape_projection.graph;
+  let mut node_indexes: Vec<_> = root_shape_ids
+    .into_iter()
+    .filter_map(|shape_id| shape_projection.node_id_to_index.get(shape_id).copied())
+    .collect();
+  let mut visited = HashSet::new();
+  let mut used_shape_ids = HashSet::new();
+  while let Some(node_index) = node_indexes.pop() {
+    if !visited.insert(node_index) {
+      continue;
+    }
+    match &graph[node_index] {
+      shape::Node::Shape(node) => {
+        used_shape_ids.insert(node.shape_id.clone());
+        for edge in graph.edges_directed(node_index, Direction::Outgoing) {
+          match edge.weight() {
+            shape::Edge::IsDescendantOf => node_indexes.push(edge.target()),
+            shape::Edge::HasBinding(binding) => node_indexes.extend(
+              shape_projection
+                .node_id_to_index
+                .get(&binding.shape_id)
+                .copied(),
+            ),
+            _ => {}
+          }
+        }
+        node_indexes.extend(
+          graph
+            .edges_directed(node_index, Direction::Incoming)
+            .filter(|edge| matches!(edge.weight(), shape::Edge::IsFieldOf))
+            .map(|edge| edge.source()),
+        );
+      }
+      shape::Node::Field(_) => node_indexes.extend(
+        graph
+          .edges_directed(node_index, Direction::Incoming)
+          .filter(|edge| matches!(edge.weight(), shape::Edge::BelongsTo))
+          .map(|edge| edge.source()),
+      ),
+      _ => {}
+    }
+  }
+  used_shape_ids
+}
+
+#[derive(Debug, Hash, PartialEq, Eq)]
+enum ChangeKey {
+  Name(String),
+  Shape(String),
+  Body(String),
+  ContentType(String),
+  StatusCode(String),
+  ParameterShape(String, String),
+  Contribution(String, String),
+  ApiName,
+  GitState,
+}
+
+/// What an event changes, for events of which only the last one matters
+fn change_key(event: &SpecEvent) -> Option<ChangeKey> {
+  let id = || String::from(changed_id(event).expect("changes should have a subject"));
+  match event {
+    SpecEvent::EndpointEvent(event) => match event {
+      EndpointEvent::PathComponentRenamed(_)
+      | EndpointEvent::PathParameterRenamed(_)
+      | EndpointEvent::RequestParameterRenamed(_)
+      | EndpointEvent::HeaderParameterRenamed(_) => Some(ChangeKey::Name(id())),
+      EndpointEvent::PathParameterShapeSet(_)
+      | EndpointEvent::QueryParametersShapeSet(_)
+      | EndpointEvent::RequestParameterShapeSet(_)
+      | EndpointEvent::RequestParameterShapeUnset(_)
+      | EndpointEvent::HeaderParameterShapeSet(_)
+      | EndpointEvent::HeaderParameterShapeUnset(_) => Some(ChangeKey::Shape(id())),
+      EndpointEvent::RequestBodySet(_)
+      | EndpointEvent::RequestBodyUnset(_)
+      | EndpointEvent::ResponseBodySet(_)
+      | EndpointEvent::ResponseBodyUnset(_) => Some(ChangeKey::Body(id())),
+      EndpointEvent::RequestContentTypeSet(_) | EndpointEvent::ResponseContentTypeSet(_) => {
+        Some(ChangeKey::ContentType(id()))
+      }
+      EndpointEvent::ResponseStatusCodeSet(_) => Some(ChangeKey::StatusCode(id())),
+      _ => None,
+    },
+    SpecEvent::ShapeEvent(event) => match event {
+      ShapeEvent::ShapeRenamed(_)
+      | ShapeEvent::ShapeParameterRenamed(_)
+      | ShapeEvent::FieldRenamed(_) => Some(ChangeKey::Name(id())),
+      ShapeEvent::BaseShapeSet(_) | ShapeEvent::FieldShapeSet(_) => Some(ChangeKey::Shape(id())),
+      ShapeEvent::ShapeParameterShapeSet(e) => match &e.shape_descriptor {
+        ParameterShapeDescriptor::ProviderInShape(provider) => Some(ChangeKey::ParameterShape(
+          provider.shape_id.clone(),
+          provider.consuming_parameter_id.clone(),
+        )),
+        ParameterShapeDescriptor::ProviderInField(_) => None,
+      },
+      _ => None,
+    },
+    SpecEvent::RfcEvent(event) => match event {
+      RfcEvent::ContributionAdded(e) => Some(ChangeKey::Contribution(e.id.clone(), e.key.clone())),
+      RfcEvent::APINamed(_) => Some(ChangeKey::ApiName),
+      RfcEvent::GitStateSet(_) => Some(ChangeKey::GitState),
+      RfcEvent::BatchCommitStarted(_) | RfcEvent::BatchCommitEnded(_) => None,
+    },
+  }
+}
+
+/// The entity an event renames and its new name
+fn renamed(event: &SpecEvent) -> Option<(&str, &str)> {
+  match event {
+    SpecEvent::EndpointEvent(event) => match event {
+      EndpointEvent::PathComponentRenamed(e) => Some((&e.path_id, &e.name)),
+      EndpointEvent::PathParameterRenamed(e) => Some((&e.path_id, &e.name)),
+      EndpointEvent::RequestParameterRenamed(e) => Some((&e.parameter_id, &e.name)),
+      EndpointEvent::HeaderParameterRenamed(e) => Some((&e.parameter_id, &e.name)),
+      _ => None,
+    },
+    SpecEvent::ShapeEvent(event) => match event {
+      ShapeEvent::ShapeRenamed(e) => Some((&e.shape_id, &e.name)),
+      ShapeEvent::ShapeParameterRenamed(e) => Some((&e.shape_parameter_id, &e.name)),
+      ShapeEvent::FieldRenamed(e) => Some((&e.field_id, &e.name)),
+      _ => None,
+    },
+    SpecEvent::RfcEvent(_) => None,
+  }
+}
+
+fn rename_added(event: &mut SpecEvent, name: &str) {
+  let added_name = match event {
+    SpecEvent::EndpointEvent(event) => match event {
+      EndpointEvent::PathComponentAdded(e) => &mut e.name,
+      EndpointEvent::PathParameterAdded(e) => &mut e.name,
+      EndpointEvent::RequestParameterAddedByPathAndMethod(e) => &mut e.name,
+      EndpointEvent::HeaderParameterAdded(e) => &mut e.name,
+      EndpointEvent::ResponseHeaderParameterAdded(e) => &mut e.name,
+      _ => return,
+    },
+    SpecEvent::ShapeEvent(event) => match event {
+      ShapeEvent::ShapeAdded(e) => &mut e.name,
+      ShapeEvent::ShapeParameterAdded(e) => &mut e.name,
+      ShapeEvent::FieldAdded(e) => &mut e.name,
+      _ => return,
+    },
+    SpecEvent::RfcEvent(_) => return,
+  };
+  *added_name = String::from(name);
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::changelog::Changelog;
+  use crate::events::test_fixtures::todo_batch_commit_events;
+  use crate::projections::SpecProjection;
+  use insta::assert_json_snapshot;
+  use serde_json::json;
+
+  #[test]
+  pub fn can_compact_spec_events() {
+    let events = todo_events();
+
+    let compacted_events = compact_events(&events, &[]).expect("spec events should be compacted");
+    assert_json_snapshot!(compacted_events);
+    assert_same_spec(&events, &compacted_events, "batch_2");
+  }
+
+  #[test]
+  pub fn can_compact_spec_events_keeping_batch_commits() {
+    let events = todo_events();
+
+    let compacted_events =
+      compact_events(&events, &["batch_1"]).expect("spec events should be compacted");
+    let batch_ids: Vec<_> = compacted_events
+      .iter()
+      .filter_map(|event| match event {
+        SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(e)) => {
+          Some((e.batch_id.as_str(), e.parent_id.as_deref()))
+        }
+        _ => None,
+      })
+      .collect();
+    assert_eq!(
+      batch_ids,
+      vec![("batch_1", Some("root")), ("batch_2", Some("batch_1"))]
+    );
+    assert_same_spec(&events, &compacted_events, "batch_1");
+    assert_same_spec(&events, &compacted_events, "batch_2");
+
+    let result = compact_events(&events, &["batch_3"]);
+    assert!(matches!(result, Err(UnknownBatchCommitError(_))));
+  }
+
+  #[test]
+  pub fn can_compact_contributions_to_squashed_batch_commits() {
+    let mut events = todo_events();
+    let batch_1_ended = events
+      .iter()
+      .position(|event| matches!(event, SpecEvent::RfcEvent(RfcEvent::BatchCommitEnded(_))))
+      .unwrap();
+    events.insert(
+      batch_1_ended,
+      serde_json::from_value(
+        json!({"ContributionAdded": {"id": "batch_1", "key": "reviewer", "value": "someone"}}),
+      )
+      .unwrap(),
+    );
+
+    let batch_contributions = |compacted_events: &[SpecEvent]| -> Vec<(String, String)> {
+      compacted_events
+        .iter()
+        .filter_map(|event| match event {
+          SpecEvent::RfcEvent(RfcEvent::ContributionAdded(e)) if e.id.starts_with("batch_") => {
+            Some((e.id.clone(), e.key.clone()))
+          }
+          _ => None,
+        })
+        .collect()
+    };
+
+    let compacted_events =
+      compact_events(&events, &["batch_2"]).expect("spec events should be compacted");
+    assert_eq!(
+      batch_contributions(&compacted_events),
+      vec![
+        (String::from("batch_2"), String::from("reviewer")),
+        (
+          String::from("batch_2"),
+          String::from("breakingChangeJustification")
+        )
+      ]
+    );
+
+    let compacted_events = compact_events(&events, &[]).expect("spec events should be compacted");
+    assert_eq!(
+      batch_contributions(&compacted_events),
+      vec![
+        (String::from("batch_2"), String::from("reviewer")),
+        (
+          String::from("batch_2"),
+          String::from("breakingChangeJustification")
+        )
+      ]
+    );
+  }
+
+  fn assert_same_spec(events: &[SpecEvent], compacted_events: &[SpecEvent], batch_id: &str) {
+    let spec = SpecProjection::at_batch_commit(events, batch_id).unwrap();
+    // squashed batch commits are gone, leaving the whole compacted spec to compare with
+    let compacted_spec = match SpecProjection::at_batch_commit(compacted_events, batch_id) {
+      Ok(compacted_spec) => compacted_spec,
+      Err(_) => SpecProjection::from(compacted_events.to_vec()),
+    };
+    let changelog = Changelog::between_specs(&spec, &compacted_spec, batch_id, "compacted");
+    assert!(
+      changelog.endpoints.is_empty(),
+      "compacted spec should equal the spec at {}, but {:?}",
+      batch_id,
+      changelog.endpoints
+    );
+  }
+
+  /// The todo spec, with events in both of its batch commits that are undone again
+  fn todo_events() -> Vec<SpecEvent> {
+    let batch_1_churn: Vec<SpecEvent> = serde_json::from_value(json!([
+      {"PathComponentRenamed": {"pathId": "path_1","name": "tasks"}},
+      {"PathComponentRenamed": {"pathId": "path_1","name": "todos"}},
+      {"ShapeAdded": {"shapeId": "list_shape_1","baseShapeId": "$list","parameters": {"DynamicParameterList": {"shapeParameterIds": []}},"name": ""}},
+      {"ShapeParameterShapeSet": {"shapeDescriptor": {"ProviderInShape": {"shapeId": "list_shape_1","providerDescriptor": {"ShapeProvider": {"shapeId": "string_shape_1"}},"consumingParameterId": "$listItem"}}}},
+      {"FieldAdded": {"fieldId": "field_4","shapeId": "todo_shape","name": "tags","shapeDescriptor": {"FieldShapeFromShape": {"fieldId": "field_4","shapeId": "list_shape_1"}}}},
+      {"FieldRenamed": {"fieldId": "field_4","name": "labels"}},
+      {"FieldRemoved": {"fieldId": "field_4"}},
+      {"ResponseBodySet": {"responseId": "response_1","bodyDescriptor": {"httpContentType": "application/json","shapeId": "todo_shape","isRemoved": false}}},
+    ]))
+    .expect("should be able to deserialize test events");
+    let batch_2_churn: Vec<SpecEvent> = serde_json::from_value(json!([
+      {"ShapeRenamed": {"shapeId": "todo_shape","name": "Todo"}},
+      {"FieldRenamed": {"fieldId": "field_2","name": "rank"}},
+      {"FieldRenamed": {"fieldId": "field_2","name": "priority"}},
+      {"RequestAdded": {"requestId": "request_3","pathId": "path_2","httpMethod": "PUT"}},
+      {"RequestRemoved": {"requestId": "request_3"}},
+      {"ContributionAdded": {"id": "path_1.GET", "key": "purpose", "value": "Lists todos"}},
+      {"ContributionAdded": {"id": "path_1.GET", "key": "purpose", "value": "Lists all todos"}},
+      {"ContributionAdded": {"id": "batch_2", "key": "breakingChangeJustification", "value": "Nobody deletes todos"}},
+    ]))
+    .expect("should be able to deserialize test events");
+
+    let mut churn = vec![batch_1_churn, batch_2_churn].into_iter();
+    let mut events = vec![];
+    for event in todo_batch_commit_events() {
+      if let SpecEvent::RfcEvent(RfcEvent::BatchCommitEnded(_)) = event {
+        events.extend(churn.next().unwrap_or_default());
+      }
+      events.push(event);
+    }
+    events
+  }
+}
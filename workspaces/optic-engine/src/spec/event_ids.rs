@@ -0,0 +1,165 @@
+use crate::events::{EndpointEvent, ShapeEvent, SpecEvent};
+use crate::state::shape::{FieldShapeDescriptor, ParameterShapeDescriptor, ProviderDescriptor};
+
+/// The id of the entity an event adds, if it adds one
+pub(super) fn added_id(event: &SpecEvent) -> Option<&str> {
+  match event {
+    SpecEvent::EndpointEvent(event) => match event {
+      EndpointEvent::PathComponentAdded(e) => Some(&e.path_id),
+      EndpointEvent::PathParameterAdded(e) => Some(&e.path_id),
+      EndpointEvent::QueryParametersAdded(e) => Some(&e.query_parameters_id),
+      EndpointEvent::RequestParameterAddedByPathAndMethod(e) => Some(&e.parameter_id),
+      EndpointEvent::HeaderParameterAdded(e) => Some(&e.parameter_id),
+      EndpointEvent::ResponseHeaderParameterAdded(e) => Some(&e.parameter_id),
+      EndpointEvent::RequestAdded(e) => Some(&e.request_id),
+      EndpointEvent::ResponseAddedByPathAndMethod(e) => Some(&e.response_id),
+      _ => None,
+    },
+    SpecEvent::ShapeEvent(event) => match event {
+      ShapeEvent::ShapeAdded(e) => Some(&e.shape_id),
+      ShapeEvent::ShapeParameterAdded(e) => Some(&e.shape_parameter_id),
+      ShapeEvent::FieldAdded(e) => Some(&e.field_id),
+      _ => None,
+    },
+    SpecEvent::RfcEvent(_) => None,
+  }
+}
+
+/// The id of the entity an event removes, if it removes one
+pub(super) fn removed_id(event: &SpecEvent) -> Option<&str> {
+  match event {
+    SpecEvent::EndpointEvent(event) => match event {
+      EndpointEvent::PathComponentRemoved(e) => Some(&e.path_id),
+      EndpointEvent::PathParameterRemoved(e) => Some(&e.path_id),
+      EndpointEvent::QueryParametersRemoved(e) => Some(&e.query_parameters_id),
+      EndpointEvent::RequestParameterRemoved(e) => Some(&e.parameter_id),
+      EndpointEvent::HeaderParameterRemoved(e) => Some(&e.parameter_id),
+      EndpointEvent::RequestRemoved(e) => Some(&e.request_id),
+      EndpointEvent::ResponseRemoved(e) => Some(&e.response_id),
+      _ => None,
+    },
+    SpecEvent::ShapeEvent(event) => match event {
+      ShapeEvent::ShapeRemoved(e) => Some(&e.shape_id),
+      ShapeEvent::ShapeParameterRemoved(e) => Some(&e.shape_parameter_id),
+      ShapeEvent::FieldRemoved(e) => Some(&e.field_id),
+      _ => None,
+    },
+    SpecEvent::RfcEvent(_) => None,
+  }
+}
+
+/// The id of the entity an event adds, changes or removes
+pub(super) fn changed_id(event: &SpecEvent) -> Option<&str> {
+  match event {
+    SpecEvent::EndpointEvent(event) => Some(match event {
+      EndpointEvent::PathComponentAdded(e) => &e.path_id,
+      EndpointEvent::PathComponentRenamed(e) => &e.path_id,
+      EndpointEvent::PathComponentRemoved(e) => &e.path_id,
+      EndpointEvent::PathParameterAdded(e) => &e.path_id,
+      EndpointEvent::PathParameterShapeSet(e) => &e.path_id,
+      EndpointEvent::PathParameterRenamed(e) => &e.path_id,
+      EndpointEvent::PathParameterRemoved(e) => &e.path_id,
+      EndpointEvent::QueryParametersAdded(e) => &e.query_parameters_id,
+      EndpointEvent::QueryParametersShapeSet(e) => &e.query_parameters_id,
+      EndpointEvent::QueryParametersRemoved(e) => &e.query_parameters_id,
+      EndpointEvent::RequestParameterAddedByPathAndMethod(e) => &e.parameter_id,
+      EndpointEvent::RequestParameterRenamed(e) => &e.parameter_id,
+      EndpointEvent::RequestParameterShapeSet(e) => &e.parameter_id,
+      EndpointEvent::RequestParameterShapeUnset(e) => &e.parameter_id,
+      EndpointEvent::RequestParameterRemoved(e) => &e.parameter_id,
+      EndpointEvent::HeaderParameterAdded(e) => &e.parameter_id,
+      EndpointEvent::ResponseHeaderParameterAdded(e) => &e.parameter_id,
+      EndpointEvent::HeaderParameterShapeSet(e) => &e.parameter_id,
+      EndpointEvent::HeaderParameterRenamed(e) => &e.parameter_id,
+      EndpointEvent::HeaderParameterShapeUnset(e) => &e.parameter_id,
+      EndpointEvent::HeaderParameterRemoved(e) => &e.parameter_id,
+      EndpointEvent::RequestAdded(e) => &e.request_id,
+      EndpointEvent::RequestContentTypeSet(e) => &e.request_id,
+      EndpointEvent::RequestBodySet(e) => &e.request_id,
+      EndpointEvent::RequestBodyUnset(e) => &e.request_id,
+      EndpointEvent::RequestRemoved(e) => &e.request_id,
+      EndpointEvent::ResponseAddedByPathAndMethod(e) => &e.response_id,
+      EndpointEvent::ResponseStatusCodeSet(e) => &e.response_id,
+      EndpointEvent::ResponseContentTypeSet(e) => &e.response_id,
+      EndpointEvent::ResponseBodySet(e) => &e.response_id,
+      EndpointEvent::ResponseBodyUnset(e) => &e.response_id,
+      EndpointEvent::ResponseRemoved(e) => &e.response_id,
+    }),
+    SpecEvent::ShapeEvent(event) => Some(match event {
+      ShapeEvent::ShapeAdded(e) => &e.shape_id,
+      ShapeEvent::BaseShapeSet(e) => &e.shape_id,
+      ShapeEvent::ShapeRenamed(e) => &e.shape_id,
+      ShapeEvent::ShapeRemoved(e) => &e.shape_id,
+      ShapeEvent::ShapeParameterAdded(e) => &e.shape_parameter_id,
+      ShapeEvent::ShapeParameterShapeSet(e) => match &e.shape_descriptor {
+        ParameterShapeDescriptor::ProviderInShape(provider) => &provider.shape_id,
+        ParameterShapeDescriptor::ProviderInField(_) => return None,
+      },
+      ShapeEvent::ShapeParameterRenamed(e) => &e.shape_parameter_id,
+      ShapeEvent::ShapeParameterRemoved(e) => &e.shape_parameter_id,
+      ShapeEvent::FieldAdded(e) => &e.field_id,
+      ShapeEvent::FieldShapeSet(e) => field_shape_descriptor_field_id(&e.shape_descriptor),
+      ShapeEvent::FieldRenamed(e) => &e.field_id,
+      ShapeEvent::FieldRemoved(e) => &e.field_id,
+    }),
+    SpecEvent::RfcEvent(_) => None,
+  }
+}
+
+/// The ids of every entity an event changes or refers to
+pub(super) fn referenced_ids(event: &SpecEvent) -> Vec<&str> {
+  let mut ids: Vec<&str> = changed_id(event).into_iter().collect();
+  match event {
+    SpecEvent::EndpointEvent(event) => match event {
+      EndpointEvent::PathComponentAdded(e) => ids.push(&e.parent_path_id),
+      EndpointEvent::PathParameterAdded(e) => ids.push(&e.parent_path_id),
+      EndpointEvent::PathParameterShapeSet(e) => ids.push(&e.shape_descriptor.shape_id),
+      EndpointEvent::QueryParametersAdded(e) => ids.push(&e.path_id),
+      EndpointEvent::QueryParametersShapeSet(e) => ids.push(&e.shape_descriptor.shape_id),
+      EndpointEvent::RequestParameterAddedByPathAndMethod(e) => ids.push(&e.path_id),
+      EndpointEvent::RequestParameterShapeSet(e) => ids.push(&e.parameter_descriptor.shape_id),
+      EndpointEvent::HeaderParameterAdded(e) => ids.push(&e.request_id),
+      EndpointEvent::ResponseHeaderParameterAdded(e) => ids.push(&e.response_id),
+      EndpointEvent::HeaderParameterShapeSet(e) => ids.push(&e.parameter_descriptor.shape_id),
+      EndpointEvent::RequestAdded(e) => ids.push(&e.path_id),
+      EndpointEvent::RequestBodySet(e) => ids.push(&e.body_descriptor.shape_id),
+      EndpointEvent::ResponseAddedByPathAndMethod(e) => ids.push(&e.path_id),
+      EndpointEvent::ResponseBodySet(e) => ids.push(&e.body_descriptor.shape_id),
+      _ => {}
+    },
+    SpecEvent::ShapeEvent(event) => match event {
+      ShapeEvent::ShapeAdded(e) => ids.push(&e.base_shape_id),
+      ShapeEvent::BaseShapeSet(e) => ids.push(&e.base_shape_id),
+      ShapeEvent::ShapeParameterAdded(e) => ids.push(&e.shape_id),
+      ShapeEvent::ShapeParameterShapeSet(e) => {
+        if let ParameterShapeDescriptor::ProviderInShape(provider) = &e.shape_descriptor {
+          ids.push(&provider.consuming_parameter_id);
+          if let ProviderDescriptor::ShapeProvider(shape_provider) = &provider.provider_descriptor {
+            ids.push(&shape_provider.shape_id);
+          }
+        }
+      }
+      ShapeEvent::FieldAdded(e) => {
+        ids.push(&e.shape_id);
+        if let FieldShapeDescriptor::FieldShapeFromShape(descriptor) = &e.shape_descriptor {
+          ids.push(&descriptor.shape_id);
+        }
+      }
+      ShapeEvent::FieldShapeSet(e) => {
+        if let FieldShapeDescriptor::FieldShapeFromShape(descriptor) = &e.shape_descriptor {
+          ids.push(&descriptor.shape_id);
+        }
+      }
+      _ => {}
+    },
+    SpecEvent::RfcEvent(_) => {}
+  }
+  ids
+}
+
+pub(super) fn field_shape_descriptor_field_id(shape_descriptor: &FieldShapeDescriptor) -> &str {
+  match shape_descriptor {
+    FieldShapeDescriptor::FieldShapeFromShape(descriptor) => &descriptor.field_id,
+    FieldShapeDescriptor::FieldShapeFromParameter(descriptor) => &descriptor.field_id,
+  }
+}
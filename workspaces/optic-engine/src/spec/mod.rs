@@ -7,6 +7,13 @@ use crate::projections::SpecProjection;
 use crate::queries::EndpointQueries;
 use cqrs_core::Aggregate;
 
+mod compact;
+mod event_ids;
+mod revert;
+
+pub use compact::compact_events;
+pub use revert::{revert_batch_commands, RevertBatchError};
+
 /// Create an interface that allows commands to be applied to a spec as part of a single
 /// batch. Will produce events enclosed in `BatchCommitStarted` and `BatchCommitEnded`.
 pub fn append_batch(
@@ -70,6 +77,11 @@ impl AppendedBatch {
     Ok(())
   }
 
+  /// The spec as it is with the commands applied so far
+  pub fn spec_projection(&self) -> &SpecProjection {
+    self.command_handler.spec_projection()
+  }
+
   pub fn commit(self) -> Vec<SpecEvent> {
     let mut new_events = self.new_events;
     let end_event = self
@@ -0,0 +1,817 @@
+use super::event_ids::{
+  added_id, changed_id, field_shape_descriptor_field_id, referenced_ids, removed_id,
+};
+use crate::commands::endpoint::{
+  AddHeaderParameter, AddPathComponent, AddPathParameter, AddQueryParameters, AddRequest,
+  AddResponseByPathAndMethod, AddResponseHeaderParameter, RemoveHeaderParameter,
+  RemovePathComponent, RemovePathParameter, RemoveQueryParameters, RemoveRequest, RemoveResponse,
+  RenameHeaderParameter, RenamePathComponent, RenamePathParameter, SetHeaderParameterShape,
+  SetPathParameterShape, SetQueryParametersShape, SetRequestBodyShape, SetResponseBodyShape,
+  UnsetHeaderParameterShape,
+};
+use crate::commands::shape::{
+  AddField, RemoveField, SetBaseShape, SetFieldShape, SetParameterShape,
+};
+use crate::commands::{EndpointCommand, ShapeCommand, SpecCommand};
+use crate::events::{EndpointEvent, RfcEvent, ShapeEvent, SpecEvent, UnknownBatchCommitError};
+use crate::state::endpoint::{
+  QueryParametersShapeDescriptor, ShapedBodyDescriptor, ShapedRequestParameterShapeDescriptor,
+};
+use crate::state::shape::{
+  FieldShapeDescriptor, FieldShapeFromShape, ParameterShapeDescriptor, ProviderInShape,
+};
+use crate::state::SpecIdGenerator;
+use cqrs_core::Event;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RevertBatchError {
+  #[error(transparent)]
+  UnknownBatch(#[from] UnknownBatchCommitError),
+  #[error(
+    "batch commit '{}' can't be reverted, as later batch commit '{}' depends on '{}', which it changed",
+    .batch_id, .dependent_batch_id, .entity_id
+  )]
+  DependentBatch {
+    batch_id: String,
+    dependent_batch_id: String,
+    entity_id: String,
+  },
+  #[error(
+    "batch commit '{}' can't be reverted, as there is no command to undo its {} event",
+    .batch_id, .event_type
+  )]
+  UnsupportedEvent {
+    batch_id: String,
+    event_type: &'static str,
+  },
+}
+
+/// Compute the commands that undo the changes of a batch commit, to be appended as a new batch.
+/// Anything the batch added is removed again and anything it removed is added again with a new
+/// id, as removed ids can't be reused. Shapes the batch added are left in place, unused.
+///
+/// Refuses when events of later batch commits refer to anything the batch changed, or when the
+/// batch contains events that can't be undone with commands (like renaming fields).
+pub fn revert_batch_commands(
+  spec_events: &[SpecEvent],
+  batch_id: &str,
+  id_generator: &mut impl SpecIdGenerator,
+) -> Result<Vec<SpecCommand>, RevertBatchError> {
+  let start_index = spec_events
+    .iter()
+    .position(|event| match event {
+      SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(e)) => e.batch_id == batch_id,
+      _ => false,
+    })
+    .ok_or_else(|| UnknownBatchCommitError(String::from(batch_id)))?;
+  let end_index = SpecEvent::until_batch_commit(spec_events, batch_id)?.len() - 1;
+  let batch_events = &spec_events[start_index + 1..end_index];
+
+  let changed_ids: HashSet<&str> = batch_events.iter().filter_map(changed_id).collect();
+  let mut later_batch_id = batch_id;
+  for event in &spec_events[end_index + 1..] {
+    if let SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(e)) = event {
+      later_batch_id = &e.batch_id;
+    }
+    if let Some(entity_id) = referenced_ids(event)
+      .into_iter()
+      .find(|id| changed_ids.contains(id))
+    {
+      return Err(RevertBatchError::DependentBatch {
+        batch_id: String::from(batch_id),
+        dependent_batch_id: String::from(later_batch_id),
+        entity_id: String::from(entity_id),
+      });
+    }
+  }
+
+  let mut revert = Revert {
+    added_ids: batch_events.iter().filter_map(added_id).collect(),
+    removed_ids: batch_events.iter().filter_map(removed_id).collect(),
+    new_ids: HashMap::new(),
+    id_generator,
+  };
+  let mut commands = vec![];
+  for (index, event) in batch_events.iter().enumerate().rev() {
+    // the state right before the event, so events undone in reverse end up where the batch started
+    let prior_events = &spec_events[..start_index + 1 + index];
+    let event_commands = revert.event_commands(event, prior_events).ok_or_else(|| {
+      RevertBatchError::UnsupportedEvent {
+        batch_id: String::from(batch_id),
+        event_type: event.event_type(),
+      }
+    })?;
+    commands.extend(event_commands);
+  }
+
+  Ok(commands)
+}
+
+struct Revert<'a, G> {
+  added_ids: HashSet<&'a str>,
+  removed_ids: HashSet<&'a str>,
+  new_ids: HashMap<String, String>,
+  id_generator: &'a mut G,
+}
+
+impl<'a, G: SpecIdGenerator> Revert<'a, G> {
+  /// The commands undoing an event, or `None` when it can't be undone
+  fn event_commands(
+    &mut self,
+    event: &SpecEvent,
+    prior_events: &[SpecEvent],
+  ) -> Option<Vec<SpecCommand>> {
+    let subject_id = changed_id(event);
+    let is_added = added_id(event).is_some();
+    if let Some(subject_id) = subject_id {
+      // removing what the batch added takes everything that happened to it along, while something
+      // both added and removed by the batch was never there to begin with
+      if (!is_added && self.added_ids.contains(subject_id))
+        || (is_added && self.removed_ids.contains(subject_id))
+      {
+        return Some(vec![]);
+      }
+    }
+
+    match event {
+      SpecEvent::EndpointEvent(event) => self.endpoint_event_commands(event, prior_events),
+      SpecEvent::ShapeEvent(event) => self.shape_event_commands(event, prior_events),
+      SpecEvent::RfcEvent(_) => Some(vec![]),
+    }
+  }
+
+  fn endpoint_event_commands(
+    &mut self,
+    event: &EndpointEvent,
+    prior_events: &[SpecEvent],
+  ) -> Option<Vec<SpecCommand>> {
+    let command = match event {
+      EndpointEvent::PathComponentAdded(e) => {
+        EndpointCommand::RemovePathComponent(RemovePathComponent {
+          path_id: e.path_id.clone(),
+        })
+      }
+      EndpointEvent::PathParameterAdded(e) => {
+        EndpointCommand::RemovePathParameter(RemovePathParameter {
+          path_id: e.path_id.clone(),
+        })
+      }
+      EndpointEvent::PathComponentRenamed(e) => {
+        EndpointCommand::RenamePathComponent(RenamePathComponent {
+          path_id: e.path_id.clone(),
+          name: prior_path(prior_events, &e.path_id)?.name,
+        })
+      }
+      EndpointEvent::PathParameterRenamed(e) => {
+        EndpointCommand::RenamePathParameter(RenamePathParameter {
+          path_id: e.path_id.clone(),
+          name: prior_path(prior_events, &e.path_id)?.name,
+        })
+      }
+      EndpointEvent::PathComponentRemoved(_) | EndpointEvent::PathParameterRemoved(_) => {
+        let path_id = match event {
+          EndpointEvent::PathComponentRemoved(e) => &e.path_id,
+          EndpointEvent::PathParameterRemoved(e) => &e.path_id,
+          _ => unreachable!("only removed paths are added again"),
+        };
+        let path = prior_path(prior_events, path_id)?;
+        let new_path_id = self.new_id(path_id, |ids| ids.path());
+        let parent_path_id = self.current_id(&path.parent_path_id);
+        if !path.is_parameter {
+          return Some(vec![SpecCommand::from(EndpointCommand::AddPathComponent(
+            AddPathComponent {
+              path_id: new_path_id,
+              parent_path_id,
+              name: path.name,
+            },
+          ))]);
+        }
+
+        let mut commands = vec![SpecCommand::from(EndpointCommand::AddPathParameter(
+          AddPathParameter {
+            path_id: new_path_id.clone(),
+            parent_path_id,
+            name: path.name,
+          },
+        ))];
+        if let Some(shape_descriptor) = path.shape_descriptor {
+          commands.push(SpecCommand::from(EndpointCommand::SetPathParameterShape(
+            SetPathParameterShape {
+              path_id: new_path_id,
+              shaped_request_parameter_shape_descriptor: shape_descriptor,
+            },
+          )));
+        }
+        return Some(commands);
+      }
+      EndpointEvent::PathParameterShapeSet(e) => {
+        EndpointCommand::SetPathParameterShape(SetPathParameterShape {
+          path_id: e.path_id.clone(),
+          shaped_request_parameter_shape_descriptor: prior_path(prior_events, &e.path_id)?
+            .shape_descriptor?,
+        })
+      }
+
+      EndpointEvent::QueryParametersAdded(e) => {
+        EndpointCommand::RemoveQueryParameters(RemoveQueryParameters {
+          query_parameters_id: e.query_parameters_id.clone(),
+        })
+      }
+      EndpointEvent::QueryParametersShapeSet(e) => {
+        EndpointCommand::SetQueryParametersShape(SetQueryParametersShape {
+          query_parameters_id: e.query_parameters_id.clone(),
+          shape_descriptor: prior_query_parameters(prior_events, &e.query_parameters_id)?
+            .shape_descriptor?,
+        })
+      }
+      EndpointEvent::QueryParametersRemoved(e) => {
+        let query_parameters = prior_query_parameters(prior_events, &e.query_parameters_id)?;
+        let new_query_parameters_id = self.new_id(&e.query_parameters_id, |ids| ids.query_params());
+        let mut commands = vec![SpecCommand::from(EndpointCommand::AddQueryParameters(
+          AddQueryParameters {
+            query_parameters_id: new_query_parameters_id.clone(),
+            path_id: self.current_id(&query_parameters.path_id),
+            http_method: query_parameters.http_method,
+          },
+        ))];
+        if let Some(shape_descriptor) = query_parameters.shape_descriptor {
+          commands.push(SpecCommand::from(EndpointCommand::SetQueryParametersShape(
+            SetQueryParametersShape {
+              query_parameters_id: new_query_parameters_id,
+              shape_descriptor,
+            },
+          )));
+        }
+        return Some(commands);
+      }
+
+      EndpointEvent::HeaderParameterAdded(e) => {
+        EndpointCommand::RemoveHeaderParameter(RemoveHeaderParameter {
+          parameter_id: e.parameter_id.clone(),
+        })
+      }
+      EndpointEvent::ResponseHeaderParameterAdded(e) => {
+        EndpointCommand::RemoveHeaderParameter(RemoveHeaderParameter {
+          parameter_id: e.parameter_id.clone(),
+        })
+      }
+      EndpointEvent::HeaderParameterRenamed(e) => {
+        EndpointCommand::RenameHeaderParameter(RenameHeaderParameter {
+          parameter_id: e.parameter_id.clone(),
+          name: prior_header_parameter(prior_events, &e.parameter_id)?.name,
+        })
+      }
+      EndpointEvent::HeaderParameterShapeSet(e) => {
+        match prior_header_parameter(prior_events, &e.parameter_id)?.shape_descriptor {
+          Some(parameter_descriptor) => {
+            EndpointCommand::SetHeaderParameterShape(SetHeaderParameterShape {
+              parameter_id: e.parameter_id.clone(),
+              parameter_descriptor,
+            })
+          }
+          None => EndpointCommand::UnsetHeaderParameterShape(UnsetHeaderParameterShape {
+            parameter_id: e.parameter_id.clone(),
+          }),
+        }
+      }
+      EndpointEvent::HeaderParameterShapeUnset(e) => {
+        EndpointCommand::SetHeaderParameterShape(SetHeaderParameterShape {
+          parameter_id: e.parameter_id.clone(),
+          parameter_descriptor: prior_header_parameter(prior_events, &e.parameter_id)?
+            .shape_descriptor?,
+        })
+      }
+      EndpointEvent::HeaderParameterRemoved(e) => {
+        let header_parameter = prior_header_parameter(prior_events, &e.parameter_id)?;
+        let new_parameter_id = self.new_id(&e.parameter_id, |ids| ids.request_parameter());
+        let mut commands = vec![SpecCommand::from(match header_parameter.owner {
+          HeaderParameterOwner::Request(request_id) => {
+            EndpointCommand::AddHeaderParameter(AddHeaderParameter {
+              parameter_id: new_parameter_id.clone(),
+              request_id: self.current_id(&request_id),
+              name: header_parameter.name,
+            })
+          }
+          HeaderParameterOwner::Response(response_id) => {
+            EndpointCommand::AddResponseHeaderParameter(AddResponseHeaderParameter {
+              parameter_id: new_parameter_id.clone(),
+              response_id: self.current_id(&response_id),
+              name: header_parameter.name,
+            })
+          }
+        })];
+        if let Some(parameter_descriptor) = header_parameter.shape_descriptor {
+          commands.push(SpecCommand::from(EndpointCommand::SetHeaderParameterShape(
+            SetHeaderParameterShape {
+              parameter_id: new_parameter_id,
+              parameter_descriptor,
+            },
+          )));
+        }
+        return Some(commands);
+      }
+
+      EndpointEvent::RequestAdded(e) => EndpointCommand::RemoveRequest(RemoveRequest {
+        request_id: e.request_id.clone(),
+      }),
+      EndpointEvent::RequestBodySet(e) => {
+        EndpointCommand::SetRequestBodyShape(SetRequestBodyShape {
+          request_id: e.request_id.clone(),
+          body_descriptor: prior_body(prior_events, &e.request_id)?.body_descriptor?,
+        })
+      }
+      EndpointEvent::RequestRemoved(e) => {
+        let request = prior_body(prior_events, &e.request_id)?;
+        let new_request_id = self.new_id(&e.request_id, |ids| ids.request());
+        let mut commands = vec![SpecCommand::from(EndpointCommand::AddRequest(AddRequest {
+          request_id: new_request_id.clone(),
+          path_id: self.current_id(&request.path_id),
+          http_method: request.http_method,
+        }))];
+        if let Some(body_descriptor) = request.body_descriptor {
+          commands.push(SpecCommand::from(EndpointCommand::SetRequestBodyShape(
+            SetRequestBodyShape {
+              request_id: new_request_id,
+              body_descriptor,
+            },
+          )));
+        }
+        return Some(commands);
+      }
+
+      EndpointEvent::ResponseAddedByPathAndMethod(e) => {
+        EndpointCommand::RemoveResponse(RemoveResponse {
+          response_id: e.response_id.clone(),
+        })
+      }
+      EndpointEvent::ResponseBodySet(e) => {
+        EndpointCommand::SetResponseBodyShape(SetResponseBodyShape {
+          response_id: e.response_id.clone(),
+          body_descriptor: prior_body(prior_events, &e.response_id)?.body_descriptor?,
+        })
+      }
+      EndpointEvent::ResponseRemoved(e) => {
+        let response = prior_body(prior_events, &e.response_id)?;
+        let new_response_id = self.new_id(&e.response_id, |ids| ids.response());
+        let mut commands = vec![SpecCommand::from(
+          EndpointCommand::AddResponseByPathAndMethod(AddResponseByPathAndMethod {
+            response_id: new_response_id.clone(),
+            path_id: self.current_id(&response.path_id),
+            http_method: response.http_method,
+            http_status_code: response.http_status_code?,
+          }),
+        )];
+        if let Some(body_descriptor) = response.body_descriptor {
+          commands.push(SpecCommand::from(EndpointCommand::SetResponseBodyShape(
+            SetResponseBodyShape {
+              response_id: new_response_id,
+              body_descriptor,
+            },
+          )));
+        }
+        return Some(commands);
+      }
+
+      // no commands exist to set these back
+      EndpointEvent::RequestParameterAddedByPathAndMethod(_)
+      | EndpointEvent::RequestParameterRenamed(_)
+      | EndpointEvent::RequestParameterShapeSet(_)
+      | EndpointEvent::RequestParameterShapeUnset(_)
+      | EndpointEvent::RequestParameterRemoved(_)
+      | EndpointEvent::RequestContentTypeSet(_)
+      | EndpointEvent::RequestBodyUnset(_)
+      | EndpointEvent::ResponseStatusCodeSet(_)
+      | EndpointEvent::ResponseContentTypeSet(_)
+      | EndpointEvent::ResponseBodyUnset(_) => return None,
+    };
+
+    Some(vec![SpecCommand::from(command)])
+  }
+
+  fn shape_event_commands(
+    &mut self,
+    event: &ShapeEvent,
+    prior_events: &[SpecEvent],
+  ) -> Option<Vec<SpecCommand>> {
+    let command = match event {
+      ShapeEvent::ShapeAdded(_) => return Some(vec![]),
+      ShapeEvent::ShapeParameterAdded(e) if self.added_ids.contains(e.shape_id.as_str()) => {
+        return Some(vec![])
+      }
+      ShapeEvent::BaseShapeSet(e) => ShapeCommand::SetBaseShape(SetBaseShape {
+        shape_id: e.shape_id.clone(),
+        base_shape_id: prior_base_shape_id(prior_events, &e.shape_id)?,
+      }),
+      ShapeEvent::ShapeParameterShapeSet(e) => match &e.shape_descriptor {
+        ParameterShapeDescriptor::ProviderInShape(provider) => {
+          if self.added_ids.contains(provider.shape_id.as_str()) {
+            return Some(vec![]);
+          }
+          ShapeCommand::SetParameterShape(SetParameterShape {
+            shape_descriptor: ParameterShapeDescriptor::ProviderInShape(prior_parameter_shape(
+              prior_events,
+              provider,
+            )?),
+          })
+        }
+        ParameterShapeDescriptor::ProviderInField(_) => return None,
+      },
+      ShapeEvent::FieldAdded(e) => {
+        if self.added_ids.contains(e.shape_id.as_str()) {
+          return Some(vec![]);
+        }
+        ShapeCommand::RemoveField(RemoveField {
+          field_id: e.field_id.clone(),
+        })
+      }
+      ShapeEvent::FieldShapeSet(e) => ShapeCommand::SetFieldShape(SetFieldShape {
+        shape_descriptor: FieldShapeDescriptor::FieldShapeFromShape(
+          prior_field(
+            prior_events,
+            field_shape_descriptor_field_id(&e.shape_descriptor),
+          )?
+          .shape_descriptor?,
+        ),
+      }),
+      ShapeEvent::FieldRemoved(e) => {
+        let field = prior_field(prior_events, &e.field_id)?;
+        let new_field_id = self.new_id(&e.field_id, |ids| ids.field());
+        ShapeCommand::AddField(AddField {
+          field_id: new_field_id.clone(),
+          shape_id: field.shape_id,
+          name: field.name,
+          shape_descriptor: FieldShapeDescriptor::FieldShapeFromShape(FieldShapeFromShape {
+            field_id: new_field_id,
+            shape_id: field.shape_descriptor?.shape_id,
+          }),
+        })
+      }
+
+      // no commands exist to set these back
+      ShapeEvent::ShapeParameterAdded(_)
+      | ShapeEvent::ShapeRenamed(_)
+      | ShapeEvent::ShapeRemoved(_)
+      | ShapeEvent::ShapeParameterRenamed(_)
+      | ShapeEvent::ShapeParameterRemoved(_)
+      | ShapeEvent::FieldRenamed(_) => return None,
+    };
+
+    Some(vec![SpecCommand::from(command)])
+  }
+
+  fn new_id(&mut self, id: &str, generate: impl FnOnce(&mut G) -> String) -> String {
+    let new_id = generate(self.id_generator);
+    self.new_ids.insert(String::from(id), new_id.clone());
+    new_id
+  }
+
+  // ids of things added again by earlier commands of the revert have to be referred to by their
+  // new id
+  fn current_id(&self, id: &str) -> String {
+    self
+      .new_ids
+      .get(id)
+      .cloned()
+      .unwrap_or_else(|| String::from(id))
+  }
+}
+
+// Prior state
+// -----------
+
+struct PriorPath {
+  parent_path_id: String,
+  name: String,
+  is_parameter: bool,
+  shape_descriptor: Option<ShapedRequestParameterShapeDescriptor>,
+}
+
+fn prior_path(prior_events: &[SpecEvent], path_id: &str) -> Option<PriorPath> {
+  let mut path = None;
+  for event in prior_events {
+    match event {
+      SpecEvent::EndpointEvent(EndpointEvent::PathComponentAdded(e)) if e.path_id == path_id => {
+        path = Some(PriorPath {
+          parent_path_id: e.parent_path_id.clone(),
+          name: e.name.clone(),
+          is_parameter: false,
+          shape_descriptor: None,
+        });
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::PathParameterAdded(e)) if e.path_id == path_id => {
+        path = Some(PriorPath {
+          parent_path_id: e.parent_path_id.clone(),
+          name: e.name.clone(),
+          is_parameter: true,
+          shape_descriptor: None,
+        });
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::PathComponentRenamed(e)) if e.path_id == path_id => {
+        path.as_mut()?.name = e.name.clone();
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::PathParameterRenamed(e)) if e.path_id == path_id => {
+        path.as_mut()?.name = e.name.clone();
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::PathParameterShapeSet(e)) if e.path_id == path_id => {
+        path.as_mut()?.shape_descriptor = Some(e.shape_descriptor.clone());
+      }
+      _ => {}
+    }
+  }
+  path
+}
+
+struct PriorQueryParameters {
+  path_id: String,
+  http_method: String,
+  shape_descriptor: Option<QueryParametersShapeDescriptor>,
+}
+
+fn prior_query_parameters(
+  prior_events: &[SpecEvent],
+  query_parameters_id: &str,
+) -> Option<PriorQueryParameters> {
+  let mut query_parameters = None;
+  for event in prior_events {
+    match event {
+      SpecEvent::EndpointEvent(EndpointEvent::QueryParametersAdded(e))
+        if e.query_parameters_id == query_parameters_id =>
+      {
+        query_parameters = Some(PriorQueryParameters {
+          path_id: e.path_id.clone(),
+          http_method: e.http_method.clone(),
+          shape_descriptor: None,
+        });
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::QueryParametersShapeSet(e))
+        if e.query_parameters_id == query_parameters_id =>
+      {
+        query_parameters.as_mut()?.shape_descriptor = Some(e.shape_descriptor.clone());
+      }
+      _ => {}
+    }
+  }
+  query_parameters
+}
+
+enum HeaderParameterOwner {
+  Request(String),
+  Response(String),
+}
+
+struct PriorHeaderParameter {
+  owner: HeaderParameterOwner,
+  name: String,
+  shape_descriptor: Option<ShapedRequestParameterShapeDescriptor>,
+}
+
+fn prior_header_parameter(
+  prior_events: &[SpecEvent],
+  parameter_id: &str,
+) -> Option<PriorHeaderParameter> {
+  let mut header_parameter = None;
+  for event in prior_events {
+    match event {
+      SpecEvent::EndpointEvent(EndpointEvent::HeaderParameterAdded(e))
+        if e.parameter_id == parameter_id =>
+      {
+        header_parameter = Some(PriorHeaderParameter {
+          owner: HeaderParameterOwner::Request(e.request_id.clone()),
+          name: e.name.clone(),
+          shape_descriptor: None,
+        });
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::ResponseHeaderParameterAdded(e))
+        if e.parameter_id == parameter_id =>
+      {
+        header_parameter = Some(PriorHeaderParameter {
+          owner: HeaderParameterOwner::Response(e.response_id.clone()),
+          name: e.name.clone(),
+          shape_descriptor: None,
+        });
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::HeaderParameterRenamed(e))
+        if e.parameter_id == parameter_id =>
+      {
+        header_parameter.as_mut()?.name = e.name.clone();
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::HeaderParameterShapeSet(e))
+        if e.parameter_id == parameter_id =>
+      {
+        header_parameter.as_mut()?.shape_descriptor = Some(e.parameter_descriptor.clone());
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::HeaderParameterShapeUnset(e))
+        if e.parameter_id == parameter_id =>
+      {
+        header_parameter.as_mut()?.shape_descriptor = None;
+      }
+      _ => {}
+    }
+  }
+  header_parameter
+}
+
+// Requests and responses, which only responses have a status code for
+struct PriorBody {
+  path_id: String,
+  http_method: String,
+  http_status_code: Option<u16>,
+  body_descriptor: Option<ShapedBodyDescriptor>,
+}
+
+fn prior_body(prior_events: &[SpecEvent], request_or_response_id: &str) -> Option<PriorBody> {
+  let mut body = None;
+  for event in prior_events {
+    match event {
+      SpecEvent::EndpointEvent(EndpointEvent::RequestAdded(e))
+        if e.request_id == request_or_response_id =>
+      {
+        body = Some(PriorBody {
+          path_id: e.path_id.clone(),
+          http_method: e.http_method.clone(),
+          http_status_code: None,
+          body_descriptor: None,
+        });
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::ResponseAddedByPathAndMethod(e))
+        if e.response_id == request_or_response_id =>
+      {
+        body = Some(PriorBody {
+          path_id: e.path_id.clone(),
+          http_method: e.http_method.clone(),
+          http_status_code: Some(e.http_status_code),
+          body_descriptor: None,
+        });
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::ResponseStatusCodeSet(e))
+        if e.response_id == request_or_response_id =>
+      {
+        body.as_mut()?.http_status_code = Some(e.http_status_code);
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::RequestBodySet(e))
+        if e.request_id == request_or_response_id =>
+      {
+        body.as_mut()?.body_descriptor = Some(e.body_descriptor.clone());
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::ResponseBodySet(e))
+        if e.response_id == request_or_response_id =>
+      {
+        body.as_mut()?.body_descriptor = Some(e.body_descriptor.clone());
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::RequestBodyUnset(e))
+        if e.request_id == request_or_response_id =>
+      {
+        body.as_mut()?.body_descriptor = None;
+      }
+      SpecEvent::EndpointEvent(EndpointEvent::ResponseBodyUnset(e))
+        if e.response_id == request_or_response_id =>
+      {
+        body.as_mut()?.body_descriptor = None;
+      }
+      _ => {}
+    }
+  }
+  body
+}
+
+fn prior_base_shape_id(prior_events: &[SpecEvent], shape_id: &str) -> Option<String> {
+  prior_events.iter().rev().find_map(|event| match event {
+    SpecEvent::ShapeEvent(ShapeEvent::ShapeAdded(e)) if e.shape_id == shape_id => {
+      Some(e.base_shape_id.clone())
+    }
+    SpecEvent::ShapeEvent(ShapeEvent::BaseShapeSet(e)) if e.shape_id == shape_id => {
+      Some(e.base_shape_id.clone())
+    }
+    _ => None,
+  })
+}
+
+fn prior_parameter_shape(
+  prior_events: &[SpecEvent],
+  provider: &ProviderInShape,
+) -> Option<ProviderInShape> {
+  prior_events.iter().rev().find_map(|event| match event {
+    SpecEvent::ShapeEvent(ShapeEvent::ShapeParameterShapeSet(e)) => match &e.shape_descriptor {
+      ParameterShapeDescriptor::ProviderInShape(prior_provider)
+        if prior_provider.shape_id == provider.shape_id
+          && prior_provider.consuming_parameter_id == provider.consuming_parameter_id =>
+      {
+        Some(prior_provider.clone())
+      }
+      _ => None,
+    },
+    _ => None,
+  })
+}
+
+struct PriorField {
+  shape_id: String,
+  name: String,
+  // fields with shapes from parameters can't be added with commands
+  shape_descriptor: Option<FieldShapeFromShape>,
+}
+
+fn prior_field(prior_events: &[SpecEvent], field_id: &str) -> Option<PriorField> {
+  let from_shape = |descriptor: &FieldShapeDescriptor| match descriptor {
+    FieldShapeDescriptor::FieldShapeFromShape(descriptor) => Some(descriptor.clone()),
+    FieldShapeDescriptor::FieldShapeFromParameter(_) => None,
+  };
+
+  let mut field = None;
+  for event in prior_events {
+    match event {
+      SpecEvent::ShapeEvent(ShapeEvent::FieldAdded(e)) if e.field_id == field_id => {
+        field = Some(PriorField {
+          shape_id: e.shape_id.clone(),
+          name: e.name.clone(),
+          shape_descriptor: from_shape(&e.shape_descriptor),
+        });
+      }
+      SpecEvent::ShapeEvent(ShapeEvent::FieldRenamed(e)) if e.field_id == field_id => {
+        field.as_mut()?.name = e.name.clone();
+      }
+      SpecEvent::ShapeEvent(ShapeEvent::FieldShapeSet(e))
+        if field_shape_descriptor_field_id(&e.shape_descriptor) == field_id =>
+      {
+        field.as_mut()?.shape_descriptor = from_shape(&e.shape_descriptor);
+      }
+      _ => {}
+    }
+  }
+  field
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::changelog::Changelog;
+  use crate::commands::CommandContext;
+  use crate::events::test_fixtures::todo_batch_commit_events;
+  use crate::projections::SpecProjection;
+  use crate::spec::append_batch;
+  use chrono::{TimeZone, Utc};
+  use insta::assert_json_snapshot;
+
+  #[test]
+  pub fn can_revert_the_last_batch_commit() {
+    let events = todo_batch_commit_events();
+
+    let commands = revert_batch_commands(&events, "batch_2", &mut SequentialIdGenerator::default())
+      .expect("last batch commit should be revertable");
+    assert_json_snapshot!(commands);
+
+    let mut batch = append_batch(
+      SpecProjection::from(events.clone()),
+      String::from("Revert batch batch_2"),
+      CommandContext::new(
+        String::from("batch_3"),
+        String::from("anonymous"),
+        String::from("session_1"),
+        Utc.timestamp(0, 0),
+      ),
+    );
+    for command in commands {
+      batch
+        .with_command(command)
+        .expect("revert commands should be applicable");
+    }
+    let mut reverted_events = events;
+    reverted_events.extend(batch.commit());
+
+    let changelog = Changelog::between_batches(&reverted_events, "batch_1", "batch_3")
+      .expect("changelog between known batches should be described");
+    assert!(
+      changelog.endpoints.is_empty(),
+      "reverted spec should equal the spec before the batch, but {:?}",
+      changelog.endpoints
+    );
+  }
+
+  #[test]
+  pub fn cannot_revert_batch_commits_later_ones_depend_on() {
+    let events = todo_batch_commit_events();
+
+    let result = revert_batch_commands(&events, "batch_1", &mut SequentialIdGenerator::default());
+    assert!(matches!(
+      result,
+      Err(RevertBatchError::DependentBatch { dependent_batch_id, .. }) if dependent_batch_id == "batch_2"
+    ));
+
+    let result = revert_batch_commands(&events, "batch_4", &mut SequentialIdGenerator::default());
+    assert!(matches!(result, Err(RevertBatchError::UnknownBatch(_))));
+  }
+
+  // ids distinct from those in the test events
+  #[derive(Debug, Default)]
+  struct SequentialIdGenerator {
+    next_id: u32,
+  }
+  impl SpecIdGenerator for SequentialIdGenerator {
+    fn generate_id(&mut self, prefix: &str) -> String {
+      self.next_id += 1;
+      format!("{}reverted_{}", prefix, self.next_id.to_string())
+    }
+  }
+}
@@ -4,6 +4,7 @@ use serde_json::map::Map as JsonMap;
 use serde_json::Value as JsonValue;
 use serde_urlencoded;
 use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
 
 #[derive(PartialEq, Clone, Debug, Hash, Eq)]
 pub enum BodyDescriptor {
@@ -11,14 +12,30 @@ pub enum BodyDescriptor {
   Array(ItemsDescriptor),
   String,
   Number,
+  /// Text that could stand for a number, like the values of a query string, matching both
+  NumericString,
   Boolean,
   Null,
+  /// Content that isn't text, like the files of a form. There's no core shape for binary content,
+  /// so it's learned as and matches `$string`, much like OpenAPI describes binary content as a
+  /// string.
+  Binary,
 }
 
 impl BodyDescriptor {
   pub fn empty_object() -> Self {
     Self::Object(ObjectDescriptor::from(std::iter::empty()))
   }
+
+  /// Describes text that isn't typed, like query string values and headers, as the number it
+  /// could stand for when it looks like one.
+  pub fn from_text(text: &str) -> Self {
+    if serde_json::from_str::<serde_json::Number>(text).is_ok() {
+      BodyDescriptor::NumericString
+    } else {
+      BodyDescriptor::String
+    }
+  }
 }
 
 #[derive(PartialEq, Clone, Debug, Hash, Eq)]
@@ -184,6 +201,9 @@ impl From<&String> for BodyDescriptor {
   }
 }
 
+/// The key-value pairs of a query string, which describe nested values: repeated keys become
+/// lists, like `tag=a&tag=b` or `ids[]=1`, and bracketed keys nested objects, like
+/// `filter[status]=open`. When keys conflict, the structure of the first one is kept.
 #[derive(Debug, Default)]
 pub struct ParsedQueryString {
   entries: Vec<(String, String)>,
@@ -198,25 +218,445 @@ impl ParsedQueryString {
 
 impl From<ParsedQueryString> for BodyDescriptor {
   fn from(parsed_qs: ParsedQueryString) -> Self {
-    let mut values_by_key = BTreeMap::new();
+    BodyDescriptor::from(FieldValue::from_entries(
+      parsed_qs
+        .entries
+        .into_iter()
+        .map(|(key, value)| (key, FieldValue::Text(value))),
+    ))
+  }
+}
 
-    for (key, value) in parsed_qs.entries {
-      let entry = values_by_key.entry(key).or_insert_with(|| vec![]);
-      entry.push(value);
-    }
+/// The fields of a `multipart/form-data` body, named like the keys of a query string. Parts
+/// carrying a file are binary, others text.
+#[derive(Debug, Default)]
+pub struct ParsedMultipartForm {
+  entries: Vec<(String, Option<String>)>,
+}
 
-    let fields = values_by_key.into_iter().map(|(key, values)| {
-      let value_descriptor = if values.len() == 1 {
-        BodyDescriptor::String
+impl ParsedMultipartForm {
+  /// Parses a body with the boundary of its content type, `None` when it isn't one.
+  pub fn from_str(content_type: &str, body: &str) -> Option<Self> {
+    let boundary = content_type.split(';').skip(1).find_map(|parameter| {
+      let (name, value) = parameter.split_once('=')?;
+      if name.trim().eq_ignore_ascii_case("boundary") {
+        Some(value.trim().trim_matches('"'))
       } else {
-        let item_descriptor = ItemsDescriptor::from(BodyDescriptor::String); // we only ever expect strings with this parser
-        BodyDescriptor::Array(item_descriptor)
+        None
+      }
+    })?;
+    let delimiter = format!("--{}", boundary);
+
+    let mut parts = body.split(delimiter.as_str()).skip(1);
+    let mut entries = vec![];
+    loop {
+      let part = parts.next()?;
+      if part.starts_with("--") {
+        break;
+      }
+
+      let part = part
+        .strip_prefix("\r\n")
+        .or_else(|| part.strip_prefix('\n'))?;
+      let (head, content) = part
+        .split_once("\r\n\r\n")
+        .or_else(|| part.split_once("\n\n"))?;
+      let content = content
+        .strip_suffix("\r\n")
+        .or_else(|| content.strip_suffix('\n'))
+        .unwrap_or(content);
+
+      let disposition = head.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name.trim().eq_ignore_ascii_case("content-disposition") {
+          Some(value)
+        } else {
+          None
+        }
+      })?;
+      let disposition_parameter = |parameter_name: &str| {
+        disposition.split(';').skip(1).find_map(|parameter| {
+          let (name, value) = parameter.split_once('=')?;
+          if name.trim().eq_ignore_ascii_case(parameter_name) {
+            Some(String::from(value.trim().trim_matches('"')))
+          } else {
+            None
+          }
+        })
+      };
+
+      let name = disposition_parameter("name")?;
+      let value = match disposition_parameter("filename") {
+        Some(_) => None,
+        None => Some(String::from(content)),
+      };
+      entries.push((name, value));
+    }
+
+    Some(Self { entries })
+  }
+}
+
+impl From<ParsedMultipartForm> for BodyDescriptor {
+  fn from(parsed_form: ParsedMultipartForm) -> Self {
+    BodyDescriptor::from(FieldValue::from_entries(
+      parsed_form
+        .entries
+        .into_iter()
+        .map(|(name, value)| (name, value.map_or(FieldValue::Binary, FieldValue::Text))),
+    ))
+  }
+}
+
+/// The elements of an XML document, described as a field named after its root element. Elements
+/// become fields named after them, namespace prefix included, repeated ones lists, and attributes
+/// fields prefixed with `@`. Elements carrying only text are described like query string values.
+#[derive(Debug)]
+pub struct ParsedXmlDocument {
+  root: FieldValue,
+}
+
+/// How deep elements can be nested, like the nesting of JSON values is limited when parsing them
+const MAX_XML_DEPTH: usize = 128;
+
+#[derive(Debug, Error)]
+pub enum XmlDocumentError {
+  #[error(transparent)]
+  Invalid(#[from] roxmltree::Error),
+  #[error("elements are nested deeper than {} levels", MAX_XML_DEPTH)]
+  TooDeep,
+}
+
+impl ParsedXmlDocument {
+  pub fn from_str(xml: &str) -> Result<Self, XmlDocumentError> {
+    let document = roxmltree::Document::parse(xml)?;
+    let root_element = document.root_element();
+    let mut fields = BTreeMap::new();
+    fields.insert(
+      Self::element_name(root_element),
+      Self::element_value(root_element, 1)?,
+    );
+    Ok(Self {
+      root: FieldValue::Object(fields),
+    })
+  }
+
+  /// Names as they're written, keeping their namespace prefix, like `soap:Body`
+  fn qualified_name(element: roxmltree::Node, namespace: Option<&str>, name: &str) -> String {
+    match namespace.and_then(|uri| element.lookup_prefix(uri)) {
+      Some(prefix) => format!("{}:{}", prefix, name),
+      None => String::from(name),
+    }
+  }
+
+  fn element_name(element: roxmltree::Node) -> String {
+    let tag_name = element.tag_name();
+    Self::qualified_name(element, tag_name.namespace(), tag_name.name())
+  }
+
+  fn element_value(element: roxmltree::Node, depth: usize) -> Result<FieldValue, XmlDocumentError> {
+    if depth > MAX_XML_DEPTH {
+      return Err(XmlDocumentError::TooDeep);
+    }
+    let child_elements = element.children().filter(|child| child.is_element());
+    if element.attributes().is_empty() && child_elements.clone().next().is_none() {
+      return Ok(FieldValue::Text(Self::element_text(element)));
+    }
+
+    let mut fields = BTreeMap::new();
+    for attribute in element.attributes() {
+      let name = Self::qualified_name(element, attribute.namespace(), attribute.name());
+      fields.insert(
+        format!("@{}", name),
+        FieldValue::Text(String::from(attribute.value())),
+      );
+    }
+    for child in child_elements {
+      let name = Self::element_name(child);
+      let value = Self::element_value(child, depth + 1)?;
+      match fields.get_mut(&name) {
+        None => {
+          fields.insert(name, value);
+        }
+        Some(FieldValue::List(items)) => items.push(value),
+        Some(existing) => {
+          let first = std::mem::replace(existing, FieldValue::List(vec![]));
+          *existing = FieldValue::List(vec![first, value]);
+        }
+      }
+    }
+
+    // text mixed with elements or next to attributes
+    let text = Self::element_text(element);
+    if !text.trim().is_empty() {
+      fields.insert(String::from("#text"), FieldValue::Text(text));
+    }
+
+    Ok(FieldValue::Object(fields))
+  }
+
+  fn element_text(element: roxmltree::Node) -> String {
+    element
+      .children()
+      .filter(|child| child.is_text())
+      .filter_map(|child| child.text())
+      .collect()
+  }
+}
+
+impl From<ParsedXmlDocument> for BodyDescriptor {
+  fn from(parsed_xml: ParsedXmlDocument) -> Self {
+    BodyDescriptor::from(parsed_xml.root)
+  }
+}
+
+/// Values nested by their names, as found in query strings, forms and XML documents.
+#[derive(Debug, PartialEq)]
+enum FieldValue {
+  Text(String),
+  Binary,
+  List(Vec<FieldValue>),
+  Object(BTreeMap<String, FieldValue>),
+}
+
+impl FieldValue {
+  fn from_entries(entries: impl IntoIterator<Item = (String, FieldValue)>) -> Self {
+    let mut root = FieldValue::Object(BTreeMap::new());
+    for (key, value) in entries {
+      let (name, mut key_path) = QueryKeySegment::parse(&key);
+      key_path.insert(0, QueryKeySegment::Key(name));
+      root.insert(&key_path, value);
+    }
+    root
+  }
+
+  fn container_for(segment: &QueryKeySegment) -> Self {
+    match segment {
+      QueryKeySegment::Key(_) => FieldValue::Object(BTreeMap::new()),
+      QueryKeySegment::Index(_) | QueryKeySegment::Append => FieldValue::List(vec![]),
+    }
+  }
+
+  fn insert(&mut self, key_path: &[QueryKeySegment], value: FieldValue) {
+    let (segment, rest) = match key_path.split_first() {
+      Some(split) => split,
+      None => return,
+    };
+
+    match (self, segment) {
+      (FieldValue::Object(fields), QueryKeySegment::Key(key)) => {
+        match (fields.get_mut(key), rest) {
+          (None, []) => {
+            fields.insert(key.clone(), value);
+          }
+          (None, [next, ..]) => {
+            let mut container = Self::container_for(next);
+            container.insert(rest, value);
+            fields.insert(key.clone(), container);
+          }
+          // repeated keys
+          (Some(FieldValue::List(items)), []) => items.push(value),
+          (Some(existing @ (FieldValue::Text(_) | FieldValue::Binary)), []) => {
+            let first = std::mem::replace(existing, FieldValue::List(vec![]));
+            *existing = FieldValue::List(vec![first, value]);
+          }
+          (Some(FieldValue::Object(_)), []) => {}
+          (Some(nested), rest) => nested.insert(rest, value),
+        }
+      }
+      (FieldValue::List(items), QueryKeySegment::Append) => match rest {
+        [] => items.push(value),
+        [next, ..] => {
+          let mut container = Self::container_for(next);
+          container.insert(rest, value);
+          items.push(container);
+        }
+      },
+      // indices only group the values of a key, new ones are appended
+      (FieldValue::List(items), QueryKeySegment::Index(index)) => {
+        match (items.get_mut(*index), rest) {
+          (Some(item), [_, ..]) => item.insert(rest, value),
+          (_, []) => items.push(value),
+          (None, [next, ..]) => {
+            let mut container = Self::container_for(next);
+            container.insert(rest, value);
+            items.push(container);
+          }
+        }
+      }
+      // conflicting with the structure established by an earlier key
+      _ => {}
+    }
+  }
+}
+
+impl From<FieldValue> for BodyDescriptor {
+  fn from(query_value: FieldValue) -> Self {
+    match query_value {
+      FieldValue::Text(text) => BodyDescriptor::from_text(&text),
+      FieldValue::Binary => BodyDescriptor::Binary,
+      FieldValue::List(items) => {
+        BodyDescriptor::Array(ItemsDescriptor::from(items.into_iter().map(Self::from)))
+      }
+      FieldValue::Object(fields) => BodyDescriptor::Object(ObjectDescriptor::from(
+        fields
+          .into_iter()
+          .map(|(key, value)| (key, Self::from(value))),
+      )),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+enum QueryKeySegment {
+  Key(String),
+  Index(usize),
+  Append,
+}
+
+impl QueryKeySegment {
+  /// The name of a key and the bracketed segments following it, like `filter[status][]`. Keys
+  /// with unbalanced brackets are names as a whole.
+  fn parse(key: &str) -> (String, Vec<Self>) {
+    let literal = || (String::from(key), vec![]);
+    let (name, mut rest) = match key.find('[') {
+      Some(0) | None => return literal(),
+      Some(start) => (&key[..start], &key[start..]),
+    };
+
+    let mut segments = vec![];
+    while !rest.is_empty() {
+      let end = match (rest.strip_prefix('['), rest.find(']')) {
+        (Some(_), Some(end)) => end,
+        _ => return literal(),
       };
+      let segment = &rest[1..end];
+      segments.push(if segment.is_empty() {
+        QueryKeySegment::Append
+      } else if let Ok(index) = segment.parse() {
+        QueryKeySegment::Index(index)
+      } else {
+        QueryKeySegment::Key(String::from(segment))
+      });
+      rest = &rest[end + 1..];
+    }
+
+    (String::from(name), segments)
+  }
+}
+
+// Headers are matched case-insensitively, so names are normalized to lowercase when parsed
+#[derive(Debug, Default)]
+pub struct ParsedHeaders {
+  entries: Vec<(String, String)>,
+}
+
+impl ParsedHeaders {
+  pub fn from_json(json: JsonValue) -> Self {
+    let mut entries = vec![];
+
+    match json {
+      // { "X-Request-Id": "abc", "Set-Cookie": ["a=1", "b=2"] }
+      JsonValue::Object(fields) => {
+        for (name, value) in fields {
+          match value {
+            JsonValue::Array(values) => {
+              for value in values {
+                entries.push((name.to_lowercase(), Self::json_header_value(value)));
+              }
+            }
+            value => entries.push((name.to_lowercase(), Self::json_header_value(value))),
+          }
+        }
+      }
+      // [{ "name": "X-Request-Id", "value": "abc" }]
+      JsonValue::Array(items) => {
+        for item in items {
+          let name = item.get("name").and_then(|name| name.as_str());
+          let value = item.get("value").cloned().unwrap_or(JsonValue::Null);
+          if let Some(name) = name {
+            entries.push((name.to_lowercase(), Self::json_header_value(value)));
+          }
+        }
+      }
+      _ => {}
+    }
+
+    Self { entries }
+  }
+
+  pub fn from_text(text: &str) -> Self {
+    let entries = text
+      .lines()
+      .filter_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        Some((name.trim().to_lowercase(), String::from(value.trim())))
+      })
+      .collect();
+
+    Self { entries }
+  }
+
+  fn json_header_value(value: JsonValue) -> String {
+    match value {
+      JsonValue::String(string) => string,
+      JsonValue::Null => String::from(""),
+      other => other.to_string(),
+    }
+  }
+
+  pub fn names(&self) -> impl Iterator<Item = &String> {
+    let unique_names: std::collections::BTreeSet<&String> =
+      self.entries.iter().map(|(name, _)| name).collect();
+    unique_names.into_iter()
+  }
+
+  /// Describes the values of a header, with `true` and `false` taken for booleans. Repeated
+  /// headers are described as a list.
+  pub fn get_descriptor(&self, name: &str) -> Option<BodyDescriptor> {
+    self.describe_values(name, true)
+  }
 
-      (key, value_descriptor)
-    });
+  /// Describes the values of a header like `get_descriptor`, without taking any for booleans.
+  pub fn get_text_descriptor(&self, name: &str) -> Option<BodyDescriptor> {
+    self.describe_values(name, false)
+  }
+
+  fn describe_values(&self, name: &str, with_booleans: bool) -> Option<BodyDescriptor> {
+    let name = name.to_lowercase();
+    let mut value_descriptors = self
+      .entries
+      .iter()
+      .filter(|(entry_name, _)| *entry_name == name)
+      .map(|(_, value)| match value.as_str() {
+        "true" | "false" if with_booleans => BodyDescriptor::Boolean,
+        value => BodyDescriptor::from_text(value),
+      })
+      .collect::<Vec<_>>();
+
+    match value_descriptors.len() {
+      0 => None,
+      1 => value_descriptors.pop(),
+      _ => Some(BodyDescriptor::Array(ItemsDescriptor::from(
+        value_descriptors.into_iter(),
+      ))),
+    }
+  }
+}
+
+impl From<ParsedHeaders> for BodyDescriptor {
+  fn from(parsed_headers: ParsedHeaders) -> Self {
+    let fields = parsed_headers
+      .names()
+      .map(|name| {
+        let value_descriptor = parsed_headers
+          .get_descriptor(name)
+          .expect("header should be present for its own name");
+        (name.clone(), value_descriptor)
+      })
+      .collect::<Vec<_>>();
 
-    BodyDescriptor::Object(ObjectDescriptor::from(fields))
+    BodyDescriptor::Object(ObjectDescriptor::from(fields.into_iter()))
   }
 }
 
@@ -242,4 +682,146 @@ mod test {
       body_descriptor
     );
   }
+
+  #[test]
+  fn query_string_with_brackets_can_be_parsed_to_body_descriptor() {
+    let parsed = ParsedQueryString::from_str(
+      "tag=a&tag=b&ids[]=1&ids[]=2&filter[status]=open&filter[since]=2020\
+      &items[0][name]=first&items[0][count]=3&items[1][name]=second&limit=10&broken[=x",
+    )
+    .expect("should be able to parse a query string");
+
+    let body_descriptor = BodyDescriptor::from(parsed);
+
+    assert_debug_snapshot!(
+      "query_string_with_brackets_can_be_parsed_to_body_descriptor__body_descriptor",
+      body_descriptor
+    );
+
+    let conflicting = ParsedQueryString::from_str("filter[status]=open&filter=all&ids[]=1&ids=2")
+      .expect("should be able to parse a query string");
+
+    assert_debug_snapshot!(
+      "query_string_with_brackets_can_be_parsed_to_body_descriptor__conflicting",
+      BodyDescriptor::from(conflicting)
+    );
+  }
+
+  #[test]
+  fn multipart_form_can_be_parsed_to_body_descriptor() {
+    let body = [
+      "--XyZ",
+      "Content-Disposition: form-data; name=\"title\"",
+      "",
+      "holiday",
+      "--XyZ",
+      "Content-Disposition: form-data; name=\"size\"",
+      "",
+      "1024",
+      "--XyZ",
+      "Content-Disposition: form-data; name=\"photos[]\"; filename=\"beach.png\"",
+      "Content-Type: image/png",
+      "",
+      "\u{89}PNG",
+      "--XyZ--",
+      "",
+    ]
+    .join("\r\n");
+
+    let parsed = ParsedMultipartForm::from_str("multipart/form-data; boundary=XyZ", &body)
+      .expect("should be able to parse a multipart form");
+
+    assert_debug_snapshot!(
+      "multipart_form_can_be_parsed_to_body_descriptor__body_descriptor",
+      BodyDescriptor::from(parsed)
+    );
+
+    assert!(
+      ParsedMultipartForm::from_str("multipart/form-data", &body).is_none(),
+      "forms can't be parsed without a boundary"
+    );
+    assert!(
+      ParsedMultipartForm::from_str("multipart/form-data; boundary=XyZ", "holiday").is_none(),
+      "forms can't be parsed without parts"
+    );
+  }
+
+  #[test]
+  fn xml_document_can_be_parsed_to_body_descriptor() {
+    let parsed = ParsedXmlDocument::from_str(
+      r#"<?xml version="1.0"?>
+      <rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+        <channel>
+          <title>Releases</title>
+          <item><title>v1</title><dc:creator dc:role="author">optic</dc:creator></item>
+          <item><title>v2</title><guid isPermaLink="false">42</guid></item>
+        </channel>
+      </rss>"#,
+    )
+    .expect("should be able to parse an xml document");
+
+    assert_debug_snapshot!(
+      "xml_document_can_be_parsed_to_body_descriptor__body_descriptor",
+      BodyDescriptor::from(parsed)
+    );
+
+    assert!(ParsedXmlDocument::from_str("<rss><channel></rss>").is_err());
+  }
+
+  #[test]
+  fn xml_documents_nested_too_deep_cant_be_parsed() {
+    let nested = |depth: usize| format!("{}x{}", "<a>".repeat(depth), "</a>".repeat(depth));
+
+    assert!(ParsedXmlDocument::from_str(&nested(MAX_XML_DEPTH)).is_ok());
+    assert!(matches!(
+      ParsedXmlDocument::from_str(&nested(MAX_XML_DEPTH + 1)),
+      Err(XmlDocumentError::TooDeep)
+    ));
+    assert!(
+      ParsedXmlDocument::from_str(&nested(20_000)).is_err(),
+      "deeply nested documents shouldn't overflow the stack"
+    );
+  }
+
+  #[test]
+  fn headers_can_be_parsed_to_body_descriptor() {
+    let parsed = ParsedHeaders::from_json(serde_json::json!({
+      "X-Request-Id": "a5d3b4c2",
+      "Set-Cookie": ["a=1", "b=2"],
+      "content-length": 24
+    }));
+
+    assert_debug_snapshot!("headers_can_be_parsed_to_body_descriptor__parsed", &parsed);
+    assert_eq!(
+      parsed.get_descriptor("x-request-id"),
+      Some(BodyDescriptor::String),
+      "header names are matched case-insensitively"
+    );
+
+    let body_descriptor = BodyDescriptor::from(parsed);
+
+    assert_debug_snapshot!(
+      "headers_can_be_parsed_to_body_descriptor__body_descriptor",
+      body_descriptor
+    );
+  }
+
+  #[test]
+  fn headers_can_be_parsed_from_name_value_pairs_and_text() {
+    let from_pairs = ParsedHeaders::from_json(serde_json::json!([
+      { "name": "Authorization", "value": "Bearer abc" },
+      { "name": "Accept", "value": "application/json" }
+    ]));
+    let from_text =
+      ParsedHeaders::from_text("Authorization: Bearer abc\r\nAccept: application/json");
+
+    assert_eq!(
+      from_pairs.names().collect::<Vec<_>>(),
+      vec!["accept", "authorization"]
+    );
+    assert_eq!(
+      from_text.names().collect::<Vec<_>>(),
+      vec!["accept", "authorization"]
+    );
+  }
 }
@@ -19,11 +19,23 @@ pub struct ShapedBodyDescriptor {
   pub is_removed: bool,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize, Clone)]
+#[derive(Debug, Deserialize, PartialEq, Serialize, Clone, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct ShapedRequestParameterShapeDescriptor {
   pub shape_id: ShapeId,
   pub is_removed: bool,
+  /// Format string values have to be in, on top of matching the shape
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub format: Option<StringFormat>,
+  /// The only values allowed, on top of matching the shape
+  #[serde(default, rename = "enum", skip_serializing_if = "Option::is_none")]
+  pub enum_values: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum StringFormat {
+  Uuid,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize, Clone)]
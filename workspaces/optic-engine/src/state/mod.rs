@@ -9,6 +9,10 @@ pub mod shape;
 pub trait SpecIdGenerator {
   fn generate_id(&mut self, prefix: &str) -> String;
 
+  fn path(&mut self) -> String {
+    self.generate_id("path_")
+  }
+
   fn field(&mut self) -> String {
     self.generate_id("field_")
   }
@@ -25,6 +29,10 @@ pub trait SpecIdGenerator {
     self.generate_id("response_")
   }
 
+  fn request_parameter(&mut self) -> String {
+    self.generate_id("request_parameter_")
+  }
+
   fn shape(&mut self) -> String {
     self.generate_id("shape_")
   }
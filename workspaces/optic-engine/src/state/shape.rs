@@ -177,12 +177,21 @@ impl ShapeKind {
       Self::ListKind => Some(ShapeKindParameterDescriptor {
         shape_parameter_id: "$listItem",
       }),
+      Self::MapKind => Some(ShapeKindParameterDescriptor {
+        shape_parameter_id: "$mapValue",
+      }),
       Self::NullableKind => Some(ShapeKindParameterDescriptor {
         shape_parameter_id: "$nullableInner",
       }),
       Self::OptionalKind => Some(ShapeKindParameterDescriptor {
         shape_parameter_id: "$optionalInner",
       }),
+      Self::IdentifierKind => Some(ShapeKindParameterDescriptor {
+        shape_parameter_id: "$identifierInner",
+      }),
+      Self::ReferenceKind => Some(ShapeKindParameterDescriptor {
+        shape_parameter_id: "$referenceInner",
+      }),
       _ => None,
     }
   }
@@ -1,5 +1,7 @@
+use crate::events::HttpInteraction;
 use futures::Stream;
-use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader, Lines};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader, Lines};
 use tokio_stream::wrappers::LinesStream;
 
 pub fn json_lines<R>(source: R) -> impl Stream<Item = Result<String, std::io::Error>>
@@ -9,3 +11,62 @@ where
   // 10 megabytes of capacity, to deal with unbound nature of request and response bodies
   super::json_lines(source, 10 * 1024 * 1024)
 }
+
+/// Read the interactions recorded in a HAR document. As a HAR document is a single json value,
+/// the source is read completely before any of the interactions are produced.
+pub async fn from_har<R>(mut source: R) -> Result<Vec<HttpInteraction>, HarReaderError>
+where
+  R: AsyncRead + Unpin,
+{
+  let mut json = String::new();
+  source.read_to_string(&mut json).await?;
+
+  Ok(HttpInteraction::from_har_str(&json)?)
+}
+
+/// Read the interactions from an Avro object container file, like the ones written by capture
+/// agents. Decoding is blocking work, so it happens on the blocking thread pool, with the
+/// interactions streamed back as they are decoded.
+#[cfg(feature = "avro")]
+pub fn avro<R>(
+  source: R,
+) -> impl Stream<Item = Result<HttpInteraction, crate::errors::HttpInteractionAvroError>>
+where
+  R: std::io::Read + Send + 'static,
+{
+  let (sender, receiver) = tokio::sync::mpsc::channel(32);
+
+  tokio::task::spawn_blocking(move || {
+    let mut deserializer = HttpInteraction::from_avro();
+    let reader = match deserializer.reader(source) {
+      Ok(reader) => reader,
+      Err(err) => {
+        let _ = sender.blocking_send(Err(err));
+        return;
+      }
+    };
+
+    for interaction in reader {
+      if sender.blocking_send(interaction).is_err() {
+        break; // receiver has been dropped, nobody is interested in the rest
+      }
+    }
+  });
+
+  tokio_stream::wrappers::ReceiverStream::new(receiver)
+}
+
+#[derive(Debug, Error)]
+pub enum HarReaderError {
+  #[error("json deserialisation error: {}", .source)]
+  Json {
+    #[from]
+    source: serde_json::Error,
+  },
+
+  #[error("io error: {}", .source)]
+  Io {
+    #[from]
+    source: std::io::Error,
+  },
+}
@@ -1,5 +1,5 @@
 use insta::assert_debug_snapshot;
-use optic_engine::SpecEvent;
+use optic_engine::{EndpointQueries, RfcEvent, SpecEvent, SpecProjection, ROOT_BATCH_ID};
 
 #[test]
 fn spec_should_deserialize() {
@@ -13,3 +13,43 @@ fn spec_should_deserialize() {
   .expect("ergast spec should deserialize");
   assert_debug_snapshot!(events);
 }
+
+#[test]
+fn spec_can_be_projected_as_of_a_batch_commit() {
+  let events = SpecEvent::from_file(
+    std::env::current_dir()
+      .unwrap()
+      .join("tests/fixtures/ergast-example-spec.json")
+      .to_str()
+      .unwrap(),
+  )
+  .expect("ergast spec should deserialize");
+
+  let first_batch_events =
+    SpecEvent::until_batch_commit(&events, "3960ebac-7dba-4118-97df-3af91005dc61")
+      .expect("first batch commit should exist in the spec");
+  assert!(matches!(
+    first_batch_events.last(),
+    Some(SpecEvent::RfcEvent(RfcEvent::BatchCommitEnded(_)))
+  ));
+  assert!(first_batch_events.len() < events.len());
+
+  let root_events = SpecEvent::until_batch_commit(&events, ROOT_BATCH_ID)
+    .expect("root should always exist in the spec");
+  assert!(!root_events
+    .iter()
+    .any(|event| matches!(event, SpecEvent::RfcEvent(RfcEvent::BatchCommitStarted(_)))));
+
+  let first_batch_spec =
+    SpecProjection::at_batch_commit(&events, "3960ebac-7dba-4118-97df-3af91005dc61")
+      .expect("spec should be projected as of the first batch commit");
+  let latest_spec = SpecProjection::from(events.clone());
+  let path_count = |spec: &SpecProjection| {
+    EndpointQueries::new(spec.endpoint())
+      .resolve_path_patterns()
+      .len()
+  };
+  assert!(path_count(&first_batch_spec) < path_count(&latest_spec));
+
+  assert!(SpecProjection::at_batch_commit(&events, "not-a-batch").is_err());
+}
@@ -13,7 +13,6 @@ use tokio_stream::StreamExt;
 
 #[cfg(feature = "avro")]
 #[test]
-#[should_panic] // avro deserializing doesn't work yet
 fn can_read_avro_serialized_interactions() {
   let fd = std::fs::File::open(
     std::env::current_dir()
@@ -27,9 +26,64 @@ fn can_read_avro_serialized_interactions() {
   let interactions: Vec<_> = HttpInteraction::from_avro()
     .reader(fd)
     .expect("interactions from capture should deserialize")
-    .collect();
+    .collect::<Result<_, _>>()
+    .expect("interactions from capture should deserialize");
 
   assert!(interactions.len() > 0);
+  assert_eq!(interactions[0].request.path, "/api/f1/2019/1/results");
+  assert_eq!(interactions[0].response.status_code, 200);
+}
+
+#[cfg(feature = "avro")]
+#[tokio::test]
+async fn can_async_read_avro_serialized_interactions() {
+  let fd = std::fs::File::open(
+    std::env::current_dir()
+      .unwrap()
+      .join("tests/fixtures/ergast-captures/0.optic-capture.avro")
+      .to_str()
+      .unwrap(),
+  )
+  .expect("test capture file should be readable");
+
+  let interactions: Vec<_> = streams::http_interaction::avro(fd)
+    .collect::<Vec<_>>()
+    .await
+    .into_iter()
+    .collect::<Result<_, _>>()
+    .expect("interactions from capture should deserialize");
+
+  assert!(interactions.len() > 0);
+}
+
+#[cfg(feature = "avro")]
+#[test]
+fn rejects_avro_data_blocks_with_invalid_sizes() {
+  let container_header = [
+    b"Obj\x01".to_vec(), // magic
+    vec![0],             // empty metadata map
+    vec![7u8; 16],       // sync marker
+  ]
+  .concat();
+
+  // a block count of 1, followed by a size of -1
+  let negative_size = [container_header.clone(), vec![2, 1]].concat();
+  // a block count of 1, followed by a size of 2^40 with only a few bytes left
+  let truncated_block = [
+    container_header,
+    vec![2, 0x80, 0x80, 0x80, 0x80, 0x80, 0x40],
+    vec![0u8; 8],
+  ]
+  .concat();
+
+  for source in vec![negative_size, truncated_block] {
+    let results: Vec<_> = HttpInteraction::from_avro()
+      .reader(&source[..])
+      .expect("container header should be valid")
+      .collect();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+  }
 }
 
 #[tokio::test]
@@ -65,3 +119,78 @@ async fn can_async_read_stream_of_newline_delimited_json() {
   let remaining_events = lines.take(3).collect::<Vec<_>>().await;
   assert_eq!(remaining_events.len(), 2);
 }
+
+#[tokio::test]
+async fn can_read_interactions_from_har_document() {
+  let fd = tokio::fs::File::open(
+    std::env::current_dir()
+      .unwrap()
+      .join("tests/fixtures/todos-interactions.har")
+      .to_str()
+      .unwrap(),
+  )
+  .await
+  .expect("test fixture should exist");
+
+  let interactions = streams::http_interaction::from_har(fd)
+    .await
+    .expect("har document should be readable");
+
+  // the aborted request has no response and is left out
+  assert_eq!(interactions.len(), 3);
+
+  let list_interaction = &interactions[0];
+  assert_eq!(list_interaction.request.host, "localhost:3000");
+  assert_eq!(list_interaction.request.method, "GET");
+  assert_eq!(list_interaction.request.path, "/todos");
+  assert_eq!(
+    list_interaction.request.query.data.as_text.as_deref(),
+    Some("done=true&limit=10")
+  );
+  assert_eq!(list_interaction.response.status_code, 200);
+  assert_eq!(
+    list_interaction.response.body.content_type.as_deref(),
+    Some("application/json; charset=utf-8")
+  );
+  assert!(list_interaction
+    .response
+    .body
+    .value
+    .as_json_string
+    .is_some());
+
+  let create_interaction = &interactions[1];
+  assert_eq!(create_interaction.request.host, "api.example.com");
+  assert_eq!(create_interaction.request.path, "/todos");
+  assert!(create_interaction.request.query.data.as_text.is_none());
+  assert_eq!(
+    create_interaction
+      .request
+      .body
+      .value
+      .as_json_string
+      .as_deref(),
+    Some(r#"{"title":"walk the dog"}"#)
+  );
+  assert_eq!(
+    create_interaction
+      .response
+      .body
+      .value
+      .as_json_string
+      .as_deref(),
+    Some(r#"{"id":"2","title":"walk the dog"}"#)
+  );
+  assert_eq!(
+    create_interaction.request.headers.as_json_string.as_deref(),
+    Some(r#"[{"name":"content-type","value":"application/json"}]"#)
+  );
+
+  let attachment_interaction = &interactions[2];
+  assert_eq!(
+    attachment_interaction.response.body.content_type.as_deref(),
+    Some("image/png"),
+    "binary content keeps its content type"
+  );
+  assert!(attachment_interaction.response.body.value.as_text.is_none());
+}
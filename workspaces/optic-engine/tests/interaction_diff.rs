@@ -1,7 +1,8 @@
 use futures::sink::SinkExt;
 use insta::assert_debug_snapshot;
 use optic_engine::{
-  diff_interaction, streams, DiffInteractionConfig, HttpInteraction, SpecEvent, SpecProjection,
+  diff_interaction, streams, DiffInteractionConfig, HttpInteraction, InteractionDiffResult,
+  SpecEvent, SpecProjection,
 };
 use petgraph::dot::Dot;
 use serde_json::json;
@@ -264,6 +265,468 @@ fn can_yield_unmatched_shape() {
   assert_eq!(results.len(), 0);
 }
 
+#[test]
+fn can_ignore_interactions_and_body_trails() {
+  let events: Vec<SpecEvent> = serde_json::from_value(
+    json!([
+      {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"xyz"}},
+      {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"POST"}},
+      {"ResponseAddedByPathAndMethod":{"responseId":"response_1", "httpStatusCode":200,"pathId":"path_1","httpMethod":"POST"}},
+      {"ShapeAdded":{"shapeId":"shape_1","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"shape_2","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"FieldAdded":{"fieldId":"field_1","shapeId":"shape_1","name":"name","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_1","shapeId":"shape_2"}}}},
+      {"RequestBodySet": {"shapeId":"shape_1","requestId": "request_1", "bodyDescriptor":{"httpContentType":"application/json","shapeId":"shape_1","isRemoved":false}}}
+    ]),
+  ).expect("should be able to deserialize shape added events as spec events");
+
+  let spec_projection = SpecProjection::from(events);
+
+  let interaction = |path: &str| {
+    let empty = json!({ "asJsonString": null, "asText": null, "asShapeHashBytes": null });
+    serde_json::from_value::<HttpInteraction>(json!({
+      "uuid": "5",
+      "request": {
+        "host": "localhost",
+        "method": "POST",
+        "path": path,
+        "query": empty,
+        "headers": empty,
+        "body": {
+          "contentType": "application/json",
+          "value": {
+            "asJsonString": "{\"name\":\"x\",\"debug\":{\"sql\":\"SELECT 1\"}}",
+            "asText": null,
+            "asShapeHashBytes": null
+          }
+        }
+      },
+      "response": {
+        "statusCode": 200,
+        "headers": empty,
+        "body": { "contentType": null, "value": empty }
+      },
+      "tags": []
+    }))
+    .expect("example http interaction should deserialize")
+  };
+
+  let results = diff_interaction(
+    &spec_projection,
+    interaction("/xyz"),
+    &DiffInteractionConfig::default(),
+  );
+  assert_debug_snapshot!(
+    "can_ignore_interactions_and_body_trails__unignored",
+    results
+  );
+  assert_eq!(results.len(), 1);
+
+  let config: DiffInteractionConfig = serde_json::from_value(json!({
+    "ignore": {
+      "paths": ["/health"],
+      "bodyTrails": ["$.debug"]
+    }
+  }))
+  .expect("diff config should deserialize");
+
+  let results = diff_interaction(&spec_projection, interaction("/xyz"), &config);
+  assert_eq!(results.len(), 0);
+
+  let unmatched_results = diff_interaction(
+    &spec_projection,
+    interaction("/health"),
+    &DiffInteractionConfig::default(),
+  );
+  assert_eq!(unmatched_results.len(), 1);
+  let results = diff_interaction(&spec_projection, interaction("/health"), &config);
+  assert_eq!(results.len(), 0);
+}
+
+#[test]
+fn can_yield_ambiguous_request_url() {
+  let events: Vec<SpecEvent> = serde_json::from_value(json!([
+    {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"posts"}},
+    {"PathParameterAdded":{"pathId":"path_2","parentPathId":"path_1","name":"postId"}},
+    {"PathParameterAdded":{"pathId":"path_3","parentPathId":"path_1","name":"slug"}},
+    {"RequestAdded":{"requestId":"request_1","pathId":"path_2","httpMethod":"GET"}},
+    {"RequestAdded":{"requestId":"request_2","pathId":"path_3","httpMethod":"GET"}},
+  ]))
+  .expect("should be able to deserialize path events as spec events");
+
+  let spec_projection = SpecProjection::from(events);
+
+  let empty = json!({ "asJsonString": null, "asText": null, "asShapeHashBytes": null });
+  let interaction: HttpInteraction = serde_json::from_value(json!({
+    "uuid": "5",
+    "request": {
+      "host": "localhost",
+      "method": "GET",
+      "path": "/posts/hello-world",
+      "query": empty,
+      "headers": empty,
+      "body": { "contentType": null, "value": empty }
+    },
+    "response": {
+      "statusCode": 200,
+      "headers": empty,
+      "body": { "contentType": null, "value": empty }
+    },
+    "tags": []
+  }))
+  .expect("example http interaction should deserialize");
+
+  let results = diff_interaction(
+    &spec_projection,
+    interaction,
+    &DiffInteractionConfig::default(),
+  );
+  assert_debug_snapshot!(results);
+  assert_eq!(results.len(), 1);
+}
+
+#[test]
+fn can_yield_unmatched_path_parameter_shapes() {
+  let events: Vec<SpecEvent> = serde_json::from_value(json!([
+    {"ShapeAdded":{"shapeId":"number_shape_1","baseShapeId":"$number","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"orders"}},
+    {"PathParameterAdded":{"pathId":"path_2","parentPathId":"path_1","name":"orderId"}},
+    {"PathParameterShapeSet":{"pathId":"path_2","shapeDescriptor":{"shapeId":"number_shape_1","isRemoved":false}}},
+    {"PathComponentAdded":{"pathId":"path_3","parentPathId":"path_2","name":"items"}},
+    {"PathParameterAdded":{"pathId":"path_4","parentPathId":"path_3","name":"itemId"}},
+    {"PathParameterShapeSet":{"pathId":"path_4","shapeDescriptor":{"shapeId":"string_shape_1","isRemoved":false,"format":"uuid"}}},
+    {"PathComponentAdded":{"pathId":"path_5","parentPathId":"root","name":"reports"}},
+    {"PathParameterAdded":{"pathId":"path_6","parentPathId":"path_5","name":"period"}},
+    {"PathParameterShapeSet":{"pathId":"path_6","shapeDescriptor":{"shapeId":"string_shape_1","isRemoved":false,"enum":["daily","weekly"]}}},
+    {"PathComponentAdded":{"pathId":"path_7","parentPathId":"root","name":"users"}},
+    {"PathParameterAdded":{"pathId":"path_8","parentPathId":"path_7","name":"userId"}},
+    {"RequestAdded":{"requestId":"request_1","pathId":"path_2","httpMethod":"GET"}},
+    {"RequestAdded":{"requestId":"request_2","pathId":"path_4","httpMethod":"GET"}},
+    {"RequestAdded":{"requestId":"request_3","pathId":"path_6","httpMethod":"GET"}},
+    {"RequestAdded":{"requestId":"request_4","pathId":"path_8","httpMethod":"GET"}},
+  ]))
+  .expect("should be able to deserialize path events as spec events");
+
+  let spec_projection = SpecProjection::from(events);
+
+  let path_parameter_diffs = |path: &str| {
+    let empty = json!({ "asJsonString": null, "asText": null, "asShapeHashBytes": null });
+    let interaction: HttpInteraction = serde_json::from_value(json!({
+      "uuid": "5",
+      "request": {
+        "host": "localhost",
+        "method": "GET",
+        "path": path,
+        "query": empty,
+        "headers": empty,
+        "body": { "contentType": null, "value": empty }
+      },
+      "response": {
+        "statusCode": 200,
+        "headers": empty,
+        "body": { "contentType": null, "value": empty }
+      },
+      "tags": []
+    }))
+    .expect("example http interaction should deserialize");
+
+    diff_interaction(
+      &spec_projection,
+      interaction,
+      &DiffInteractionConfig::default(),
+    )
+    .into_iter()
+    .filter(|result| {
+      matches!(
+        result,
+        InteractionDiffResult::UnmatchedPathParameterShape(_)
+      )
+    })
+    .collect::<Vec<_>>()
+  };
+
+  assert!(path_parameter_diffs("/orders/12").is_empty());
+  assert!(
+    !path_parameter_diffs("/orders/+12").is_empty(),
+    "path parameters only stand for numbers written like query string values"
+  );
+  assert!(path_parameter_diffs("/orders/12/items/550e8400-e29b-41d4-a716-446655440000").is_empty());
+  assert!(path_parameter_diffs("/reports/weekly").is_empty());
+  assert!(
+    path_parameter_diffs("/users/undefined").is_empty(),
+    "path parameters without a shape are not diffed"
+  );
+
+  assert_debug_snapshot!(
+    "can_yield_unmatched_path_parameter_shapes__number",
+    path_parameter_diffs("/orders/undefined")
+  );
+  assert_debug_snapshot!(
+    "can_yield_unmatched_path_parameter_shapes__uuid",
+    path_parameter_diffs("/orders/12/items/undefined")
+  );
+  assert_debug_snapshot!(
+    "can_yield_unmatched_path_parameter_shapes__enum",
+    path_parameter_diffs("/reports/monthly")
+  );
+}
+
+#[test]
+fn can_yield_unmatched_query_parameter_shapes() {
+  let events: Vec<SpecEvent> = serde_json::from_value(json!([
+    {"ShapeAdded":{"shapeId":"number_shape_1","baseShapeId":"$number","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"ShapeAdded":{"shapeId":"list_shape_1","baseShapeId":"$list","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"list_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"string_shape_1"}},"consumingParameterId":"$listItem"}}}},
+    {"ShapeAdded":{"shapeId":"filter_shape_1","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"FieldAdded":{"fieldId":"field_1","shapeId":"filter_shape_1","name":"status","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_1","shapeId":"string_shape_1"}}}},
+    {"ShapeAdded":{"shapeId":"query_shape_1","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"FieldAdded":{"fieldId":"field_2","shapeId":"query_shape_1","name":"limit","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_2","shapeId":"number_shape_1"}}}},
+    {"FieldAdded":{"fieldId":"field_3","shapeId":"query_shape_1","name":"tag","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_3","shapeId":"list_shape_1"}}}},
+    {"FieldAdded":{"fieldId":"field_4","shapeId":"query_shape_1","name":"filter","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_4","shapeId":"filter_shape_1"}}}},
+    {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"search"}},
+    {"QueryParametersAdded":{"queryParametersId":"query_1","httpMethod":"GET","pathId":"path_1"}},
+    {"QueryParametersShapeSet":{"queryParametersId":"query_1","shapeDescriptor":{"shapeId":"query_shape_1","isRemoved":false}}},
+    {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"GET"}},
+  ]))
+  .expect("should be able to deserialize query parameter events as spec events");
+
+  let spec_projection = SpecProjection::from(events);
+
+  let query_parameter_diffs = |query: &str| {
+    let empty = json!({ "asJsonString": null, "asText": null, "asShapeHashBytes": null });
+    let interaction: HttpInteraction = serde_json::from_value(json!({
+      "uuid": "5",
+      "request": {
+        "host": "localhost",
+        "method": "GET",
+        "path": "/search",
+        "query": { "asJsonString": null, "asText": query, "asShapeHashBytes": null },
+        "headers": empty,
+        "body": { "contentType": null, "value": empty }
+      },
+      "response": {
+        "statusCode": 200,
+        "headers": empty,
+        "body": { "contentType": null, "value": empty }
+      },
+      "tags": []
+    }))
+    .expect("example http interaction should deserialize");
+
+    diff_interaction(
+      &spec_projection,
+      interaction,
+      &DiffInteractionConfig::default(),
+    )
+    .into_iter()
+    .filter(|result| {
+      matches!(
+        result,
+        InteractionDiffResult::UnmatchedQueryParametersShape(_)
+      )
+    })
+    .collect::<Vec<_>>()
+  };
+
+  assert!(query_parameter_diffs("limit=10&tag=a&tag=b&filter[status]=open").is_empty());
+  assert!(
+    query_parameter_diffs("limit=10&tag[]=2020&filter[status]=404").is_empty(),
+    "numeric values match strings as well"
+  );
+
+  assert_debug_snapshot!(
+    "can_yield_unmatched_query_parameter_shapes__not_a_number",
+    query_parameter_diffs("limit=ten&tag=a&tag=b&filter[status]=open")
+  );
+  assert_debug_snapshot!(
+    "can_yield_unmatched_query_parameter_shapes__nested",
+    query_parameter_diffs("limit=10&tag=a&tag=b&filter=open")
+  );
+}
+
+#[test]
+fn can_yield_unmatched_form_body_shapes() {
+  let events: Vec<SpecEvent> = serde_json::from_value(json!([
+    {"ShapeAdded":{"shapeId":"number_shape_1","baseShapeId":"$number","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"ShapeAdded":{"shapeId":"login_shape_1","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"FieldAdded":{"fieldId":"field_1","shapeId":"login_shape_1","name":"username","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_1","shapeId":"string_shape_1"}}}},
+    {"FieldAdded":{"fieldId":"field_2","shapeId":"login_shape_1","name":"password","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_2","shapeId":"string_shape_1"}}}},
+    {"ShapeAdded":{"shapeId":"upload_shape_1","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"FieldAdded":{"fieldId":"field_3","shapeId":"upload_shape_1","name":"file","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_3","shapeId":"string_shape_1"}}}},
+    {"FieldAdded":{"fieldId":"field_4","shapeId":"upload_shape_1","name":"size","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_4","shapeId":"number_shape_1"}}}},
+    {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"login"}},
+    {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"POST"}},
+    {"RequestBodySet":{"requestId":"request_1","bodyDescriptor":{"httpContentType":"application/x-www-form-urlencoded","shapeId":"login_shape_1","isRemoved":false}}},
+    {"PathComponentAdded":{"pathId":"path_2","parentPathId":"root","name":"uploads"}},
+    {"RequestAdded":{"requestId":"request_2","pathId":"path_2","httpMethod":"POST"}},
+    {"RequestBodySet":{"requestId":"request_2","bodyDescriptor":{"httpContentType":"multipart/form-data","shapeId":"upload_shape_1","isRemoved":false}}},
+  ]))
+  .expect("should be able to deserialize form body events as spec events");
+
+  let spec_projection = SpecProjection::from(events);
+
+  let form_body_diffs = |path: &str, content_type: &str, body: &str| {
+    let empty = json!({ "asJsonString": null, "asText": null, "asShapeHashBytes": null });
+    let interaction: HttpInteraction = serde_json::from_value(json!({
+      "uuid": "5",
+      "request": {
+        "host": "localhost",
+        "method": "POST",
+        "path": path,
+        "query": empty,
+        "headers": empty,
+        "body": {
+          "contentType": content_type,
+          "value": { "asJsonString": null, "asText": body, "asShapeHashBytes": null }
+        }
+      },
+      "response": {
+        "statusCode": 200,
+        "headers": empty,
+        "body": { "contentType": null, "value": empty }
+      },
+      "tags": []
+    }))
+    .expect("example http interaction should deserialize");
+
+    diff_interaction(
+      &spec_projection,
+      interaction,
+      &DiffInteractionConfig::default(),
+    )
+    .into_iter()
+    .filter(|result| {
+      matches!(
+        result,
+        InteractionDiffResult::UnmatchedRequestBodyContentType(_)
+          | InteractionDiffResult::UnmatchedRequestBodyShape(_)
+      )
+    })
+    .collect::<Vec<_>>()
+  };
+
+  let upload = |size: &str| {
+    [
+      "--XyZ",
+      "Content-Disposition: form-data; name=\"file\"; filename=\"notes.bin\"",
+      "Content-Type: application/octet-stream",
+      "",
+      "\u{0}\u{1}",
+      "--XyZ",
+      "Content-Disposition: form-data; name=\"size\"",
+      "",
+      size,
+      "--XyZ--",
+      "",
+    ]
+    .join("\r\n")
+  };
+
+  assert!(form_body_diffs(
+    "/login",
+    "application/x-www-form-urlencoded",
+    "username=optic&password=hunter2"
+  )
+  .is_empty());
+  assert!(
+    form_body_diffs(
+      "/uploads",
+      "multipart/form-data; boundary=XyZ",
+      &upload("2")
+    )
+    .is_empty(),
+    "multipart bodies match regardless of their boundary"
+  );
+
+  assert_debug_snapshot!(
+    "can_yield_unmatched_form_body_shapes__urlencoded",
+    form_body_diffs(
+      "/login",
+      "application/x-www-form-urlencoded",
+      "username=optic"
+    )
+  );
+  assert_debug_snapshot!(
+    "can_yield_unmatched_form_body_shapes__multipart",
+    form_body_diffs(
+      "/uploads",
+      "multipart/form-data; boundary=XyZ",
+      &upload("two")
+    )
+  );
+}
+
+#[test]
+fn can_yield_unmatched_xml_body_shapes() {
+  let events: Vec<SpecEvent> = serde_json::from_value(json!([
+    {"ShapeAdded":{"shapeId":"number_shape_1","baseShapeId":"$number","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"ShapeAdded":{"shapeId":"callback_shape_1","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"FieldAdded":{"fieldId":"field_1","shapeId":"callback_shape_1","name":"@id","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_1","shapeId":"string_shape_1"}}}},
+    {"FieldAdded":{"fieldId":"field_2","shapeId":"callback_shape_1","name":"p:amount","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_2","shapeId":"number_shape_1"}}}},
+    {"ShapeAdded":{"shapeId":"document_shape_1","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+    {"FieldAdded":{"fieldId":"field_3","shapeId":"document_shape_1","name":"p:payment","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_3","shapeId":"callback_shape_1"}}}},
+    {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"callbacks"}},
+    {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"POST"}},
+    {"RequestBodySet":{"requestId":"request_1","bodyDescriptor":{"httpContentType":"application/soap+xml","shapeId":"document_shape_1","isRemoved":false}}},
+  ]))
+  .expect("should be able to deserialize xml body events as spec events");
+
+  let spec_projection = SpecProjection::from(events);
+
+  let xml_body_diffs = |body: &str| {
+    let empty = json!({ "asJsonString": null, "asText": null, "asShapeHashBytes": null });
+    let interaction: HttpInteraction = serde_json::from_value(json!({
+      "uuid": "5",
+      "request": {
+        "host": "localhost",
+        "method": "POST",
+        "path": "/callbacks",
+        "query": empty,
+        "headers": empty,
+        "body": {
+          "contentType": "application/soap+xml",
+          "value": { "asJsonString": null, "asText": body, "asShapeHashBytes": null }
+        }
+      },
+      "response": {
+        "statusCode": 200,
+        "headers": empty,
+        "body": { "contentType": null, "value": empty }
+      },
+      "tags": []
+    }))
+    .expect("example http interaction should deserialize");
+
+    diff_interaction(
+      &spec_projection,
+      interaction,
+      &DiffInteractionConfig::default(),
+    )
+    .into_iter()
+    .filter(|result| matches!(result, InteractionDiffResult::UnmatchedRequestBodyShape(_)))
+    .collect::<Vec<_>>()
+  };
+
+  assert!(xml_body_diffs(
+    r#"<p:payment xmlns:p="urn:payments" id="p_1"><p:amount>12.50</p:amount></p:payment>"#
+  )
+  .is_empty());
+
+  assert_debug_snapshot!(
+    "can_yield_unmatched_xml_body_shapes__not_a_number",
+    xml_body_diffs(
+      r#"<p:payment xmlns:p="urn:payments" id="p_1"><p:amount>twelve</p:amount></p:payment>"#
+    )
+  );
+
+  // documents nested too deep for their elements to be described are diffed as text
+  let nested_body = format!("{}x{}", "<a>".repeat(20_000), "</a>".repeat(20_000));
+  assert!(!xml_body_diffs(&nested_body).is_empty());
+}
+
 #[test]
 fn can_handle_no_request_and_response_body() {
   // This is how the diff would be learnt with no request or response body
@@ -331,3 +794,157 @@ fn can_handle_no_request_and_response_body() {
   );
   assert_eq!(results.len(), 0);
 }
+
+#[test]
+fn can_yield_unmatched_headers() {
+  let events: Vec<SpecEvent> = serde_json::from_value(
+    json!([
+      {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"xyz"}},
+      {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"POST"}},
+      {"ResponseAddedByPathAndMethod":{"responseId":"response_1", "httpStatusCode":200,"pathId":"path_1","httpMethod":"POST"}},
+      {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"string_shape_2","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"optional_shape_1","baseShapeId":"$optional","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"optional_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"string_shape_2"}},"consumingParameterId":"$optionalInner"}}}},
+      {"ShapeAdded":{"shapeId":"number_shape_1","baseShapeId":"$number","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"HeaderParameterAdded":{"parameterId":"header_1","requestId":"request_1","name":"X-Request-Id"}},
+      {"HeaderParameterShapeSet":{"parameterId":"header_1","parameterDescriptor":{"shapeId":"string_shape_1","isRemoved":false}}},
+      {"HeaderParameterAdded":{"parameterId":"header_2","requestId":"request_1","name":"X-Trace-Id"}},
+      {"HeaderParameterShapeSet":{"parameterId":"header_2","parameterDescriptor":{"shapeId":"optional_shape_1","isRemoved":false}}},
+      {"ResponseHeaderParameterAdded":{"parameterId":"header_3","responseId":"response_1","name":"X-Rate-Limit"}},
+      {"HeaderParameterShapeSet":{"parameterId":"header_3","parameterDescriptor":{"shapeId":"number_shape_1","isRemoved":false}}},
+    ]),
+  ).expect("should be able to deserialize header parameter events as spec events");
+
+  let spec_projection = SpecProjection::from(events);
+
+  let interaction = HttpInteraction::from_json_str(
+    r#"{
+    "uuid": "5",
+    "request": {
+      "host": "localhost",
+      "method": "POST",
+      "path": "/xyz",
+      "query": {
+        "asJsonString": null,
+        "asText": null,
+        "asShapeHashBytes": null
+      },
+      "headers": {
+        "asJsonString": "{\"x-request-id\":\"abc\"}",
+        "asText": null,
+        "asShapeHashBytes": null
+      },
+      "body": {
+        "contentType": null,
+        "value": {
+          "asJsonString": null,
+          "asText": null,
+          "asShapeHashBytes": null
+        }
+      }
+    },
+    "response": {
+      "statusCode": 200,
+      "headers": {
+        "asJsonString": null,
+        "asText": "Content-Type: text/plain",
+        "asShapeHashBytes": null
+      },
+      "body": {
+        "contentType": null,
+        "value": {
+          "asJsonString": null,
+          "asText": null,
+          "asShapeHashBytes": null
+        }
+      }
+    },
+    "tags": []
+  }"#,
+  )
+  .expect("example http interaction should deserialize");
+
+  let results = diff_interaction(
+    &spec_projection,
+    interaction,
+    &DiffInteractionConfig::default(),
+  );
+  assert_debug_snapshot!(results);
+  assert_eq!(results.len(), 1);
+}
+
+#[test]
+fn can_match_numeric_and_boolean_headers() {
+  let events: Vec<SpecEvent> = serde_json::from_value(
+    json!([
+      {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"xyz"}},
+      {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"POST"}},
+      {"ResponseAddedByPathAndMethod":{"responseId":"response_1", "httpStatusCode":200,"pathId":"path_1","httpMethod":"POST"}},
+      {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"boolean_shape_1","baseShapeId":"$boolean","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"number_shape_1","baseShapeId":"$number","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"HeaderParameterAdded":{"parameterId":"header_1","requestId":"request_1","name":"X-Request-Id"}},
+      {"HeaderParameterShapeSet":{"parameterId":"header_1","parameterDescriptor":{"shapeId":"string_shape_1","isRemoved":false}}},
+      {"HeaderParameterAdded":{"parameterId":"header_2","requestId":"request_1","name":"X-Debug"}},
+      {"HeaderParameterShapeSet":{"parameterId":"header_2","parameterDescriptor":{"shapeId":"boolean_shape_1","isRemoved":false}}},
+      {"ResponseHeaderParameterAdded":{"parameterId":"header_3","responseId":"response_1","name":"X-Rate-Limit"}},
+      {"HeaderParameterShapeSet":{"parameterId":"header_3","parameterDescriptor":{"shapeId":"number_shape_1","isRemoved":false}}},
+    ]),
+  ).expect("should be able to deserialize header parameter events as spec events");
+
+  let spec_projection = SpecProjection::from(events);
+
+  let header_diffs = |request_id: &str, debug: &str, rate_limit: &str| {
+    let empty = json!({ "asJsonString": null, "asText": null, "asShapeHashBytes": null });
+    let request_headers = json!({ "x-request-id": request_id, "x-debug": debug });
+    let interaction: HttpInteraction = serde_json::from_value(json!({
+      "uuid": "5",
+      "request": {
+        "host": "localhost",
+        "method": "POST",
+        "path": "/xyz",
+        "query": empty,
+        "headers": {
+          "asJsonString": request_headers.to_string(),
+          "asText": null,
+          "asShapeHashBytes": null
+        },
+        "body": { "contentType": null, "value": empty }
+      },
+      "response": {
+        "statusCode": 200,
+        "headers": {
+          "asJsonString": null,
+          "asText": format!("X-Rate-Limit: {}", rate_limit),
+          "asShapeHashBytes": null
+        },
+        "body": { "contentType": null, "value": empty }
+      },
+      "tags": []
+    }))
+    .expect("example http interaction should deserialize");
+
+    diff_interaction(
+      &spec_projection,
+      interaction,
+      &DiffInteractionConfig::default(),
+    )
+  };
+
+  assert!(header_diffs("abc", "true", "100").is_empty());
+  assert!(
+    header_diffs("12345", "false", "2.5").is_empty(),
+    "numbers and booleans can be documented as text as well"
+  );
+  assert!(header_diffs("true", "false", "100").is_empty());
+
+  let results = header_diffs("abc", "yes", "many");
+  assert_eq!(results.len(), 2);
+  assert!(results
+    .iter()
+    .any(|result| matches!(result, InteractionDiffResult::UnmatchedRequestHeaders(_))));
+  assert!(results
+    .iter()
+    .any(|result| matches!(result, InteractionDiffResult::UnmatchedResponseHeaders(_))));
+}
@@ -6,9 +6,9 @@ use std::path::Path;
 use tokio::fs::read_to_string;
 
 use optic_engine::{
-  analyze_documented_bodies, diff_interaction, Aggregate, DiffInteractionConfig, HttpInteraction,
-  LearnedShapeDiffAffordancesProjection, SpecCommand, SpecEvent, SpecIdGenerator, SpecProjection,
-  TaggedInput,
+  analyze_documented_bodies, diff_interaction, Aggregate, AnalyzeDocumentedBodiesConfig,
+  DiffInteractionConfig, HttpInteraction, LearnedShapeDiffAffordancesProjection, SpecCommand,
+  SpecEvent, SpecIdGenerator, SpecProjection, TaggedInput,
 };
 
 #[tokio::main]
@@ -28,7 +28,12 @@ async fn query_param_required_but_missing() {
   let mut learned_shape_diff_affordances =
     LearnedShapeDiffAffordancesProjection::from(diff_results);
 
-  let results = analyze_documented_bodies(&spec, interaction).filter(|result| {
+  let results = analyze_documented_bodies(
+    &spec,
+    interaction,
+    &AnalyzeDocumentedBodiesConfig::default(),
+  )
+  .filter(|result| {
     matches!(
       result.body_location,
       optic_engine::BodyAnalysisLocation::MatchedQueryParameters { .. }
@@ -93,7 +98,12 @@ async fn query_param_new_and_optional() {
 
   for interaction in capture.session.samples {
     let interaction_pointer = interaction.uuid.clone();
-    let results = analyze_documented_bodies(&spec, interaction).filter(|result| {
+    let results = analyze_documented_bodies(
+      &spec,
+      interaction,
+      &AnalyzeDocumentedBodiesConfig::default(),
+    )
+    .filter(|result| {
       matches!(
         result.body_location,
         optic_engine::BodyAnalysisLocation::MatchedQueryParameters { .. }
@@ -6,8 +6,9 @@ use std::path::Path;
 use tokio::fs::read_to_string;
 
 use optic_engine::{
-  analyze_documented_bodies, diff_interaction, Aggregate, DiffInteractionConfig, HttpInteraction,
-  LearnedShapeDiffAffordancesProjection, SpecEvent, SpecProjection, TaggedInput,
+  analyze_documented_bodies, diff_interaction, Aggregate, AnalyzeDocumentedBodiesConfig,
+  DiffInteractionConfig, HttpInteraction, LearnedShapeDiffAffordancesProjection, SpecEvent,
+  SpecProjection, TaggedInput,
 };
 
 #[tokio::main]
@@ -28,10 +29,14 @@ async fn a_known_field_is_missing() {
   let mut learned_shape_diff_affordances =
     LearnedShapeDiffAffordancesProjection::from(diff_results);
 
-  let analysis = analyze_documented_bodies(&spec, interaction)
-    .collect::<Vec<_>>()
-    .pop()
-    .unwrap();
+  let analysis = analyze_documented_bodies(
+    &spec,
+    interaction,
+    &AnalyzeDocumentedBodiesConfig::default(),
+  )
+  .collect::<Vec<_>>()
+  .pop()
+  .unwrap();
 
   let tagged_analysis = TaggedInput(analysis, interaction_pointers);
   learned_shape_diff_affordances.apply(tagged_analysis);
@@ -60,10 +65,14 @@ async fn deeply_nested_fields_inside_of_arrays() {
   let mut learned_shape_diff_affordances =
     LearnedShapeDiffAffordancesProjection::from(diff_results);
 
-  let analysis = analyze_documented_bodies(&spec, interaction)
-    .collect::<Vec<_>>()
-    .pop()
-    .unwrap();
+  let analysis = analyze_documented_bodies(
+    &spec,
+    interaction,
+    &AnalyzeDocumentedBodiesConfig::default(),
+  )
+  .collect::<Vec<_>>()
+  .pop()
+  .unwrap();
 
   let tagged_analysis = TaggedInput(analysis, interaction_pointers);
   learned_shape_diff_affordances.apply(tagged_analysis);
@@ -106,10 +115,14 @@ async fn optional_single_string_or_list_of_strings() {
   let mut learned_shape_diff_affordances =
     LearnedShapeDiffAffordancesProjection::from(diff_results);
 
-  let analysis = analyze_documented_bodies(&spec, interaction)
-    .collect::<Vec<_>>()
-    .pop()
-    .unwrap();
+  let analysis = analyze_documented_bodies(
+    &spec,
+    interaction,
+    &AnalyzeDocumentedBodiesConfig::default(),
+  )
+  .collect::<Vec<_>>()
+  .pop()
+  .unwrap();
 
   let tagged_analysis = TaggedInput(analysis, interaction_pointers);
   learned_shape_diff_affordances.apply(tagged_analysis);
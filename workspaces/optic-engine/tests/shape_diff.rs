@@ -548,3 +548,177 @@ fn can_handle_base_shape_changes() {
   assert_eq!(results.len(), 0);
   assert_debug_snapshot!("can_handle_base_shape_changes__fingerprints", fingerprints);
 }
+
+#[test]
+fn can_yield_unmatched_shape_for_mismatched_map_values() {
+  let events: Vec<SpecEvent> = serde_json::from_value(
+    json!([
+      {"ShapeAdded":{"shapeId":"number_shape_1","baseShapeId":"$number","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"map_shape_1","baseShapeId":"$map","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"map_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"number_shape_1"}},"consumingParameterId":"$mapValue"}}}}
+    ]),
+  )
+  .expect("should be able to deserialize shape added events as spec events");
+  let shape_projection = ShapeProjection::from(events);
+
+  let shape_id = String::from("map_shape_1");
+
+  let matching_body = json!({ "user-1": 3, "user-2": 9 });
+  let results = diff_shape(
+    &shape_projection,
+    Some(BodyDescriptor::from(matching_body)),
+    &shape_id,
+  );
+  assert_eq!(
+    results.len(),
+    0,
+    "map with values matching the value shape should not yield any results"
+  );
+
+  let mismatched_body = json!({ "user-1": 3, "user-2": "nine" });
+  let results = diff_shape(
+    &shape_projection,
+    Some(BodyDescriptor::from(mismatched_body)),
+    &shape_id,
+  );
+  assert_eq!(results.len(), 1);
+  assert_debug_snapshot!(
+    "can_yield_unmatched_shape_for_mismatched_map_values__results",
+    results
+  );
+}
+
+#[test]
+fn can_diff_references_to_shared_shapes() {
+  let events: Vec<SpecEvent> = serde_json::from_value(
+    json!([
+      {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"user_shape","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":"User"}},
+      {"FieldAdded":{"fieldId":"field_1","shapeId":"user_shape","name":"name","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_1","shapeId":"string_shape_1"}}}},
+      {"ShapeAdded":{"shapeId":"reference_shape_1","baseShapeId":"$reference","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"reference_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"user_shape"}},"consumingParameterId":"$referenceInner"}}}},
+      {"ShapeAdded":{"shapeId":"object_shape_1","baseShapeId":"$object","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"FieldAdded":{"fieldId":"field_2","shapeId":"object_shape_1","name":"author","shapeDescriptor":{"FieldShapeFromShape":{"fieldId":"field_2","shapeId":"reference_shape_1"}}}},
+    ]),
+  )
+  .expect("should be able to deserialize shape added events as spec events");
+  let shape_projection = ShapeProjection::from(events);
+
+  let shape_id = String::from("object_shape_1");
+
+  let matching_body = json!({ "author": { "name": "Ada" } });
+  let results = diff_shape(
+    &shape_projection,
+    Some(BodyDescriptor::from(matching_body)),
+    &shape_id,
+  );
+  assert_eq!(
+    results.len(),
+    0,
+    "body matching the referenced shape should not yield any results"
+  );
+
+  let mismatched_body = json!({ "author": { "name": 3 } });
+  let results = diff_shape(
+    &shape_projection,
+    Some(BodyDescriptor::from(mismatched_body)),
+    &shape_id,
+  );
+  assert_eq!(results.len(), 1);
+  assert_debug_snapshot!("can_diff_references_to_shared_shapes__results", results);
+}
+
+#[test]
+fn can_diff_cyclic_references() {
+  let events: Vec<SpecEvent> = serde_json::from_value(
+    json!([
+      {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"reference_shape_1","baseShapeId":"$reference","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"reference_shape_2","baseShapeId":"$reference","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"reference_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"reference_shape_2"}},"consumingParameterId":"$referenceInner"}}}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"reference_shape_2","providerDescriptor":{"ShapeProvider":{"shapeId":"reference_shape_1"}},"consumingParameterId":"$referenceInner"}}}},
+    ]),
+  )
+  .expect("should be able to deserialize shape added events as spec events");
+  let shape_projection = ShapeProjection::from(events);
+
+  let shape_id = String::from("reference_shape_1");
+
+  let body = json!("anything");
+  let results = diff_shape(
+    &shape_projection,
+    Some(BodyDescriptor::from(body)),
+    &shape_id,
+  );
+  assert_debug_snapshot!("can_diff_cyclic_references__results", results);
+}
+
+#[test]
+fn can_diff_cyclic_references_through_nullables() {
+  let events: Vec<SpecEvent> = serde_json::from_value(
+    json!([
+      {"ShapeAdded":{"shapeId":"reference_shape_1","baseShapeId":"$reference","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"nullable_shape_1","baseShapeId":"$nullable","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"reference_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"nullable_shape_1"}},"consumingParameterId":"$referenceInner"}}}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"nullable_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"reference_shape_1"}},"consumingParameterId":"$nullableInner"}}}},
+    ]),
+  )
+  .expect("should be able to deserialize shape added events as spec events");
+  let shape_projection = ShapeProjection::from(events);
+
+  let shape_id = String::from("reference_shape_1");
+
+  let matching_results = diff_shape(
+    &shape_projection,
+    Some(BodyDescriptor::from(json!(null))),
+    &shape_id,
+  );
+  assert!(matching_results.is_empty());
+
+  let results = diff_shape(
+    &shape_projection,
+    Some(BodyDescriptor::from(json!("anything"))),
+    &shape_id,
+  );
+  assert_debug_snapshot!(
+    "can_diff_cyclic_references_through_nullables__results",
+    results
+  );
+}
+
+#[test]
+fn can_diff_cyclic_references_through_one_ofs() {
+  let events: Vec<SpecEvent> = serde_json::from_value(
+    json!([
+      {"ShapeAdded":{"shapeId":"string_shape_1","baseShapeId":"$string","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"reference_shape_1","baseShapeId":"$reference","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeAdded":{"shapeId":"oneof_shape_1","baseShapeId":"$oneOf","parameters":{"DynamicParameterList":{"shapeParameterIds":[]}},"name":""}},
+      {"ShapeParameterAdded":{"shapeParameterId":"oneof_parameter_1","shapeId":"oneof_shape_1","name":"","shapeDescriptor":{"ProviderInShape":{"shapeId":"oneof_shape_1","providerDescriptor":{"NoProvider":{}},"consumingParameterId":"oneof_parameter_1"}}}},
+      {"ShapeParameterAdded":{"shapeParameterId":"oneof_parameter_2","shapeId":"oneof_shape_1","name":"","shapeDescriptor":{"ProviderInShape":{"shapeId":"oneof_shape_1","providerDescriptor":{"NoProvider":{}},"consumingParameterId":"oneof_parameter_2"}}}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"oneof_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"reference_shape_1"}},"consumingParameterId":"oneof_parameter_1"}}}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"oneof_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"string_shape_1"}},"consumingParameterId":"oneof_parameter_2"}}}},
+      {"ShapeParameterShapeSet":{"shapeDescriptor":{"ProviderInShape":{"shapeId":"reference_shape_1","providerDescriptor":{"ShapeProvider":{"shapeId":"oneof_shape_1"}},"consumingParameterId":"$referenceInner"}}}},
+    ]),
+  )
+  .expect("should be able to deserialize shape added events as spec events");
+  let shape_projection = ShapeProjection::from(events);
+
+  let shape_id = String::from("reference_shape_1");
+
+  let matching_results = diff_shape(
+    &shape_projection,
+    Some(BodyDescriptor::from(json!("anything"))),
+    &shape_id,
+  );
+  assert!(matching_results.is_empty());
+
+  let results = diff_shape(
+    &shape_projection,
+    Some(BodyDescriptor::from(json!(12))),
+    &shape_id,
+  );
+  assert_debug_snapshot!(
+    "can_diff_cyclic_references_through_one_ofs__results",
+    results
+  );
+}
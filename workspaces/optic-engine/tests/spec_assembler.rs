@@ -82,3 +82,101 @@ pub fn can_assemble_spec_events_from_serialized_chunks() {
     assembled_events
   );
 }
+
+#[test]
+pub fn can_assemble_spec_events_from_chunks_committed_on_separate_branches() {
+  let raw_chunks = vec![
+    (
+      String::from("specification.json"),
+      true,
+      json!([
+        {"PathComponentAdded": {"pathId": "path_1","parentPathId": "root","name": "todos"}},
+        {"ShapeAdded": { "shapeId": "object_shape_1", "baseShapeId": "$object", "parameters": { "DynamicParameterList": { "shapeParameterIds": [] } }, "name": "" }},
+        {"ShapeAdded": {"shapeId": "string_shape_1","baseShapeId": "$string","parameters": {"DynamicParameterList": {"shapeParameterIds": []}},"name": ""}},
+        {"FieldAdded": {"fieldId": "field_1","shapeId": "object_shape_1","name": "title","shapeDescriptor": {"FieldShapeFromShape": {"fieldId": "field_1","shapeId": "string_shape_1"}}}},
+      ]),
+    ),
+    (
+      String::from("0002.json"),
+      false,
+      // committed on a second branch, a bit later than the first
+      json!([
+        {"BatchCommitStarted": {"batchId": "batch-b", "parentId": "root", "commitMessage": "Add GET /todos/{id}", "eventContext": { "clientId": "anonymous", "clientSessionId": "session-b", "clientCommandBatchId": "batch-b", "createdAt": "2021-03-03T12:00:00.000Z" } }},
+        {"PathParameterAdded": {"pathId": "path_3","parentPathId": "path_1","name": "todoId"}},
+        {"RequestAdded": { "requestId": "request_2","pathId": "path_3","httpMethod": "GET" }},
+        {"FieldRenamed": {"fieldId": "field_1","name": "name"}},
+        {"FieldAdded": {"fieldId": "field_3","shapeId": "object_shape_1","name": "isDone","shapeDescriptor": {"FieldShapeFromShape": {"fieldId": "field_3","shapeId": "string_shape_1"}}}},
+        {"BatchCommitEnded": { "batchId": "batch-b" }}
+      ]),
+    ),
+    (
+      String::from("0001.json"),
+      false,
+      json!([
+        {"BatchCommitStarted": {"batchId": "batch-a", "parentId": "root", "commitMessage": "Add GET /todos/{id}", "eventContext": { "clientId": "anonymous", "clientSessionId": "session-a", "clientCommandBatchId": "batch-a", "createdAt": "2021-03-03T11:00:00.000Z" } }},
+        {"PathParameterAdded": {"pathId": "path_2","parentPathId": "path_1","name": "id"}},
+        {"RequestAdded": { "requestId": "request_1","pathId": "path_2","httpMethod": "GET" }},
+        {"FieldRenamed": {"fieldId": "field_1","name": "summary"}},
+        {"FieldAdded": {"fieldId": "field_2","shapeId": "object_shape_1","name": "isDone","shapeDescriptor": {"FieldShapeFromShape": {"fieldId": "field_2","shapeId": "string_shape_1"}}}},
+        {"BatchCommitEnded": { "batchId": "batch-a" }}
+      ]),
+    ),
+    (
+      String::from("0003.json"),
+      false,
+      // committed on the first branch, after the first commit
+      json!([
+        {"BatchCommitStarted": {"batchId": "batch-a2", "parentId": "batch-a", "commitMessage": "Add POST /todos" }},
+        {"RequestAdded": { "requestId": "request_3","pathId": "path_1","httpMethod": "POST" }},
+        {"BatchCommitEnded": { "batchId": "batch-a2" }}
+      ]),
+    ),
+    (
+      String::from("0004.json"),
+      false,
+      // committed after merging both branches
+      json!([
+        {"BatchCommitStarted": {"batchId": "batch-c", "parentId": "batch-b", "commitMessage": "Add PUT /todos" }},
+        {"RequestAdded": { "requestId": "request_4","pathId": "path_1","httpMethod": "PUT" }},
+        {"BatchCommitEnded": { "batchId": "batch-c" }}
+      ]),
+    ),
+  ];
+
+  let spec_chunk_events: Vec<SpecChunkEvent> = raw_chunks
+    .clone()
+    .into_iter()
+    .map(|(file_name, is_root, events_json)| {
+      let events: Vec<SpecEvent> =
+        serde_json::from_value(events_json).expect("example events should be valid spec events");
+
+      SpecChunkEvent::from((file_name, is_root, events))
+    })
+    .collect();
+
+  let assembler_projection = SpecAssemblerProjection::from(spec_chunk_events);
+
+  let (assembled_events, conflicts) = assembler_projection
+    .into_events_with_conflicts()
+    .expect("example chunks should assemble");
+
+  let expected_events = vec![
+    raw_chunks[0].clone(),
+    raw_chunks[2].clone(),
+    raw_chunks[3].clone(),
+    raw_chunks[1].clone(),
+    raw_chunks[4].clone(),
+  ]
+  .into_iter()
+  .flat_map(|(_filename, _is_root, events)| {
+    serde_json::from_value::<Vec<SpecEvent>>(events).unwrap()
+  })
+  .collect::<Vec<_>>();
+
+  assert_eq!(assembled_events, expected_events);
+
+  assert_debug_snapshot!(
+    "can_assemble_spec_events_from_chunks_committed_on_separate_branches__conflicts",
+    conflicts
+  );
+}
@@ -6,9 +6,10 @@ use tokio::fs::read_to_string;
 
 use optic_engine::{
   analyze_undocumented_bodies, Aggregate, AnalyzeUndocumentedBodiesConfig, EndpointCommand,
-  HttpInteraction, LearnedUndocumentedBodiesProjection, SpecCommand, SpecEvent, SpecIdGenerator,
-  SpecProjection,
+  HttpInteraction, LearnedUndocumentedBodiesProjection, ShapeCommand, SpecCommand, SpecEvent,
+  SpecIdGenerator, SpecProjection,
 };
+use serde_json::json;
 
 #[tokio::main]
 #[test]
@@ -127,6 +128,73 @@ async fn requests_with_and_without_query_params() {
   // dbg!(Dot::with_config(&_updated_spec.shape().graph, &[]));
 }
 
+#[test]
+fn leaves_ignored_body_trails_out_of_learned_bodies() {
+  let events: Vec<SpecEvent> = serde_json::from_value(json!([
+    {"PathComponentAdded":{"pathId":"path_1","parentPathId":"root","name":"todos"}},
+    {"RequestAdded":{"requestId":"request_1","pathId":"path_1","httpMethod":"GET"}},
+    {"ResponseAddedByPathAndMethod":{"responseId":"response_1","httpStatusCode":200,"pathId":"path_1","httpMethod":"GET"}}
+  ]))
+  .expect("should be able to deserialize spec events");
+  let spec = SpecProjection::from(events);
+
+  let empty = json!({ "asJsonString": null, "asText": null, "asShapeHashBytes": null });
+  let interaction: HttpInteraction = serde_json::from_value(json!({
+    "uuid": "1",
+    "request": {
+      "host": "localhost",
+      "method": "GET",
+      "path": "/todos",
+      "query": empty,
+      "headers": empty,
+      "body": { "contentType": null, "value": empty }
+    },
+    "response": {
+      "statusCode": 200,
+      "headers": empty,
+      "body": {
+        "contentType": "application/json",
+        "value": {
+          "asJsonString": "{\"title\":\"x\",\"debug\":{\"sql\":\"SELECT 1\"}}",
+          "asText": null,
+          "asShapeHashBytes": null
+        }
+      }
+    },
+    "tags": []
+  }))
+  .expect("example http interaction should deserialize");
+
+  let learner_config: AnalyzeUndocumentedBodiesConfig = serde_json::from_value(json!({
+    "ignore": { "bodyTrails": ["$.debug"] }
+  }))
+  .expect("learner config should deserialize");
+
+  let mut learned_undocumented_bodies = LearnedUndocumentedBodiesProjection::default();
+  for result in analyze_undocumented_bodies(&spec, interaction, &learner_config) {
+    learned_undocumented_bodies.apply(result)
+  }
+
+  let mut id_generator = SequentialIdGenerator { next_id: 1093 };
+  let commands = learned_undocumented_bodies
+    .into_endpoint_bodies(&mut id_generator)
+    .flat_map(|endpoint_bodies| endpoint_bodies.into_commands())
+    .collect::<Vec<_>>();
+
+  let field_names = commands
+    .iter()
+    .filter_map(|command| match command {
+      SpecCommand::ShapeCommand(ShapeCommand::AddField(add_field)) => Some(add_field.name.as_str()),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  assert!(field_names.contains(&"title"));
+  assert!(!field_names.contains(&"debug"));
+  assert!(!field_names.contains(&"sql"));
+
+  assert_valid_commands(spec, commands);
+}
+
 #[derive(Deserialize, Debug)]
 struct DebugCapture {
   events: Vec<SpecEvent>,